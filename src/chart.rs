@@ -1,9 +1,174 @@
 use super::ffi::clay;
-use super::ui::consts::{DROP_DOWN_HISTOGRAM, DROP_DOWN_HIST_LINE, DROP_DOWN_LINE};
+use super::montecarlo::MonteCarlo;
+use super::stats::{self, SummaryStats};
+use super::ui::consts::{
+    DROP_DOWN_BOX, DROP_DOWN_HEATMAP, DROP_DOWN_HISTOGRAM, DROP_DOWN_HIST_LINE, DROP_DOWN_LINE, DROP_DOWN_PATHS,
+    DROP_DOWN_PP, DROP_DOWN_QQ, DROP_DOWN_SCATTER, DROP_DOWN_VIOLIN,
+};
+use super::ui::Stream;
 
 #[derive(Debug, Default, Clone)]
 pub struct ChartDataHistogram {
     pub data: Vec<u32>,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ChartDataHistogram {
+    pub fn bin_width(&self) -> f32 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
+        (self.max - self.min) / (self.data.len() as f32)
+    }
+
+    pub fn bin_at(&self, x: f32) -> Option<usize> {
+        let width = self.bin_width();
+        if width <= 0.0 || x < self.min || x > self.max {
+            return None;
+        }
+        Some((((x - self.min) / width) as usize).min(self.data.len() - 1))
+    }
+
+    // Factor converting a bin count into a density, so the histogram integrates to one
+    pub fn density_scale(&self) -> f32 {
+        let total: u32 = self.data.iter().sum();
+        1.0 / ((total as f32) * self.bin_width())
+    }
+}
+
+// Histogram filled one sample at a time, the bin edges are fixed up front instead of following the data
+#[derive(Debug, Default, Clone)]
+pub struct ChartDataStreamHistogram {
+    pub data: Vec<u32>,
+    pub min: f32,
+    pub max: f32,
+    pub below: u64, // Samples falling outside of [min, max]
+    pub above: u64,
+}
+
+impl ChartDataStreamHistogram {
+    pub fn reset(&mut self, min: f32, max: f32, bins: usize) {
+        self.data.clear();
+        self.data.resize(bins, 0);
+        self.min = min;
+        self.max = max;
+        self.below = 0;
+        self.above = 0;
+    }
+
+    pub fn push(&mut self, x: f32) {
+        if self.data.is_empty() || x < self.min || x.is_nan() {
+            self.below += 1;
+            return;
+        }
+        if x > self.max {
+            self.above += 1;
+            return;
+        }
+
+        let width = (self.max - self.min) / (self.data.len() as f32);
+        let idx = (((x - self.min) / width) as usize).min(self.data.len() - 1);
+        self.data[idx] += 1;
+    }
+
+    pub fn extend(&mut self, values: &[f32]) {
+        for x in values.iter() {
+            self.push(*x);
+        }
+    }
+
+    // Interpolated within the bin holding the quantile, NaN when it falls outside of [min, max]
+    pub fn quantile(&self, p: f32) -> f32 {
+        let inside: u64 = self.data.iter().map(|c| u64::from(*c)).sum();
+        let total = self.below + inside + self.above;
+        let target = f64::from(p) * (total as f64);
+        if inside == 0 || target < self.below as f64 || target > (self.below + inside) as f64 {
+            return f32::NAN;
+        }
+
+        let width = (self.max - self.min) / (self.data.len() as f32);
+        let mut seen = self.below as f64;
+        for (idx, count) in self.data.iter().enumerate() {
+            let next = seen + f64::from(*count);
+            if *count > 0 && target <= next {
+                let frac = ((target - seen) / f64::from(*count)) as f32;
+                return self.min + width * (idx as f32 + frac);
+            }
+            seen = next;
+        }
+        self.max
+    }
+
+    // Copies the counts into a regular histogram, reusing its buffer
+    pub fn fill(&self, hist: &mut ChartDataHistogram) {
+        hist.data.clone_from(&self.data);
+        hist.min = self.min;
+        hist.max = self.max;
+    }
+}
+
+// Counts of pairs on a regular grid over the range of the data, stored row by row from the lowest y
+#[derive(Debug, Default, Clone)]
+pub struct ChartDataHistogram2D {
+    pub data: Vec<u32>,
+    pub bins: usize, // On each axis
+    pub xmin: f32,
+    pub xmax: f32,
+    pub ymin: f32,
+    pub ymax: f32,
+}
+
+impl ChartDataHistogram2D {
+    pub fn fill(&mut self, x: &[f32], y: &[f32], bins: usize) {
+        self.data.clear();
+        self.bins = bins;
+        if x.is_empty() || bins == 0 {
+            return;
+        }
+
+        self.xmin = x.iter().copied().fold(f32::MAX, f32::min);
+        self.xmax = x.iter().copied().fold(f32::MIN, f32::max);
+        self.ymin = y.iter().copied().fold(f32::MAX, f32::min);
+        self.ymax = y.iter().copied().fold(f32::MIN, f32::max);
+        self.data.resize(bins * bins, 0);
+        for (xv, yv) in x.iter().zip(y.iter()) {
+            if let Some((col, row)) = self.cell_at(*xv, *yv) {
+                self.data[row * bins + col] += 1;
+            }
+        }
+    }
+
+    pub fn cell_width(&self) -> f32 {
+        (self.xmax - self.xmin) / (self.bins.max(1) as f32)
+    }
+
+    pub fn cell_height(&self) -> f32 {
+        (self.ymax - self.ymin) / (self.bins.max(1) as f32)
+    }
+
+    // Column and row of the cell holding (x, y)
+    pub fn cell_at(&self, x: f32, y: f32) -> Option<(usize, usize)> {
+        let (width, height) = (self.cell_width(), self.cell_height());
+        if self.bins == 0 || width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        if x < self.xmin || x > self.xmax || y < self.ymin || y > self.ymax {
+            return None;
+        }
+
+        let col = (((x - self.xmin) / width) as usize).min(self.bins - 1);
+        let row = (((y - self.ymin) / height) as usize).min(self.bins - 1);
+        Some((col, row))
+    }
+
+    pub fn count(&self, col: usize, row: usize) -> u32 {
+        self.data.get(row * self.bins + col).copied().unwrap_or(0)
+    }
+
+    pub fn max_count(&self) -> u32 {
+        self.data.iter().copied().max().unwrap_or(0)
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -22,17 +187,139 @@ impl ChartDataLine {
         self.x.clear();
         self.y.clear();
     }
+
+    // Linear interpolation of `y`, the `x` values must be sorted
+    pub fn interpolate(&self, x: f32) -> Option<f32> {
+        let idx = self.x.partition_point(|val| *val < x);
+        if idx == 0 || idx >= self.x.len() {
+            return None;
+        }
+
+        let (x0, x1) = (self.x[idx - 1], self.x[idx]);
+        let (y0, y1) = (self.y[idx - 1], self.y[idx]);
+        if (x1 - x0).abs() < crate::math::EPS {
+            return Some(y0);
+        }
+        Some(y0 + (x - x0) * (y1 - y0) / (x1 - x0))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChartDataBox {
+    pub q1: f32,
+    pub median: f32,
+    pub q3: f32,
+    pub lower: f32,
+    pub upper: f32,
+    pub outliers: Vec<f32>,
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+impl Default for ChartDataBox {
+    fn default() -> Self {
+        Self {
+            q1: f32::NAN,
+            median: f32::NAN,
+            q3: f32::NAN,
+            lower: f32::NAN,
+            upper: f32::NAN,
+            outliers: Vec::new(),
+        }
+    }
+}
+
+impl ChartDataBox {
+    pub fn update(&mut self, sorted: &[f32], summary: &SummaryStats) {
+        self.q1 = summary.q1;
+        self.median = summary.median;
+        self.q3 = summary.q3;
+        let (lower, upper) = stats::tukey_whiskers(sorted, summary.q1, summary.q3);
+        self.lower = lower;
+        self.upper = upper;
+
+        self.outliers.clear();
+        self.outliers
+            .extend(sorted.iter().filter(|x| **x < lower || **x > upper));
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum ChartKind {
     #[default]
     Hist,
     Line,
     HistLine,
+    QQ,
+    PP,
+    Box,
+    Violin,
+    Paths,
+    Scatter,
+    Heatmap,
 }
 
 impl ChartKind {
+    pub const ALL: [Self; 10] = [
+        Self::Hist,
+        Self::Line,
+        Self::HistLine,
+        Self::QQ,
+        Self::PP,
+        Self::Box,
+        Self::Violin,
+        Self::Paths,
+        Self::Scatter,
+        Self::Heatmap,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hist => DROP_DOWN_HISTOGRAM,
+            Self::Line => DROP_DOWN_LINE,
+            Self::HistLine => DROP_DOWN_HIST_LINE,
+            Self::QQ => DROP_DOWN_QQ,
+            Self::PP => DROP_DOWN_PP,
+            Self::Box => DROP_DOWN_BOX,
+            Self::Violin => DROP_DOWN_VIOLIN,
+            Self::Paths => DROP_DOWN_PATHS,
+            Self::Scatter => DROP_DOWN_SCATTER,
+            Self::Heatmap => DROP_DOWN_HEATMAP,
+        }
+    }
+
+    // Name in the configuration and session files
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Hist => "histogram",
+            Self::Line => "line",
+            Self::HistLine => "hist_line",
+            Self::QQ => "qq",
+            Self::PP => "pp",
+            Self::Box => "box",
+            Self::Violin => "violin",
+            Self::Paths => "paths",
+            Self::Scatter => "scatter",
+            Self::Heatmap => "heatmap",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.key() == key)
+    }
+
+    // Whether the chart paints areas (`fill`) and/or lines and points (`colour`)
+    pub fn uses_fill(&self) -> bool {
+        matches!(self, Self::Hist | Self::HistLine | Self::Box | Self::Violin | Self::Heatmap)
+    }
+
+    pub fn uses_colour(&self) -> bool {
+        matches!(self, Self::Line | Self::HistLine | Self::QQ | Self::PP | Self::Paths | Self::Scatter)
+    }
+
+    // Charts of pairs, which leave the univariate sample aside
+    pub fn is_bivariate(&self) -> bool {
+        matches!(self, Self::Scatter | Self::Heatmap)
+    }
+
     pub fn from_str(s: &str) -> Self {
         if s == DROP_DOWN_HISTOGRAM {
             Self::Hist
@@ -40,6 +327,20 @@ impl ChartKind {
             Self::Line
         } else if s == DROP_DOWN_HIST_LINE {
             Self::HistLine
+        } else if s == DROP_DOWN_QQ {
+            Self::QQ
+        } else if s == DROP_DOWN_PP {
+            Self::PP
+        } else if s == DROP_DOWN_BOX {
+            Self::Box
+        } else if s == DROP_DOWN_VIOLIN {
+            Self::Violin
+        } else if s == DROP_DOWN_PATHS {
+            Self::Paths
+        } else if s == DROP_DOWN_SCATTER {
+            Self::Scatter
+        } else if s == DROP_DOWN_HEATMAP {
+            Self::Heatmap
         } else {
             eprintln!("ERROR: Unable to match name '{s}' to a valid ChartKind");
             Self::default()
@@ -47,16 +348,47 @@ impl ChartKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct ChartData {
     pub hist: ChartDataHistogram,
+    pub previous: ChartDataHistogram, // What `hist` was before the last regeneration, the start of a morph
     pub line: ChartDataLine,
     pub kind: ChartKind,
+    pub stats: SummaryStats,
+    pub theory: SummaryStats,
+    pub sample: Vec<f32>,
+    pub sorted: Vec<f32>,
+    pub kde: ChartDataLine,
+    pub kde_bandwidth: f32,
+    pub qq: ChartDataLine,
+    pub pp: ChartDataLine,
+    pub boxplot: ChartDataBox,
+    pub violin: ChartDataLine,
+    pub paths: Vec<ChartDataLine>, // Sample paths of `State::process` driven by this data's generator
+    pub finals: ChartDataHistogram,
+    pub scatter: ChartDataLine, // Pairs drawn from the bivariate Normal of `State::correlation`
+    pub heatmap: ChartDataHistogram2D,
+    pub marginal_x: ChartDataHistogram,
+    pub marginal_y: ChartDataHistogram,
+}
+
+impl ChartData {
+    // Nothing left to morph from, used when the same data is shown again
+    pub fn settle(&mut self) {
+        self.previous.clone_from(&self.hist);
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum CustomElementKind {
     Chart(*const ChartData),
+    Cell(usize),              // Small multiples, 0 is the active series and i > 0 is `State::series[i - 1]`
+    Finals(*const ChartData), // Where the sample paths end, next to the paths
+    MonteCarloPi(*const MonteCarlo),
+    MonteCarloIntegral(*const MonteCarlo),
+    MonteCarloBuffon(*const MonteCarlo),
+    MonteCarloEstimate(*const MonteCarlo), // Running estimate and its interval against the number of trials
+    StreamMean(*const Stream),             // Running mean of the stream against the number of samples
 }
 
 #[derive(Debug, Clone)]
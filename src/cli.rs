@@ -0,0 +1,370 @@
+use crate::chart::ChartKind;
+use crate::error::AppError;
+use crate::ui::session::{Session, MAX_WINDOW, MIN_WINDOW};
+use crate::ui::{consts, RandomGenerator, State};
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const DEFAULT_WIDTH: i32 = 1080;
+pub const DEFAULT_HEIGHT: i32 = 720;
+pub const DEFAULT_FPS: i32 = 60;
+const MAX_FPS: i32 = 1000;
+const MAX_DURATION: u64 = 10_000; // Milliseconds
+const MAX_SAMPLES: usize = 10_000_000;
+const MAX_BINS: u16 = 1000;
+
+pub const HELP: &str = "\
+Usage: claydash [OPTIONS]
+
+Window:
+  --geometry WxH[+X+Y]    Window size, and position on the screen (default 1080x720)
+  --fullscreen            Start in fullscreen mode
+  --fps N                 Target frames per second, 1 to 1000 (default 60)
+  --duration MS           Length of the chart animations in milliseconds, 0 to 10000 (default 750)
+
+Simulation:
+  --distribution NAME     uniform, normal or gamma
+  --params A,B            Parameters of the distribution: a,b for uniform (a < b), mu,sigma for normal
+                          (sigma > 0), alpha,beta for gamma (alpha a whole number from 1 to 255, beta > 0)
+  --chart KIND            histogram, line, hist_line, qq, pp, box, violin, paths, scatter or heatmap
+  --seed N                Seed of the first sample, a whole number
+  --samples N             Size of the simulated samples, 1 to 10000000 (default 150000)
+  --bins N                Number of histogram bars, 1 to 1000 (default 50)
+
+Files:
+  --theme PATH            Theme file, instead of $CLAYDASH_THEME or theme.toml in the configuration directory
+  --font PATH             TTF or OTF file for the labels, over the theme
+  --data PATH             Sample to show instead of a simulated one: numbers separated by spaces, commas or
+                          semicolons, `#` starts a comment and a non-numeric first line is skipped as a header
+  --no-restore            Do not restore the last session
+
+  -h, --help              Print this help
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Geometry {
+    pub width: i32,
+    pub height: i32,
+    pub position: Option<(i32, i32)>, // Of the top left corner
+}
+
+// Launch options, `None` keeps the value of the restored session or the built-in default
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub geometry: Option<Geometry>,
+    pub fullscreen: bool,
+    pub fps: i32,
+    pub duration: Option<Duration>,
+    pub distribution: Option<&'static str>, // One of `consts::DISTRIBUTIONS`
+    pub params: Option<[f32; 2]>,
+    pub chart: Option<ChartKind>,
+    pub seed: Option<u64>,
+    pub samples: Option<usize>,
+    pub bins: Option<u16>,
+    pub theme: Option<PathBuf>,
+    pub font: Option<PathBuf>,
+    pub data: Option<PathBuf>,
+    pub restore: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            geometry: None,
+            fullscreen: false,
+            fps: DEFAULT_FPS,
+            duration: None,
+            distribution: None,
+            params: None,
+            chart: None,
+            seed: None,
+            samples: None,
+            bins: None,
+            theme: None,
+            font: None,
+            data: None,
+            restore: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run(Options),
+    Help,
+}
+
+fn invalid(message: String) -> AppError {
+    AppError::InvalidArgument(message)
+}
+
+fn number<T: std::str::FromStr + PartialOrd + std::fmt::Display>(
+    option: &str,
+    value: &str,
+    min: T,
+    max: T,
+) -> Result<T, AppError> {
+    match value.parse::<T>() {
+        Ok(number) if number >= min && number <= max => Ok(number),
+        _ => Err(invalid(format!(
+            "`{option}` expects a whole number from {min} to {max}, found `{value}`"
+        ))),
+    }
+}
+
+fn geometry(value: &str) -> Result<Geometry, AppError> {
+    let wrong = || invalid(format!("`--geometry` expects WxH or WxH+X+Y, e.g. 1280x800+0+0, found `{value}`"));
+    let (size, position) = match value.find(['+', '-']) {
+        Some(idx) => (&value[..idx], Some(&value[idx..])),
+        None => (value, None),
+    };
+    let (width, height) = size.split_once(['x', 'X']).ok_or_else(wrong)?;
+    let side = |text: &str| number("--geometry", text, MIN_WINDOW, MAX_WINDOW);
+    let (width, height) = (side(width)?, side(height)?);
+
+    // X11 style, each coordinate starts with its sign
+    let position = match position {
+        None => None,
+        Some(position) => {
+            let split = position[1..].find(['+', '-']).map(|idx| idx + 1).ok_or_else(wrong)?;
+            let coordinate = |text: &str| {
+                text.strip_prefix('+')
+                    .unwrap_or(text)
+                    .parse::<i32>()
+                    .map_err(|_| wrong())
+            };
+            Some((coordinate(&position[..split])?, coordinate(&position[split..])?))
+        }
+    };
+    Ok(Geometry { width, height, position })
+}
+
+fn params(value: &str) -> Result<[f32; 2], AppError> {
+    let parsed: Vec<Option<f32>> = value
+        .split(',')
+        .map(|field| field.trim().parse::<f32>().ok().filter(|x| x.is_finite()))
+        .collect();
+    match parsed[..] {
+        [Some(first), Some(second)] => Ok([first, second]),
+        _ => Err(invalid(format!(
+            "`--params` expects two numbers separated by a comma, found `{value}`"
+        ))),
+    }
+}
+
+// The arguments after the program name, as `--option value` or `--option=value`
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, AppError> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (option, inline) = match arg.split_once('=') {
+            Some((option, value)) if option.starts_with("--") => (option.to_string(), Some(value.to_string())),
+            _ => (arg, None),
+        };
+
+        // Flags first, they take no value
+        match option.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--fullscreen" | "--no-restore" if inline.is_some() => {
+                return Err(invalid(format!("`{option}` does not take a value")));
+            }
+            "--fullscreen" => {
+                options.fullscreen = true;
+                continue;
+            }
+            "--no-restore" => {
+                options.restore = false;
+                continue;
+            }
+            _ if !option.starts_with('-') => return Err(invalid(format!("unexpected argument `{option}`"))),
+            _ => {}
+        }
+
+        let known = [
+            "--geometry",
+            "--fps",
+            "--duration",
+            "--distribution",
+            "--params",
+            "--chart",
+            "--seed",
+            "--samples",
+            "--bins",
+            "--theme",
+            "--font",
+            "--data",
+        ];
+        if !known.contains(&option.as_str()) {
+            return Err(invalid(format!("unknown option `{option}`")));
+        }
+        let value = inline
+            .or_else(|| args.next())
+            .ok_or_else(|| invalid(format!("`{option}` expects a value")))?;
+        let option = option.as_str();
+        match option {
+            "--geometry" => options.geometry = Some(geometry(&value)?),
+            "--fps" => options.fps = number(option, &value, 1, MAX_FPS)?,
+            "--duration" => options.duration = Some(Duration::from_millis(number(option, &value, 0, MAX_DURATION)?)),
+            "--distribution" => {
+                let found = consts::DISTRIBUTIONS
+                    .iter()
+                    .copied()
+                    .find(|known| known.eq_ignore_ascii_case(&value))
+                    .ok_or_else(|| {
+                        invalid(format!("unknown distribution `{value}`, expected uniform, normal or gamma"))
+                    })?;
+                options.distribution = Some(found);
+            }
+            "--params" => options.params = Some(params(&value)?),
+            "--chart" => {
+                let kind = ChartKind::from_key(&value.to_lowercase()).ok_or_else(|| {
+                    let keys: Vec<&str> = ChartKind::ALL.iter().map(|kind| kind.key()).collect();
+                    invalid(format!("unknown chart `{value}`, expected one of {}", keys.join(", ")))
+                })?;
+                options.chart = Some(kind);
+            }
+            "--seed" => options.seed = Some(number(option, &value, 0, u64::MAX)?),
+            "--samples" => options.samples = Some(number(option, &value, 1, MAX_SAMPLES)?),
+            "--bins" => options.bins = Some(number(option, &value, 1, MAX_BINS)?),
+            "--theme" => options.theme = Some(PathBuf::from(value)),
+            "--font" => options.font = Some(PathBuf::from(value)),
+            "--data" => options.data = Some(PathBuf::from(value)),
+            _ => unreachable!("`{}` is in the list of known options", option),
+        }
+    }
+
+    if let Some(params) = options.params {
+        let name = options
+            .distribution
+            .ok_or_else(|| invalid("`--params` needs `--distribution`, the parameters depend on it".to_string()))?;
+        RandomGenerator::check_params(name, params).map_err(|problem| invalid(format!("`--params`: {problem}")))?;
+    }
+    Ok(Command::Run(options))
+}
+
+impl Options {
+    pub fn size(&self) -> Option<(i32, i32)> {
+        self.geometry.map(|geometry| (geometry.width, geometry.height))
+    }
+
+    // Over the restored session, before the first frame
+    pub fn apply(&self, state: &mut State) -> Result<(), AppError> {
+        // Unlike the default paths, a file asked for must be there
+        for (option, path) in [("--theme", &self.theme), ("--font", &self.font)] {
+            if let Some(path) = path.as_ref().filter(|path| !path.is_file()) {
+                return Err(invalid(format!("`{option}`: no such file `{}`", path.display())));
+            }
+        }
+        if let Some(duration) = self.duration {
+            state.animation.duration = duration;
+        }
+        if let Some(samples) = self.samples {
+            state.sample_count = samples;
+        }
+        if let Some(bins) = self.bins {
+            state.bin_count = bins;
+        }
+        if let Some(path) = self.font.as_ref() {
+            state.font_file = Some(path.clone());
+        }
+        if let Some(path) = self.data.as_ref() {
+            state.input = Some(crate::input::load(path)?);
+        }
+        if let Some((width, height)) = self.size() {
+            state.width = width;
+            state.height = height;
+        }
+
+        let changes_data = self.distribution.is_some()
+            || self.chart.is_some()
+            || self.seed.is_some()
+            || self.samples.is_some()
+            || self.bins.is_some()
+            || self.data.is_some();
+        if changes_data {
+            // Without new parameters, the distribution of the session keeps its own
+            let params = self.params.or_else(|| {
+                let same = self.distribution.is_none_or(|name| name == state.generator.name());
+                same.then(|| state.generator.params())
+            });
+            let session = Session {
+                distribution: self.distribution,
+                params,
+                chart: self.chart,
+                seed: self.seed,
+                ..Session::default()
+            };
+            session.apply(state);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(args: &[&str]) -> Result<Options, AppError> {
+        match parse(args.iter().map(|arg| arg.to_string()))? {
+            Command::Run(options) => Ok(options),
+            Command::Help => panic!("{:?} asked for the help", args),
+        }
+    }
+
+    #[test]
+    fn parses_both_value_forms() {
+        let options = run(&["--fps", "30", "--distribution=Gamma", "--params", "3,0.5", "--chart=qq"]).unwrap();
+        assert_eq!(options.fps, 30);
+        assert_eq!(options.distribution, Some(consts::DROP_DOWN_GAMMA));
+        assert_eq!(options.params, Some([3.0, 0.5]));
+        assert_eq!(options.chart, Some(ChartKind::QQ));
+        assert!(options.restore);
+        assert_eq!(run(&[]).unwrap(), Options::default());
+        assert!(!run(&["--no-restore"]).unwrap().restore);
+        assert_eq!(parse(["--fps".to_string(), "x".to_string(), "-h".to_string()]).ok(), None);
+        assert_eq!(parse(["--help".to_string()]).ok(), Some(Command::Help));
+    }
+
+    #[test]
+    fn parses_geometry() {
+        let size = |w, h, position| Some(Geometry { width: w, height: h, position });
+        assert_eq!(run(&["--geometry", "1280x800"]).unwrap().geometry, size(1280, 800, None));
+        assert_eq!(run(&["--geometry=800X600+10+20"]).unwrap().geometry, size(800, 600, Some((10, 20))));
+        assert_eq!(
+            run(&["--geometry", "800x600-10-20"]).unwrap().geometry,
+            size(800, 600, Some((-10, -20)))
+        );
+        assert!(run(&["--geometry", "800"]).is_err());
+        assert!(run(&["--geometry", "10x10"]).is_err());
+        assert!(run(&["--geometry", "800x600+10"]).is_err());
+        assert!(run(&["--geometry", "800x600+-10+20"]).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(run(&["--fps"]).is_err());
+        assert!(run(&["--fps", "0"]).is_err());
+        assert!(run(&["--bins", "-1"]).is_err());
+        assert!(run(&["--distribution", "cauchy"]).is_err());
+        assert!(run(&["--chart", "pie"]).is_err());
+        assert!(run(&["--colour", "red"]).is_err());
+        assert!(run(&["data.csv"]).is_err());
+        assert!(run(&["--fullscreen=yes"]).is_err());
+        assert!(run(&["--params", "1,2"]).is_err());
+        assert!(run(&["--distribution", "uniform", "--params", "2,1"]).is_err());
+        assert!(run(&["--distribution", "normal", "--params", "0,0"]).is_err());
+        assert!(run(&["--distribution", "gamma", "--params", "2.5,1"]).is_err());
+        assert!(run(&["--distribution", "gamma", "--params", "2,1,3"]).is_err());
+    }
+
+    #[test]
+    fn accepts_the_whole_range_of_gamma_shapes() {
+        for shape in ["1", "14", "255"] {
+            let params = format!("{},0.5", shape);
+            assert!(run(&["--distribution", "gamma", "--params", &params]).is_ok(), "shape {}", shape);
+        }
+        assert!(run(&["--distribution", "gamma", "--params", "0,1"]).is_err());
+        assert!(run(&["--distribution", "gamma", "--params", "256,1"]).is_err());
+    }
+}
@@ -0,0 +1,18 @@
+use std::path::{Path, PathBuf};
+
+// `claydash` in the user configuration directory, `$XDG_CONFIG_HOME`, `%APPDATA%` or `~/.config`
+pub fn dir() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config.join("claydash"))
+}
+
+// The path in the environment variable `env` when set, otherwise `file` in the configuration directory
+pub fn path(env: &str, file: &str) -> Option<PathBuf> {
+    match std::env::var_os(env) {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Some(dir()?.join(file)),
+    }
+}
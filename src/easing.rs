@@ -0,0 +1,114 @@
+use std::f32::consts::PI;
+
+// Timing curves mapping the linear progress of an animation, in [0, 1], to the progress shown on screen.
+// Elastic, bounce and spring overshoot 1 on the way, they all end exactly at 1
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    CubicIn,
+    #[default]
+    CubicOut,
+    CubicInOut,
+    Elastic,
+    Bounce,
+    Spring,
+}
+
+impl Easing {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::CubicIn => "Cubic in",
+            Self::CubicOut => "Cubic out",
+            Self::CubicInOut => "Cubic in/out",
+            Self::Elastic => "Elastic",
+            Self::Bounce => "Bounce",
+            Self::Spring => "Spring",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Linear => Self::CubicIn,
+            Self::CubicIn => Self::CubicOut,
+            Self::CubicOut => Self::CubicInOut,
+            Self::CubicInOut => Self::Elastic,
+            Self::Elastic => Self::Bounce,
+            Self::Bounce => Self::Spring,
+            Self::Spring => Self::Linear,
+        }
+    }
+
+    pub fn apply(&self, t: f32) -> f32 {
+        if t <= 0.0 {
+            return 0.0;
+        }
+        if t >= 1.0 {
+            return 1.0;
+        }
+
+        match self {
+            Self::Linear => t,
+            Self::CubicIn => t * t * t,
+            Self::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Self::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(3) / 2.0
+                }
+            }
+            Self::Elastic => {
+                let period = 2.0 * PI / 3.0;
+                2.0f32.powf(-10.0 * t) * ((10.0 * t - 0.75) * period).sin() + 1.0
+            }
+            Self::Bounce => bounce_out(t),
+            Self::Spring => 1.0 - (-6.0 * t).exp() * (12.0 * t).cos(),
+        }
+    }
+}
+
+// Robert Penner's bounce, four parabolas of decreasing height
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> Vec<Easing> {
+        let mut easings = vec![Easing::Linear];
+        while easings.last().map(Easing::next) != Some(Easing::Linear) {
+            easings.push(easings.last().unwrap().next());
+        }
+        easings
+    }
+
+    #[test]
+    fn curves_start_at_zero_and_end_at_one() {
+        let easings = all();
+        assert_eq!(easings.len(), 7);
+        for easing in easings {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+            // No visible jump when the clamping takes over at either end
+            assert!(easing.apply(1e-4).abs() < 5e-3, "{:?}", easing);
+            assert!((easing.apply(1.0 - 1e-4) - 1.0).abs() < 5e-3, "{:?}", easing);
+        }
+    }
+}
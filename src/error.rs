@@ -2,8 +2,32 @@
 pub enum AppError {
     MissingFunction(&'static str),
     InvalidFont(&'static str),
+    InvalidFontFile(String, String),
     InvalidDll(&'static str),
     ReloadError(String),
+    InvalidTheme {
+        path: String,
+        position: Option<crate::toml::Position>,
+        message: String,
+    },
+    InvalidKeyBindings {
+        path: String,
+        position: Option<crate::toml::Position>,
+        message: String,
+    },
+    InvalidSession {
+        path: String,
+        position: Option<crate::toml::Position>,
+        message: String,
+    },
+    SessionNotSaved(String, String),
+    SampleNotExported(String, String),
+    InvalidArgument(String),
+    InvalidDataFile {
+        path: String,
+        line: Option<usize>,
+        message: String,
+    },
 }
 
 impl std::fmt::Display for AppError {
@@ -18,6 +42,12 @@ impl std::fmt::Display for AppError {
                 f.write_str("Error: invalid font - ")?;
                 f.write_str(font)?;
             }
+            Self::InvalidFontFile(path, msg) => {
+                f.write_str("Error: invalid font file - ")?;
+                f.write_str(path)?;
+                f.write_str(": ")?;
+                f.write_str(msg)?;
+            }
             Self::InvalidDll(msg) => {
                 f.write_str("Error: invalid shared library - ")?;
                 f.write_str(msg)?;
@@ -26,6 +56,59 @@ impl std::fmt::Display for AppError {
                 f.write_str("Error: there was an error when reloading the shared library - ")?;
                 f.write_str(msg)?;
             }
+            Self::InvalidTheme { path, position, message } => {
+                f.write_str("Error: invalid theme - ")?;
+                f.write_str(path)?;
+                if let Some(position) = position {
+                    write!(f, ":{}:{}", position.line, position.column)?;
+                }
+                f.write_str(": ")?;
+                f.write_str(message)?;
+            }
+            Self::InvalidKeyBindings { path, position, message } => {
+                f.write_str("Error: invalid key bindings - ")?;
+                f.write_str(path)?;
+                if let Some(position) = position {
+                    write!(f, ":{}:{}", position.line, position.column)?;
+                }
+                f.write_str(": ")?;
+                f.write_str(message)?;
+            }
+            Self::InvalidSession { path, position, message } => {
+                f.write_str("Error: invalid session - ")?;
+                f.write_str(path)?;
+                if let Some(position) = position {
+                    write!(f, ":{}:{}", position.line, position.column)?;
+                }
+                f.write_str(": ")?;
+                f.write_str(message)?;
+            }
+            Self::SessionNotSaved(path, msg) => {
+                f.write_str("Error: could not save the session - ")?;
+                f.write_str(path)?;
+                f.write_str(": ")?;
+                f.write_str(msg)?;
+            }
+            Self::SampleNotExported(path, msg) => {
+                f.write_str("Error: could not export the sample - ")?;
+                f.write_str(path)?;
+                f.write_str(": ")?;
+                f.write_str(msg)?;
+            }
+            Self::InvalidArgument(msg) => {
+                f.write_str("Error: invalid argument - ")?;
+                f.write_str(msg)?;
+                f.write_str("\nRun `claydash --help` to list the options")?;
+            }
+            Self::InvalidDataFile { path, line, message } => {
+                f.write_str("Error: invalid data file - ")?;
+                f.write_str(path)?;
+                if let Some(line) = line {
+                    write!(f, ":{line}")?;
+                }
+                f.write_str(": ")?;
+                f.write_str(message)?;
+            }
         }
         f.write_str("\n")
     }
@@ -137,6 +137,13 @@ impl Clay_SizingAxis {
             type_: ClaySizingType::Fixed as u8,
         }
     }
+    // Fraction of the parent, in [0.0, 1.0]
+    pub const fn sizing_percent(percent: f32) -> Self {
+        Clay_SizingAxis {
+            size: Clay_SizingAxis__bindgen_ty_1 { percent },
+            type_: ClaySizingType::Percent as u8,
+        }
+    }
 }
 
 impl Clay_Sizing {
@@ -162,6 +169,27 @@ impl Clay_String {
             chars: s.as_ptr() as *const i8,
         }
     }
+
+    // SAFETY: clay keeps the pointer around until the render commands are drawn, so `s` must outlive them
+    pub fn from_string(s: &str) -> Self {
+        Self {
+            isStaticallyAllocated: false,
+            length: s.len() as i32,
+            chars: s.as_ptr() as *const i8,
+        }
+    }
+}
+
+impl Clay_StringSlice {
+    // Clay slices on spaces and newlines only, so a slice of valid UTF-8 stays valid
+    // SAFETY: `chars` must point to `length` bytes, as it does for slices clay hands out
+    pub(crate) unsafe fn as_str_lossy(&self) -> std::borrow::Cow<'_, str> {
+        if self.chars.is_null() || self.length <= 0 {
+            return std::borrow::Cow::Borrowed("");
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(self.chars as *const u8, self.length as usize) };
+        String::from_utf8_lossy(bytes)
+    }
 }
 
 pub struct ClayElementId<'s> {
@@ -209,6 +237,13 @@ impl ClayElementBuilder {
         self
     }
 
+    // Same as clay's CLAY_IDI, for elements repeated in a loop
+    pub fn with_id_index(mut self, id: &'static str, index: u32) -> Self {
+        // SAFETY: safety is at the clay level
+        self.ele.id = unsafe { Clay__HashString(Clay_String::from_str(id), index, 0) };
+        self
+    }
+
     pub fn with_layout(mut self, layout: Clay_LayoutConfig) -> Self {
         self.ele.layout = layout;
         self
@@ -470,6 +505,11 @@ impl ClayFloatingBuilder {
         Self { float: Clay_FloatingElementConfig::default() }
     }
 
+    pub fn with_offset(mut self, x: f32, y: f32) -> Self {
+        self.float.offset = Clay_Vector2 { x, y };
+        self
+    }
+
     pub fn with_zindex(mut self, index: i16) -> Self {
         self.float.zIndex = index;
         self
@@ -1,16 +1,27 @@
 use super::clay::{Clay_Dimensions, Clay_StringSlice, Clay_TextElementConfig, Clay_Vector2};
 
+use crate::font::{FontRegistry, Glyphs};
+
 use std::ffi::c_void;
 
 pub use crate::bindings::raylib::{
     BeginDrawing, BeginScissorMode, ClearBackground, CloseWindow, Color, ColorBrightness,
-    ConfigFlags_FLAG_WINDOW_RESIZABLE, DrawLineEx, DrawLineV, DrawPixelV, DrawRectangle, DrawRectangleLinesEx,
-    DrawRectangleRounded, DrawRectangleV, DrawRing, DrawText, DrawTextEx, DrawTriangle, EndDrawing, EndScissorMode,
-    Font, GenImageFontAtlas, GetFPS, GetFrameTime, GetMousePosition, GetMouseWheelMoveV, GetScreenHeight,
-    GetScreenWidth, InitWindow, IsFontValid, IsKeyDown, IsKeyPressed, IsMouseButtonDown, KeyboardKey_KEY_D,
-    KeyboardKey_KEY_M, KeyboardKey_KEY_Q, KeyboardKey_KEY_R, LoadFontData, LoadFontFromMemory, Rectangle,
-    SetConfigFlags, SetExitKey, SetTargetFPS, SetTraceLogLevel, TextFormat, TraceLogLevel_LOG_ALL,
-    TraceLogLevel_LOG_NONE, Vector2, WindowShouldClose,
+    ConfigFlags_FLAG_FULLSCREEN_MODE, ConfigFlags_FLAG_WINDOW_RESIZABLE, DrawCircleV, DrawLineEx, DrawLineV,
+    DrawPixelV, DrawRectangle, DrawRectangleLinesEx, DrawRectangleRounded, DrawRectangleV, DrawRing, DrawText,
+    DrawTextCodepoint, DrawTextEx, DrawTriangle, EndDrawing, EndScissorMode, Font, GenImageFontAtlas, GetCharPressed,
+    GetClipboardText, GetFPS, GetFrameTime, GetMousePosition, GetMouseWheelMoveV, GetScreenHeight, GetScreenWidth,
+    GetWindowScaleDPI, GlyphInfo, InitWindow, IsFontValid, IsKeyDown, IsKeyPressed, IsKeyPressedRepeat,
+    IsMouseButtonDown, IsMouseButtonPressed, IsWindowReady, KeyboardKey, KeyboardKey_KEY_B, KeyboardKey_KEY_BACKSPACE,
+    KeyboardKey_KEY_D, KeyboardKey_KEY_DELETE, KeyboardKey_KEY_DOWN, KeyboardKey_KEY_END, KeyboardKey_KEY_ENTER,
+    KeyboardKey_KEY_EQUAL, KeyboardKey_KEY_ESCAPE, KeyboardKey_KEY_F1, KeyboardKey_KEY_G, KeyboardKey_KEY_H,
+    KeyboardKey_KEY_HOME, KeyboardKey_KEY_I, KeyboardKey_KEY_K, KeyboardKey_KEY_KP_ADD, KeyboardKey_KEY_KP_ENTER,
+    KeyboardKey_KEY_KP_SUBTRACT, KeyboardKey_KEY_LEFT, KeyboardKey_KEY_LEFT_CONTROL, KeyboardKey_KEY_LEFT_SHIFT,
+    KeyboardKey_KEY_M, KeyboardKey_KEY_MINUS, KeyboardKey_KEY_N, KeyboardKey_KEY_NULL, KeyboardKey_KEY_O,
+    KeyboardKey_KEY_P, KeyboardKey_KEY_Q, KeyboardKey_KEY_R, KeyboardKey_KEY_RIGHT, KeyboardKey_KEY_RIGHT_CONTROL,
+    KeyboardKey_KEY_RIGHT_SHIFT, KeyboardKey_KEY_S, KeyboardKey_KEY_SPACE, KeyboardKey_KEY_T, KeyboardKey_KEY_TAB,
+    KeyboardKey_KEY_U, KeyboardKey_KEY_UP, KeyboardKey_KEY_V, KeyboardKey_KEY_X, KeyboardKey_KEY_ZERO, LoadFontData,
+    LoadFontFromMemory, Rectangle, SetConfigFlags, SetExitKey, SetTargetFPS, SetTraceLogLevel, SetWindowPosition,
+    SetWindowSize, TextFormat, TraceLogLevel_LOG_ALL, TraceLogLevel_LOG_NONE, UnloadFont, Vector2, WindowShouldClose,
 };
 
 crate::impl_default!(Font);
@@ -28,15 +39,16 @@ pub unsafe extern "C" fn raylib_measure_text(
 ) -> Clay_Dimensions {
     let mut txt_size = Clay_Dimensions::default();
 
-    let font: *mut Font = user_data as *mut Font;
+    let fonts: *const FontRegistry = user_data as *const FontRegistry;
     let font = unsafe {
-        match font.as_mut() {
+        match fonts.as_ref() {
             None => {
-                eprintln!("Font pointer is NULL, using default measure text");
+                eprintln!("Font registry pointer is NULL, using default measure text");
                 return txt_size;
             }
-            Some(f) => {
-                if IsFontValid(*f) {
+            Some(fonts) => {
+                let f = fonts.get((*config).fontId);
+                if IsFontValid(f) {
                     f
                 } else {
                     eprintln!("Font is invalid, using default measure text");
@@ -46,51 +58,13 @@ pub unsafe extern "C" fn raylib_measure_text(
         }
     };
 
-    let mut max_text_width: f32 = 0.0;
-    let mut line_text_width: f32 = 0.0;
-    let text_height = unsafe { (*config).fontSize };
-
-    let scale = unsafe { (*config).fontSize as f32 } / (font.baseSize as f32);
-    for i in 0..text.length {
-        let ch = unsafe { text.chars.offset(i as isize) };
-        if ch.is_null() || !ch.is_aligned() {
-            continue;
-        }
-
-        let bt = unsafe { *ch } as u8;
-        if bt <= 31 {
-            dbg!("ASCII control character {}", bt);
-            continue;
-        }
-        if bt == b'\n' {
-            max_text_width = max_text_width.max(line_text_width);
-            line_text_width = 0.0;
-            continue;
-        }
-
-        let idx = (bt - 32) as isize;
-        let glyph = unsafe { font.glyphs.offset(idx) };
-        if glyph.is_null() {
-            continue;
-        }
-
-        let info = unsafe { *glyph };
-        if info.advanceX != 0 {
-            line_text_width += info.advanceX as f32;
-        } else {
-            let recs = unsafe { font.recs.offset(idx) };
-            if recs.is_null() {
-                continue;
-            }
-
-            let recs = unsafe { *recs };
-            line_text_width += recs.width + info.offsetX as f32;
-        }
-    }
-
-    max_text_width = max_text_width.max(line_text_width);
-    txt_size.width = max_text_width * scale;
-    txt_size.height = text_height as f32;
+    let (width, height) = unsafe {
+        let glyphs = Glyphs::from_font(&font);
+        let config = &*config;
+        glyphs.measure(&text.as_str_lossy(), config.fontSize as f32, config.letterSpacing as f32)
+    };
+    txt_size.width = width;
+    txt_size.height = height;
 
     txt_size
 }
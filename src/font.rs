@@ -1,4 +1,10 @@
-use crate::ffi::raylib::{Font, IsFontValid, LoadFontFromMemory};
+use crate::error::AppError;
+use crate::ffi::raylib::{
+    Color, DrawTextCodepoint, Font, GlyphInfo, IsFontValid, LoadFontFromMemory, Rectangle, UnloadFont, Vector2,
+};
+use crate::ui::scheme::SchemeFont;
+
+use std::path::PathBuf;
 
 // Cannot use a static str in `include_bytes` even if it is constructed at compile time, so using a
 // macro to avoid repetition
@@ -8,27 +14,345 @@ macro_rules! font_path {
         "../resources/Roboto-Regular.ttf"
     };
 }
+macro_rules! mono_font_path {
+    () => {
+        "../resources/RobotoMono-Medium.ttf"
+    };
+}
 const FONT_PATH: &str = font_path!();
+const MONO_FONT_PATH: &str = mono_font_path!();
 pub const ROBOTO: &[u8] = include_bytes!(font_path!());
+pub const ROBOTO_MONO: &[u8] = include_bytes!(mono_font_path!());
 
-pub fn get_font(font_data: &[u8], font_config: &mut Font) -> Result<Font, &'static str> {
+// The `fontId` of text configs, an index in `FontRegistry`
+pub const FONT_LABEL: u16 = 0; // Proportional, labels, buttons and titles
+pub const FONT_MONO: u16 = 1; // Monospace, numbers so that they line up in tables and on axes
+pub const FONT_COUNT: usize = 2;
+
+// Compiled in fonts, one per id
+pub const EMBEDDED: [(&str, &[u8]); FONT_COUNT] = [(FONT_PATH, ROBOTO), (MONO_FONT_PATH, ROBOTO_MONO)];
+
+// Codepoints given a glyph when a font is loaded, inclusive and sorted so `Glyphs` can binary search them:
+// printable ASCII, Latin-1 (µ, ±, ², ×), Greek and the punctuation and maths symbols used in labels
+const CODEPOINT_RANGES: [(i32, i32); 8] = [
+    (0x20, 0x7e),
+    (0xa0, 0xff),
+    (0x391, 0x3a9), // Α to Ω
+    (0x3b1, 0x3c9), // α to ω
+    (0x2013, 0x2014),
+    (0x2026, 0x2026),
+    (0x2202, 0x221e), // ∂ ∑ − √ ∞ and those in between
+    (0x2248, 0x2265), // ≈ ≠ ≤ ≥ and those in between
+];
+const FALLBACK: char = '?'; // Drawn for codepoints outside the loaded set
+
+pub fn codepoints() -> Vec<i32> {
+    CODEPOINT_RANGES
+        .iter()
+        .flat_map(|(first, last)| *first..=*last)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontSource {
+    Embedded(u16), // One of `EMBEDDED`
+    File(PathBuf), // A TTF or OTF read at runtime
+}
+
+pub fn get_font(font_data: &[u8], file_type: &str, font_config: &mut Font) -> Result<Font, &'static str> {
+    let mut codepoints = codepoints();
+    // raylib picks the loader from the extension
+    let file_type = match file_type {
+        "otf" | "OTF" => ".otf\0",
+        _ => ".ttf\0",
+    };
     let font = unsafe {
         LoadFontFromMemory(
-            ".ttf\0".as_ptr() as *const i8,
+            file_type.as_ptr() as *const i8,
             font_data.as_ptr(),
             font_data.len() as i32,
             font_config.baseSize,
-            std::ptr::null_mut(),
-            font_config.glyphCount,
+            codepoints.as_mut_ptr(),
+            codepoints.len() as i32,
         )
     };
 
     unsafe {
         if !IsFontValid(font) {
-            eprintln!("Font {:?} is not valid, please check file '{}'", font, font_path!());
-            return Err(FONT_PATH);
+            eprintln!("Font {:?} is not valid", font);
+            return Err("the font data could not be loaded");
         }
     };
 
     Ok(font)
 }
+
+fn load_source(source: &FontSource, config: &SchemeFont) -> Result<Font, AppError> {
+    let mut font = Font {
+        baseSize: config.base_size,
+        glyphPadding: config.glyph_padding,
+        ..Default::default()
+    };
+
+    match source {
+        FontSource::Embedded(id) => {
+            let (path, data) = EMBEDDED[*id as usize];
+            get_font(data, "ttf", &mut font).map_err(|_| AppError::InvalidFont(path))
+        }
+        FontSource::File(path) => {
+            let invalid = |message: String| AppError::InvalidFontFile(path.display().to_string(), message);
+            let data = std::fs::read(path).map_err(|err| invalid(err.to_string()))?;
+            let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("ttf");
+            get_font(&data, extension, &mut font).map_err(|msg| invalid(msg.to_string()))
+        }
+    }
+}
+
+// The loaded fonts, indexed by `fontId`. Clay's measure function points to the registry in the state,
+// so fonts are swapped in place rather than the registry being replaced
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FontRegistry {
+    fonts: [Font; FONT_COUNT],
+    loaded: bool,
+}
+
+impl FontRegistry {
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    // Unknown ids fall back to the label font
+    pub fn get(&self, id: u16) -> Font {
+        self.fonts
+            .get(id as usize)
+            .copied()
+            .unwrap_or(self.fonts[FONT_LABEL as usize])
+    }
+
+    // A font file that cannot be read is reported and replaced by the embedded font of the same id, a
+    // broken embedded font is an error. FONTS MUST BE LOADED after raylib::InitWindow
+    pub fn load(&mut self, sources: &[FontSource; FONT_COUNT], config: &SchemeFont) -> Result<(), AppError> {
+        for (id, source) in sources.iter().enumerate() {
+            let font = match load_source(source, config) {
+                Ok(font) => font,
+                Err(err @ AppError::InvalidFontFile(..)) => {
+                    eprint!("{err}");
+                    load_source(&FontSource::Embedded(id as u16), config)?
+                }
+                Err(err) => return Err(err),
+            };
+            if self.loaded {
+                unsafe { UnloadFont(self.fonts[id]) };
+            }
+            self.fonts[id] = font;
+        }
+        self.loaded = true;
+        Ok(())
+    }
+}
+
+// The glyphs of a loaded font, with the lookup and metrics that measuring and drawing share so that text is
+// drawn exactly in the box clay laid out for it
+pub struct Glyphs<'a> {
+    infos: &'a [GlyphInfo],
+    recs: &'a [Rectangle],
+    base_size: f32,
+}
+
+impl<'a> Glyphs<'a> {
+    pub fn new(infos: &'a [GlyphInfo], recs: &'a [Rectangle], base_size: i32) -> Self {
+        Self { infos, recs, base_size: base_size as f32 }
+    }
+
+    // SAFETY: `font` must have been loaded by raylib, or hold `glyphCount` glyphs and rectangles
+    pub(crate) unsafe fn from_font(font: &'a Font) -> Self {
+        let count = font.glyphCount.max(0) as usize;
+        if font.glyphs.is_null() || font.recs.is_null() || count == 0 {
+            return Self::new(&[], &[], font.baseSize);
+        }
+        unsafe {
+            Self::new(
+                std::slice::from_raw_parts(font.glyphs, count),
+                std::slice::from_raw_parts(font.recs, count),
+                font.baseSize,
+            )
+        }
+    }
+
+    // Fonts are loaded with sorted codepoints, anything else (raylib's default font) is searched linearly
+    pub fn index(&self, codepoint: char) -> Option<usize> {
+        let value = codepoint as i32;
+        match self.infos.binary_search_by_key(&value, |info| info.value) {
+            Ok(idx) => Some(idx),
+            Err(_) => self.infos.iter().position(|info| info.value == value),
+        }
+    }
+
+    // The glyph drawn for `codepoint`, with the codepoint it stands for
+    pub fn lookup(&self, codepoint: char) -> Option<(usize, char)> {
+        match self.index(codepoint) {
+            Some(idx) => Some((idx, codepoint)),
+            None => self.index(FALLBACK).map(|idx| (idx, FALLBACK)),
+        }
+    }
+
+    fn advance(&self, idx: usize) -> f32 {
+        let info = &self.infos[idx];
+        if info.advanceX != 0 {
+            return info.advanceX as f32;
+        }
+        self.recs
+            .get(idx)
+            .map(|rec| rec.width + info.offsetX as f32)
+            .unwrap_or(0.0)
+    }
+
+    // Width of the widest line and height of all lines, in pixels at `font_size`. `spacing` is added between
+    // characters, as raylib does when drawing
+    pub fn measure(&self, text: &str, font_size: f32, spacing: f32) -> (f32, f32) {
+        let scale = font_size / self.base_size;
+        let mut width: f32 = 0.0;
+        let mut lines = 0;
+        for line in text.split('\n') {
+            lines += 1;
+            let mut line_width = 0.0;
+            let mut count = 0;
+            for c in line.chars().filter(|c| !c.is_control()) {
+                if let Some((idx, _)) = self.lookup(c) {
+                    line_width += self.advance(idx) * scale;
+                    count += 1;
+                }
+            }
+            if count > 1 {
+                line_width += spacing * (count - 1) as f32;
+            }
+            width = width.max(line_width);
+        }
+        (width, font_size * lines as f32)
+    }
+
+    // Position of each drawn character, relative to the start of the text
+    pub fn layout(&self, text: &str, font_size: f32, spacing: f32, mut place: impl FnMut(char, Vector2)) {
+        let scale = font_size / self.base_size;
+        let mut pen = Vector2 { x: 0.0, y: 0.0 };
+        for c in text.chars() {
+            if c == '\n' {
+                pen = Vector2 { x: 0.0, y: pen.y + font_size };
+                continue;
+            }
+            if c.is_control() {
+                continue;
+            }
+            if let Some((idx, glyph)) = self.lookup(c) {
+                if !glyph.is_whitespace() {
+                    place(glyph, pen);
+                }
+                pen.x += self.advance(idx) * scale + spacing;
+            }
+        }
+    }
+}
+
+pub fn draw_text(font: Font, text: &str, pos: Vector2, font_size: f32, spacing: f32, tint: Color) {
+    let glyphs = unsafe { Glyphs::from_font(&font) };
+    glyphs.layout(text, font_size, spacing, |codepoint, offset| unsafe {
+        let at = Vector2 { x: pos.x + offset.x, y: pos.y + offset.y };
+        DrawTextCodepoint(font, codepoint as i32, at, font_size, tint);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bindings::raylib::Image;
+
+    // One glyph per codepoint, `advance` pixels wide at a base size of 10
+    fn glyph_set(codepoints: &[(char, i32)]) -> (Vec<GlyphInfo>, Vec<Rectangle>) {
+        let infos = codepoints
+            .iter()
+            .map(|(c, advance)| GlyphInfo {
+                value: *c as i32,
+                offsetX: 0,
+                offsetY: 0,
+                advanceX: *advance,
+                image: Image { data: std::ptr::null_mut(), width: 0, height: 0, mipmaps: 0, format: 0 },
+            })
+            .collect();
+        let recs = codepoints
+            .iter()
+            .map(|(_, advance)| Rectangle { x: 0.0, y: 0.0, width: *advance as f32, height: 10.0 })
+            .collect();
+        (infos, recs)
+    }
+
+    fn sample_set() -> (Vec<GlyphInfo>, Vec<Rectangle>) {
+        glyph_set(&[
+            (' ', 3),
+            ('.', 2),
+            ('1', 5),
+            ('=', 6),
+            ('?', 5),
+            ('x', 5),
+            ('µ', 6),
+            ('λ', 5),
+            ('σ', 6),
+        ])
+    }
+
+    #[test]
+    fn codepoints_are_sorted_and_cover_greek_and_maths() {
+        let codepoints = codepoints();
+        assert!(codepoints.windows(2).all(|pair| pair[0] < pair[1]));
+        for c in ['A', '~', 'µ', '±', 'α', 'λ', 'σ', 'Ω', '∞', '√', '≤', '−'] {
+            assert!(codepoints.binary_search(&(c as i32)).is_ok(), "{} is not loaded", c);
+        }
+    }
+
+    #[test]
+    fn looks_up_multibyte_codepoints() {
+        let (infos, recs) = sample_set();
+        let glyphs = Glyphs::new(&infos, &recs, 10);
+        assert_eq!(glyphs.lookup('σ'), Some((8, 'σ')));
+        assert_eq!(glyphs.lookup('µ'), Some((6, 'µ')));
+        assert_eq!(glyphs.lookup('ж'), Some((4, '?')));
+    }
+
+    #[test]
+    fn measures_characters_rather_than_bytes() {
+        let (infos, recs) = sample_set();
+        let glyphs = Glyphs::new(&infos, &recs, 10);
+
+        // "σ = 1.1" is 8 bytes but 7 characters
+        assert_eq!(glyphs.measure("σ = 1.1", 10.0, 0.0), (6.0 + 3.0 + 6.0 + 3.0 + 5.0 + 2.0 + 5.0, 10.0));
+        assert_eq!(glyphs.measure("λx", 20.0, 0.0), (20.0, 20.0));
+        assert_eq!(glyphs.measure("µλσ", 10.0, 1.0), (6.0 + 5.0 + 6.0 + 2.0, 10.0));
+    }
+
+    #[test]
+    fn measures_unknown_codepoints_as_the_fallback() {
+        let (infos, recs) = sample_set();
+        let glyphs = Glyphs::new(&infos, &recs, 10);
+        assert_eq!(glyphs.measure("x日x", 10.0, 0.0), glyphs.measure("x?x", 10.0, 0.0));
+    }
+
+    #[test]
+    fn measures_the_widest_line() {
+        let (infos, recs) = sample_set();
+        let glyphs = Glyphs::new(&infos, &recs, 10);
+        assert_eq!(glyphs.measure("σσσ\nx", 10.0, 0.0), (18.0, 20.0));
+    }
+
+    #[test]
+    fn lays_out_what_is_measured() {
+        let (infos, recs) = sample_set();
+        let glyphs = Glyphs::new(&infos, &recs, 10);
+        let text = "µ = 1 λ";
+        let mut placed = Vec::new();
+        glyphs.layout(text, 10.0, 1.0, |c, pen| placed.push((c, pen.x)));
+
+        // Spaces advance the pen without being drawn
+        assert_eq!(placed, vec![('µ', 0.0), ('=', 11.0), ('1', 22.0), ('λ', 32.0)]);
+        let (width, _) = glyphs.measure(text, 10.0, 1.0);
+        assert_eq!(width, 32.0 + 5.0);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::error::AppError;
+
+use std::path::Path;
+
+const MIN_VALUES: usize = 2; // Below that there is no spread to draw
+
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || c == ',' || c == ';'
+}
+
+// Numbers separated by whitespace, commas or semicolons, `#` starts a comment. A first line that is not numeric
+// is taken as a header, e.g. the column name of a CSV export
+pub fn parse(src: &str) -> Result<Vec<f32>, (Option<usize>, String)> {
+    let mut values = Vec::new();
+    let mut seen_line = false;
+    for (idx, line) in src.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        let fields: Vec<&str> = line.split(is_separator).filter(|field| !field.is_empty()).collect();
+        if fields.is_empty() {
+            continue;
+        }
+        let is_header = !seen_line && fields.iter().any(|field| field.parse::<f32>().is_err());
+        seen_line = true;
+        if is_header {
+            continue;
+        }
+        for field in fields {
+            let value = field
+                .parse::<f32>()
+                .map_err(|_| (Some(idx + 1), format!("`{field}` is not a number")))?;
+            if !value.is_finite() {
+                return Err((Some(idx + 1), format!("`{field}` is not a finite number")));
+            }
+            values.push(value);
+        }
+    }
+    if values.len() < MIN_VALUES {
+        return Err((None, format!("expected at least {MIN_VALUES} values, found {}", values.len())));
+    }
+    Ok(values)
+}
+
+pub fn load(path: &Path) -> Result<Vec<f32>, AppError> {
+    let invalid = |line, message| AppError::InvalidDataFile { path: path.display().to_string(), line, message };
+    let src = std::fs::read_to_string(path).map_err(|err| invalid(None, err.to_string()))?;
+    parse(&src).map_err(|(line, message)| invalid(line, message))
+}
+
+// One value per line under a header, so that `parse` reads it back
+pub fn to_csv(values: &[f32]) -> String {
+    let mut out = String::from("value\n");
+    for value in values {
+        out.push_str(&value.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+pub fn save(path: &Path, values: &[f32]) -> Result<(), AppError> {
+    let failed = |err: std::io::Error| AppError::SampleNotExported(path.display().to_string(), err.to_string());
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(failed)?;
+    }
+    std::fs::write(path, to_csv(values)).map_err(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_numbers_with_any_separator() {
+        let src = "value\n1.5, 2\n# comment\n-3;4e1 5 # trailing\n\n";
+        assert_eq!(parse(src).unwrap(), vec![1.5, 2.0, -3.0, 40.0, 5.0]);
+    }
+
+    #[test]
+    fn reports_the_line_of_bad_values() {
+        assert_eq!(parse("1\n2\nthree\n").unwrap_err().0, Some(3));
+        assert_eq!(parse("1\nNaN\n").unwrap_err().0, Some(2));
+        assert_eq!(parse("x\n1\n").unwrap_err().0, None);
+        assert_eq!(parse("").unwrap_err().0, None);
+    }
+
+    #[test]
+    fn exported_values_read_back_exactly() {
+        let values = [0.1, -2.5e-7, 3.0, f32::MAX, 123_456.79];
+        assert_eq!(parse(&to_csv(&values)).unwrap(), values.to_vec());
+    }
+}
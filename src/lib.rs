@@ -1,12 +1,20 @@
 use std::mem::MaybeUninit;
 mod bindings;
 mod chart;
+pub mod cli;
+pub mod config;
 mod dll;
+pub mod easing;
 pub mod error;
 pub mod ffi;
 pub mod font;
+pub mod input;
 pub mod math;
+pub mod montecarlo;
 pub mod os;
+pub mod process;
+pub mod stats;
+pub mod toml;
 pub mod ui;
 
 fn zero<T>() -> T {
@@ -35,14 +43,14 @@ pub fn create_layout(
     ctx: *mut ffi::clay::Clay_Context,
     state: std::pin::Pin<&mut ui::State>,
 ) -> ffi::clay::Clay_RenderCommandArray {
-    ui::create_layout(ctx, state, &SCHEME)
+    let mut scheme = *state.scheme();
+    scheme.scale = state.scale();
+    ui::create_layout(ctx, state, &scheme)
 }
 
 #[unsafe(no_mangle)]
-pub fn render_layout(
-    state: std::pin::Pin<&mut ui::State>,
-    layout: ffi::clay::Clay_RenderCommandArray,
-    font: ffi::raylib::Font,
-) {
-    ui::render_layout(state, layout, font, &SCHEME)
+pub fn render_layout(state: std::pin::Pin<&mut ui::State>, layout: ffi::clay::Clay_RenderCommandArray) {
+    let mut scheme = *state.scheme();
+    scheme.scale = state.scale();
+    ui::render_layout(state, layout, &scheme)
 }
@@ -1,14 +1,14 @@
+use clay::cli::{self, Command, Options};
 use clay::error::AppError;
 use clay::ffi::{self, raylib};
+use clay::font::FontRegistry;
 use clay::ui::layout::CreateLayoutSignature;
 use clay::ui::render::RenderLayoutSignature;
-use clay::ui::State;
+use clay::ui::{keyboard, session, theme, State};
 use clay::Library;
 
 use std::pin::Pin;
 
-pub const ANIMATION_DURATION: std::time::Duration = std::time::Duration::from_millis(750);
-
 cfg_if::cfg_if! {
     if #[cfg(feature = "hot_reload")] {
         // Need to append the NULL terminator to be used from C code
@@ -56,15 +56,62 @@ fn get_fn_or_error(
     Ok(())
 }
 
+fn load_fonts(state: &mut State) -> Result<(), AppError> {
+    let sources = state.font_sources();
+    let config = state.font_config();
+    state.fonts.load(&sources, &config)
+}
+
+// Ctrl+= and Ctrl+- step the UI scale, Ctrl+0 resets it
+unsafe fn ui_zoom_keys() -> Option<i32> {
+    let pressed = |key| unsafe { raylib::IsKeyPressed(key as i32) };
+    let ctrl = unsafe {
+        raylib::IsKeyDown(raylib::KeyboardKey_KEY_LEFT_CONTROL as i32)
+            || raylib::IsKeyDown(raylib::KeyboardKey_KEY_RIGHT_CONTROL as i32)
+    };
+    if !ctrl {
+        None
+    } else if pressed(raylib::KeyboardKey_KEY_EQUAL) || pressed(raylib::KeyboardKey_KEY_KP_ADD) {
+        Some(1)
+    } else if pressed(raylib::KeyboardKey_KEY_MINUS) || pressed(raylib::KeyboardKey_KEY_KP_SUBTRACT) {
+        Some(-1)
+    } else if pressed(raylib::KeyboardKey_KEY_ZERO) {
+        Some(0)
+    } else {
+        None
+    }
+}
+
 fn main_loop(
     mem: &mut Vec<std::ffi::c_void>,
     mut state: Pin<&mut State>,
     mut dll: Option<Library>,
+    options: Options,
 ) -> Result<(), AppError> {
-    let screen_width = 1080;
-    let screen_height = 720;
-    const LIGHTGRAY: raylib::Color = raylib::Color { r: 100, g: 100, b: 100, a: 255 };
-    const FPS: i32 = 60;
+    let mut screen_width = cli::DEFAULT_WIDTH;
+    let mut screen_height = cli::DEFAULT_HEIGHT;
+
+    // The sample of the last run is drawn again from its seed before the window opens
+    let session_path = session::default_path();
+    if let Some(path) = session_path.as_ref().filter(|_| options.restore) {
+        match session::load(path) {
+            Ok(Some(last)) => {
+                screen_width = last.window_width.unwrap_or(screen_width);
+                screen_height = last.window_height.unwrap_or(screen_height);
+                last.apply(unsafe { state.as_mut().get_unchecked_mut() });
+            }
+            Ok(None) => {}
+            Err(err) => eprint!("{err}"),
+        }
+    }
+    // The command line has the last word over the session
+    if let Err(err) = options.apply(unsafe { state.as_mut().get_unchecked_mut() }) {
+        eprint!("{err}");
+        std::process::exit(2);
+    }
+    if let Some((width, height)) = options.size() {
+        (screen_width, screen_height) = (width, height);
+    }
 
     unsafe {
         cfg_if::cfg_if! {
@@ -77,24 +124,38 @@ fn main_loop(
 
             }
         }
-        raylib::SetConfigFlags(raylib::ConfigFlags_FLAG_WINDOW_RESIZABLE);
+        let mut flags = raylib::ConfigFlags_FLAG_WINDOW_RESIZABLE;
+        if options.fullscreen {
+            flags |= raylib::ConfigFlags_FLAG_FULLSCREEN_MODE;
+        }
+        raylib::SetConfigFlags(flags);
         raylib::InitWindow(screen_width, screen_height, "ClayDash\0".as_ptr() as *const i8);
-        raylib::SetTargetFPS(FPS);
-        raylib::SetExitKey(raylib::KeyboardKey_KEY_Q as i32);
+        if let Some((x, y)) = options.geometry.and_then(|geometry| geometry.position) {
+            raylib::SetWindowPosition(x, y);
+        }
+        raylib::SetTargetFPS(options.fps);
+        raylib::SetExitKey(raylib::KeyboardKey_KEY_NULL as i32); // Leaving is one of the configurable shortcuts
     };
 
+    if let Some(path) = keyboard::default_path() {
+        match keyboard::load(&path) {
+            Ok(bindings) => unsafe { state.as_mut().get_unchecked_mut() }.bindings = bindings,
+            Err(err) => eprint!("{err}"),
+        }
+    }
+
     let mut first_run = true;
+    let mut theme_file = options
+        .theme
+        .clone()
+        .or_else(theme::default_path)
+        .map(theme::ThemeFile::new);
+    let mut loaded_fonts = (state.font_config(), state.font_sources()); // Of the fonts in the registry
     let ctx = unsafe {
         let state_mut_ref = state.as_mut().get_unchecked_mut();
 
         // FONT MUST BE LOADED after raylib::InitWindow
-        let mut font = raylib::Font::default();
-        font.baseSize = clay::SCHEME.font_config.base_size;
-        font.glyphCount = clay::SCHEME.font_config.glyph_count;
-        font.glyphPadding = clay::SCHEME.font_config.glyph_padding;
-
-        let font = clay::font::get_font(clay::SCHEME.font_data, &mut font).map_err(|s| AppError::InvalidFont(s))?;
-        state_mut_ref.font = Some(font);
+        load_fonts(state_mut_ref)?;
         clay_initialize(state_mut_ref, mem)
     }?;
 
@@ -116,24 +177,22 @@ fn main_loop(
     unsafe {
         while !raylib::WindowShouldClose() && !state.should_close {
             raylib::BeginDrawing();
-            raylib::ClearBackground(LIGHTGRAY);
+            if let Some(scheme) = theme_file.as_mut().and_then(|file| file.poll()) {
+                state.as_mut().get_unchecked_mut().apply_theme_file(scheme);
+            }
+            raylib::ClearBackground(state.scheme().canvas.clear.into());
             if first_run {
                 first_run = false;
                 let state_mut_ref = state.as_mut().get_unchecked_mut();
 
                 // FONT MUST BE LOADED after raylib::InitWindow
-                let mut font = raylib::Font::default();
-                font.baseSize = clay::SCHEME.font_config.base_size;
-                font.glyphCount = clay::SCHEME.font_config.glyph_count;
-                font.glyphPadding = clay::SCHEME.font_config.glyph_padding;
-
-                let font =
-                    clay::font::get_font(clay::SCHEME.font_data, &mut font).map_err(|s| AppError::InvalidFont(s))?;
-
-                state_mut_ref.font = Some(font);
+                load_fonts(state_mut_ref)?;
 
-                std::thread::sleep(std::time::Duration::from_secs_f32(1.0 / (FPS as f32)));
-                state_mut_ref.simulate();
+                std::thread::sleep(std::time::Duration::from_secs_f32(1.0 / (options.fps as f32)));
+                // A restored session or the command line already drew the sample
+                if state_mut_ref.chart_data.is_none() {
+                    state_mut_ref.simulate();
+                }
             }
 
             cfg_if::cfg_if! {
@@ -145,6 +204,18 @@ fn main_loop(
                 }
             }
 
+            {
+                let state_mut_ref = state.as_mut().get_unchecked_mut();
+                state_mut_ref.dpi_scale = raylib::GetWindowScaleDPI().x;
+            }
+
+            // Built in themes share their fonts, only a theme file or a new scale can change them
+            let wanted_fonts = (state.font_config(), state.font_sources());
+            if wanted_fonts != loaded_fonts {
+                load_fonts(state.as_mut().get_unchecked_mut())?;
+                loaded_fonts = wanted_fonts;
+            }
+
             {
                 let state_mut_ref = state.as_mut().get_unchecked_mut();
                 state_mut_ref.height = raylib::GetScreenHeight();
@@ -153,12 +224,10 @@ fn main_loop(
             }
 
             let layout = create_layout_fun(ctx.ctx, state.as_mut());
-            match state.font {
-                None => return Err(AppError::InvalidFont("Font is not defined")),
-                Some(font) => {
-                    render_layout_fun(state.as_mut(), layout, font);
-                }
+            if !state.fonts.is_loaded() {
+                return Err(AppError::InvalidFont("Font is not defined"));
             }
+            render_layout_fun(state.as_mut(), layout);
 
             cfg_if::cfg_if! {
                 if #[cfg(feature = "hot_reload")] {
@@ -174,19 +243,16 @@ fn main_loop(
                         {
                             let state_mut_ref = state.as_mut().get_unchecked_mut();
                             state_mut_ref.chart.title = None;
-
-                            let mut font = raylib::Font::default();
-                            font.baseSize = clay::SCHEME.font_config.base_size;
-                            font.glyphCount = clay::SCHEME.font_config.glyph_count;
-                            font.glyphPadding = clay::SCHEME.font_config.glyph_padding;
-
-                            let font = clay::font::get_font(clay::SCHEME.font_data, &mut font).map_err(|s| AppError::InvalidFont(s))?;
-                            state_mut_ref.font = Some(font);
+                            load_fonts(state_mut_ref)?;
                         }
                     }
                 }
             }
 
+            if let Some(steps) = ui_zoom_keys() {
+                state.as_mut().get_unchecked_mut().zoom_ui(steps);
+            }
+
             cfg_if::cfg_if! {
                 if #[cfg(debug_assertions)] {
                     if raylib::IsKeyPressed(raylib::KeyboardKey_KEY_D as i32) {
@@ -196,6 +262,11 @@ fn main_loop(
             }
             raylib::EndDrawing();
         }
+        if let Some(path) = session_path {
+            if let Err(err) = session::save(&state, &path) {
+                eprint!("{err}");
+            }
+        }
         raylib::CloseWindow();
     }
 
@@ -214,24 +285,34 @@ fn clay_initialize<'s, 'm>(
 
     let arena = ffi::clay::ClayArena::new(memory);
     let ctx = ffi::clay::ClayContext::new(arena, dim, handler);
-    let font = state
-        .font
-        .as_mut()
-        .ok_or(AppError::InvalidFont("Font is not defined"))? as *mut raylib::Font
-        as *mut std::ffi::c_void;
+    if !state.fonts.is_loaded() {
+        return Err(AppError::InvalidFont("Font is not defined"));
+    }
+    let fonts = &mut state.fonts as *mut FontRegistry as *mut std::ffi::c_void;
 
     unsafe {
-        ffi::clay::Clay_SetMeasureTextFunction(state.measure, font);
+        ffi::clay::Clay_SetMeasureTextFunction(state.measure, fonts);
     };
 
     Ok(ctx)
 }
 
 fn main() -> Result<(), AppError> {
+    // Printed as they are rather than through the `Debug` of the returned error
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Help) => {
+            print!("{}", cli::HELP);
+            return Ok(());
+        }
+        Err(err) => {
+            eprint!("{err}");
+            std::process::exit(2);
+        }
+    };
+
     let mut state = State::default();
-    state.animation.duration = ANIMATION_DURATION;
     state.init();
-    state.font = Some(raylib::Font::default());
 
     let total_memory = unsafe { ffi::clay::Clay_MinMemorySize() };
     let mut mem = Vec::with_capacity(total_memory as usize);
@@ -266,5 +347,5 @@ fn main() -> Result<(), AppError> {
 
     // State is self-referential via field `menu`
     let pin = std::pin::pin!(state);
-    main_loop(&mut mem, pin, lib)
+    main_loop(&mut mem, pin, lib, options)
 }
@@ -0,0 +1,156 @@
+use crate::chart::ChartDataLine;
+use crate::math::{self, Distribution};
+
+const MAX_POINTS: usize = 4000; // Drawn points, the estimate keeps counting past them
+const MIN_TRIALS: u64 = 30; // Before that the normal approximation of the interval is meaningless
+const CHECKPOINT_GROWTH: f64 = 1.05; // Estimates are recorded at log spaced trial counts
+const Z_95: f32 = 1.959_964;
+
+pub const INTEGRAL_UPPER: f32 = 2.0; // exp(-x^2) is integrated over [0, INTEGRAL_UPPER]
+pub const BUFFON_LINES: f32 = 4.0; // Lines one needle length apart, needles are dropped on a square this wide
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Experiment {
+    #[default]
+    Pi, // Points in the unit square falling in the quarter circle
+    Integral, // Hit or miss under exp(-x^2)
+    Buffon,   // Needles crossing a line, the crossing probability is 2 / pi
+}
+
+impl Experiment {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pi => "Pi",
+            Self::Integral => "Integral",
+            Self::Buffon => "Buffon's needle",
+        }
+    }
+
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            Self::Pi => Some(Self::Integral),
+            Self::Integral => Some(Self::Buffon),
+            Self::Buffon => None,
+        }
+    }
+
+    pub fn exact(&self) -> f32 {
+        match self {
+            Self::Pi | Self::Buffon => std::f32::consts::PI,
+            Self::Integral => {
+                // The integral of exp(-x^2) is sqrt(pi) times a Normal probability with variance 1/2
+                let upper = f64::from(INTEGRAL_UPPER) * std::f64::consts::SQRT_2;
+                (std::f64::consts::PI.sqrt() * (math::normal_cdf(upper) - 0.5)) as f32
+            }
+        }
+    }
+
+    pub fn integrand(x: f32) -> f32 {
+        (-x * x).exp()
+    }
+
+    // Maps the proportion of hits to the quantity being estimated, monotonic so intervals map too
+    fn estimate(&self, p: f32) -> f32 {
+        match self {
+            Self::Pi => 4.0 * p,
+            Self::Integral => INTEGRAL_UPPER * p,
+            Self::Buffon => 2.0 / p,
+        }
+    }
+}
+
+// A trial, for the needle experiment the segment between (x0, y0) and (x1, y1), otherwise the point (x0, y0)
+#[derive(Debug, Clone, Copy)]
+pub struct Trial {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+    pub hit: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+    pub experiment: Experiment,
+    pub running: bool,
+    pub trials: Vec<Trial>,
+    pub count: u64,
+    pub hits: u64,
+    pub estimate: ChartDataLine, // Against the number of trials
+    pub lower: ChartDataLine,
+    pub upper: ChartDataLine,
+    checkpoint: f64,
+    rng: math::Uniform,
+}
+
+impl MonteCarlo {
+    pub fn new(experiment: Experiment, seed: u64) -> Self {
+        Self {
+            experiment,
+            running: true,
+            trials: Vec::with_capacity(MAX_POINTS),
+            count: 0,
+            hits: 0,
+            estimate: ChartDataLine::default(),
+            lower: ChartDataLine::default(),
+            upper: ChartDataLine::default(),
+            checkpoint: MIN_TRIALS as f64,
+            rng: math::Uniform::new(seed),
+        }
+    }
+
+    fn trial(&mut self) -> Trial {
+        let (u, v) = (self.rng.sample(), self.rng.sample());
+        match self.experiment {
+            Experiment::Pi => Trial { x0: u, y0: v, x1: u, y1: v, hit: u * u + v * v <= 1.0 },
+            Experiment::Integral => {
+                let x = u * INTEGRAL_UPPER;
+                Trial { x0: x, y0: v, x1: x, y1: v, hit: v <= Experiment::integrand(x) }
+            }
+            Experiment::Buffon => {
+                let angle = self.rng.sample() * std::f32::consts::PI;
+                let (cx, cy) = (u * BUFFON_LINES, v * BUFFON_LINES);
+                let (dx, dy) = (0.5 * angle.cos(), 0.5 * angle.sin());
+                let (y0, y1) = (cy - dy, cy + dy);
+                Trial { x0: cx - dx, y0, x1: cx + dx, y1, hit: y0.floor() != y1.floor() }
+            }
+        }
+    }
+
+    pub fn step(&mut self, batch: usize) {
+        for _ in 0..batch {
+            let trial = self.trial();
+            self.count += 1;
+            self.hits += u64::from(trial.hit);
+            if self.trials.len() < MAX_POINTS {
+                self.trials.push(trial);
+            }
+        }
+
+        if (self.count as f64) >= self.checkpoint {
+            if let Some((estimate, lower, upper)) = self.interval() {
+                let n = self.count as f32;
+                self.estimate.push(n, estimate);
+                self.lower.push(n, lower);
+                self.upper.push(n, upper);
+            }
+            self.checkpoint = (self.count as f64) * CHECKPOINT_GROWTH;
+        }
+    }
+
+    // Estimate with its 95% interval, from the normal approximation of the proportion of hits
+    pub fn interval(&self) -> Option<(f32, f32, f32)> {
+        if self.count < MIN_TRIALS || self.hits == 0 {
+            return None;
+        }
+
+        let p = (self.hits as f32) / (self.count as f32);
+        let half = Z_95 * (p * (1.0 - p) / (self.count as f32)).sqrt();
+        let (a, b) = (self.experiment.estimate(p - half), self.experiment.estimate(p + half));
+        let (lower, upper) = (a.min(b), a.max(b));
+        if !lower.is_finite() || !upper.is_finite() || p - half <= 0.0 {
+            return Some((self.experiment.estimate(p), f32::NAN, f32::NAN));
+        }
+        Some((self.experiment.estimate(p), lower, upper))
+    }
+}
@@ -0,0 +1,129 @@
+use crate::chart::{ChartDataHistogram, ChartDataLine};
+use crate::math::{self, Distribution};
+
+pub const STEPS: usize = 200;
+const HORIZON: f32 = 1.0; // Length of the continuous time processes
+const FINAL_BINS: u16 = 30;
+
+const GBM_START: f32 = 1.0;
+const GBM_DRIFT: f32 = 0.5;
+const GBM_VOLATILITY: f32 = 0.4;
+
+const OU_START: f32 = 2.0;
+const OU_MEAN: f32 = 0.0;
+const OU_REVERSION: f32 = 4.0;
+const OU_VOLATILITY: f32 = 1.0;
+
+const POISSON_RATE: f32 = 20.0;
+
+// Stochastic processes drawn as sample paths over time
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Process {
+    #[default]
+    RandomWalk, // Steps are draws of the active distribution, centred on its mean
+    Brownian,
+    GeometricBrownian,
+    OrnsteinUhlenbeck,
+    Poisson,
+}
+
+impl Process {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::RandomWalk => "Random walk",
+            Self::Brownian => "Brownian",
+            Self::GeometricBrownian => "Geometric BM",
+            Self::OrnsteinUhlenbeck => "Ornstein-Uhlenbeck",
+            Self::Poisson => "Poisson",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::RandomWalk => Self::Brownian,
+            Self::Brownian => Self::GeometricBrownian,
+            Self::GeometricBrownian => Self::OrnsteinUhlenbeck,
+            Self::OrnsteinUhlenbeck => Self::Poisson,
+            Self::Poisson => Self::RandomWalk,
+        }
+    }
+
+    // Fills one line per path, reusing the buffers of `output`, and the histogram of where the paths end.
+    // The random walk takes its steps from `steps`, the others from generators seeded with `seed`
+    pub fn simulate<R>(
+        &self,
+        steps: &mut R,
+        seed: u64,
+        count: usize,
+        output: &mut Vec<ChartDataLine>,
+        finals: &mut ChartDataHistogram,
+    ) where
+        R: Distribution<Value = f32>,
+    {
+        output.resize_with(count, ChartDataLine::default);
+        let mut normal = math::Normal::new(seed);
+        let mut arrivals = math::Exponential::new(seed, POISSON_RATE);
+
+        let dt = HORIZON / (STEPS as f32);
+        let centre = steps.mean();
+        for path in output.iter_mut() {
+            path.clear();
+            match self {
+                Self::RandomWalk => {
+                    let mut x = 0.0;
+                    path.push(0.0, x);
+                    for step in 1..=STEPS {
+                        x += steps.sample() - centre;
+                        path.push(step as f32, x);
+                    }
+                }
+                Self::Brownian => {
+                    let mut x = 0.0;
+                    path.push(0.0, x);
+                    for step in 1..=STEPS {
+                        x += dt.sqrt() * normal.sample();
+                        path.push((step as f32) * dt, x);
+                    }
+                }
+                Self::GeometricBrownian => {
+                    // Exact solution, so the path never goes negative whatever the step size
+                    let mut w = 0.0;
+                    path.push(0.0, GBM_START);
+                    for step in 1..=STEPS {
+                        let t = (step as f32) * dt;
+                        w += dt.sqrt() * normal.sample();
+                        let drift = (GBM_DRIFT - 0.5 * GBM_VOLATILITY * GBM_VOLATILITY) * t;
+                        path.push(t, GBM_START * (drift + GBM_VOLATILITY * w).exp());
+                    }
+                }
+                Self::OrnsteinUhlenbeck => {
+                    let mut x = OU_START;
+                    path.push(0.0, x);
+                    for step in 1..=STEPS {
+                        x += OU_REVERSION * (OU_MEAN - x) * dt + OU_VOLATILITY * dt.sqrt() * normal.sample();
+                        path.push((step as f32) * dt, x);
+                    }
+                }
+                Self::Poisson => {
+                    // Counts the exponential inter-arrival times falling before each grid point
+                    let mut next = arrivals.sample();
+                    let mut events = 0.0;
+                    path.push(0.0, events);
+                    for step in 1..=STEPS {
+                        let t = (step as f32) * dt;
+                        while next <= t {
+                            events += 1.0;
+                            next += arrivals.sample();
+                        }
+                        path.push(t, events);
+                    }
+                }
+            }
+        }
+
+        let ends: Vec<f32> = output.iter().filter_map(|path| path.y.last().copied()).collect();
+        math::cut(&ends, FINAL_BINS, &mut finals.data);
+        finals.min = ends.iter().copied().fold(f32::MAX, f32::min);
+        finals.max = ends.iter().copied().fold(f32::MIN, f32::max);
+    }
+}
@@ -0,0 +1,441 @@
+use crate::math::Distribution;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SummaryStats {
+    pub mean: f32,
+    pub variance: f32,
+    pub std_dev: f32,
+    pub skewness: f32,
+    pub kurtosis: f32,
+    pub min: f32,
+    pub max: f32,
+    pub median: f32,
+    pub q1: f32,
+    pub q3: f32,
+}
+
+impl Default for SummaryStats {
+    fn default() -> Self {
+        Self {
+            mean: f32::NAN,
+            variance: f32::NAN,
+            std_dev: f32::NAN,
+            skewness: f32::NAN,
+            kurtosis: f32::NAN,
+            min: f32::NAN,
+            max: f32::NAN,
+            median: f32::NAN,
+            q1: f32::NAN,
+            q3: f32::NAN,
+        }
+    }
+}
+
+impl SummaryStats {
+    // Moments of the mean of `n` independent draws, the quartiles are those of the limiting Normal
+    pub fn of_mean(mut self, n: usize) -> Self {
+        const Q3_Z: f32 = 0.674_489_8;
+
+        let n = n.max(1) as f32;
+        self.variance /= n;
+        self.std_dev = self.variance.sqrt();
+        self.skewness /= n.sqrt();
+        self.kurtosis /= n;
+        if n > 1.0 {
+            self.median = self.mean;
+            self.q1 = self.mean - Q3_Z * self.std_dev;
+            self.q3 = self.mean + Q3_Z * self.std_dev;
+        }
+        self
+    }
+
+    // Uses `sorted` as scratch space to compute the order statistics, so its previous content is lost
+    pub fn from_sample(sample: &[f32], sorted: &mut Vec<f32>) -> Self {
+        let mut output = Self::default();
+        if sample.is_empty() {
+            sorted.clear();
+            return output;
+        }
+
+        // Accumulating in f64 as the samples can be large enough for f32 sums to drift
+        let n = sample.len() as f64;
+        let mean = sample.iter().map(|x| *x as f64).sum::<f64>() / n;
+
+        let mut m2 = 0.0;
+        let mut m3 = 0.0;
+        let mut m4 = 0.0;
+        for val in sample.iter() {
+            let d = (*val as f64) - mean;
+            let d2 = d * d;
+            m2 += d2;
+            m3 += d2 * d;
+            m4 += d2 * d2;
+        }
+        m2 /= n;
+        m3 /= n;
+        m4 /= n;
+
+        output.mean = mean as f32;
+        output.variance = if sample.len() > 1 {
+            (m2 * n / (n - 1.0)) as f32
+        } else {
+            0.0
+        };
+        output.std_dev = output.variance.sqrt();
+        if m2 > 0.0 {
+            output.skewness = (m3 / m2.powf(1.5)) as f32;
+            output.kurtosis = (m4 / (m2 * m2) - 3.0) as f32;
+        }
+
+        sorted.clear();
+        sorted.extend_from_slice(sample);
+        sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+
+        output.min = sorted[0];
+        output.max = sorted[sorted.len() - 1];
+        output.q1 = quantile_sorted(sorted, 0.25);
+        output.median = quantile_sorted(sorted, 0.5);
+        output.q3 = quantile_sorted(sorted, 0.75);
+        output
+    }
+
+    pub fn from_distribution<R: Distribution>(dist: &R) -> Self {
+        let mut output = Self::default();
+        let (min, max) = dist.support();
+        output.mean = dist.mean();
+        output.variance = dist.variance();
+        output.std_dev = output.variance.sqrt();
+        output.skewness = dist.skewness();
+        output.kurtosis = dist.kurtosis();
+        output.min = min;
+        output.max = max;
+        output.q1 = dist.quantile(0.25);
+        output.median = dist.quantile(0.5);
+        output.q3 = dist.quantile(0.75);
+        output
+    }
+}
+
+// Moments of values seen one at a time, Welford's update extended to the fourth moment. Order statistics
+// need the whole sample, so the quartiles are left for the caller
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunningStats {
+    pub count: u64,
+    pub mean: f64,
+    m2: f64,
+    m3: f64,
+    m4: f64,
+    min: f32,
+    max: f32,
+}
+
+impl RunningStats {
+    pub fn push(&mut self, x: f32) {
+        if self.count == 0 {
+            self.min = x;
+            self.max = x;
+        }
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+
+        let before = self.count as f64;
+        self.count += 1;
+        let n = self.count as f64;
+        let delta = f64::from(x) - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term = delta * delta_n * before;
+        self.mean += delta_n;
+        self.m4 += term * delta_n2 * (n * n - 3.0 * n + 3.0) + 6.0 * delta_n2 * self.m2 - 4.0 * delta_n * self.m3;
+        self.m3 += term * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term;
+    }
+
+    // Same definitions as `SummaryStats::from_sample`
+    pub fn summary(&self) -> SummaryStats {
+        let mut output = SummaryStats::default();
+        if self.count == 0 {
+            return output;
+        }
+
+        let n = self.count as f64;
+        output.mean = self.mean as f32;
+        output.variance = if self.count > 1 {
+            (self.m2 / (n - 1.0)) as f32
+        } else {
+            0.0
+        };
+        output.std_dev = output.variance.sqrt();
+        if self.m2 > 0.0 {
+            let m2 = self.m2 / n;
+            output.skewness = (self.m3 / n / m2.powf(1.5)) as f32;
+            output.kurtosis = (self.m4 / n / (m2 * m2) - 3.0) as f32;
+        }
+        output.min = self.min;
+        output.max = self.max;
+        output
+    }
+}
+
+// Pearson's sample correlation, NaN when either variable is constant
+pub fn correlation(x: &[f32], y: &[f32]) -> f32 {
+    let n = x.len().min(y.len());
+    if n < 2 {
+        return f32::NAN;
+    }
+
+    let mean_x = x[..n].iter().map(|v| f64::from(*v)).sum::<f64>() / (n as f64);
+    let mean_y = y[..n].iter().map(|v| f64::from(*v)).sum::<f64>() / (n as f64);
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (xv, yv) in x.iter().zip(y.iter()) {
+        let (dx, dy) = (f64::from(*xv) - mean_x, f64::from(*yv) - mean_y);
+        sxy += dx * dy;
+        sxx += dx * dx;
+        syy += dy * dy;
+    }
+    (sxy / (sxx * syy).sqrt()) as f32
+}
+
+// Linear interpolation between the closest ranks, i.e. R's default (type 7) quantile
+pub fn quantile_sorted(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return f32::NAN;
+    }
+
+    let h = (sorted.len() - 1) as f32 * p.clamp(0.0, 1.0);
+    let lo = h.floor() as usize;
+    let hi = h.ceil() as usize;
+    sorted[lo] + (h - lo as f32) * (sorted[hi] - sorted[lo])
+}
+
+// Sample quantiles (y) against the quantiles of the reference distribution (x) at evenly spaced probabilities
+pub fn qq<R: Distribution>(sorted: &[f32], reference: &R, points: usize, x: &mut Vec<f32>, y: &mut Vec<f32>) {
+    x.clear();
+    y.clear();
+    if sorted.is_empty() {
+        return;
+    }
+
+    for i in 0..points {
+        let p = ((i as f32) + 0.5) / (points as f32);
+        x.push(reference.quantile(p));
+        y.push(quantile_sorted(sorted, p));
+    }
+}
+
+// Empirical probabilities (y) against the reference cdf evaluated at the matching sample quantiles (x)
+pub fn pp<R: Distribution>(sorted: &[f32], reference: &R, points: usize, x: &mut Vec<f32>, y: &mut Vec<f32>) {
+    x.clear();
+    y.clear();
+    if sorted.is_empty() {
+        return;
+    }
+
+    for i in 0..points {
+        let p = ((i as f32) + 0.5) / (points as f32);
+        x.push(reference.cdf(quantile_sorted(sorted, p)));
+        y.push(p);
+    }
+}
+
+// Tukey's whiskers: the most extreme observations that still lie within 1.5 IQR of the box
+pub fn tukey_whiskers(sorted: &[f32], q1: f32, q3: f32) -> (f32, f32) {
+    let iqr = q3 - q1;
+    let lo_fence = q1 - 1.5 * iqr;
+    let hi_fence = q3 + 1.5 * iqr;
+
+    let lower = sorted.iter().find(|x| **x >= lo_fence).copied().unwrap_or(q1);
+    let upper = sorted.iter().rev().find(|x| **x <= hi_fence).copied().unwrap_or(q3);
+    (lower, upper)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Kernel {
+    #[default]
+    Gaussian,
+    Epanechnikov,
+}
+
+impl Kernel {
+    pub fn weight(&self, u: f32) -> f32 {
+        match self {
+            Self::Gaussian => (-0.5 * u * u).exp() / (2.0 * std::f32::consts::PI).sqrt(),
+            Self::Epanechnikov => {
+                if u.abs() > 1.0 {
+                    0.0
+                } else {
+                    0.75 * (1.0 - u * u)
+                }
+            }
+        }
+    }
+
+    // Number of bandwidths after which the kernel is zero (or negligible in the Gaussian case)
+    pub fn support(&self) -> f32 {
+        match self {
+            Self::Gaussian => 4.0,
+            Self::Epanechnikov => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Bandwidth {
+    #[default]
+    Silverman,
+    Manual(f32),
+}
+
+impl Bandwidth {
+    pub fn value(&self, stats: &SummaryStats, size: usize) -> f32 {
+        match self {
+            Self::Silverman => silverman_bandwidth(stats, size),
+            Self::Manual(h) => *h,
+        }
+    }
+}
+
+pub fn silverman_bandwidth(stats: &SummaryStats, size: usize) -> f32 {
+    let iqr = stats.q3 - stats.q1;
+    let mut spread = stats.std_dev;
+    if iqr > 0.0 {
+        spread = spread.min(iqr / 1.34);
+    }
+
+    0.9 * spread * (size.max(1) as f32).powf(-0.2)
+}
+
+// Binned KDE: the sample is linearly binned onto an evenly spaced grid, which is then convolved with the
+// kernel. The cost is O(n + grid * kernel width) instead of O(n * grid), so it copes with large samples.
+pub fn kde(sample: &[f32], kernel: Kernel, bandwidth: f32, grid: usize, x: &mut Vec<f32>, y: &mut Vec<f32>) {
+    x.clear();
+    y.clear();
+    if sample.is_empty() || grid < 2 || bandwidth <= 0.0 || !bandwidth.is_finite() {
+        return;
+    }
+
+    let mut min = f32::MAX;
+    let mut max = f32::MIN;
+    for val in sample.iter() {
+        min = min.min(*val);
+        max = max.max(*val);
+    }
+
+    let reach = kernel.support() * bandwidth;
+    let lo = min - reach;
+    let hi = max + reach;
+    let step = (hi - lo) / ((grid - 1) as f32);
+
+    let mut counts = vec![0.0f64; grid];
+    for val in sample.iter() {
+        let pos = (*val - lo) / step;
+        let idx = (pos.floor() as usize).min(grid - 2);
+        let frac = (pos - idx as f32).clamp(0.0, 1.0) as f64;
+        counts[idx] += 1.0 - frac;
+        counts[idx + 1] += frac;
+    }
+
+    let half_width = ((reach / step).ceil() as usize).min(grid - 1);
+    let norm = (sample.len() as f64) * (bandwidth as f64);
+    let weights: Vec<f64> = (0..=half_width)
+        .map(|k| kernel.weight((k as f32) * step / bandwidth) as f64 / norm)
+        .collect();
+
+    for j in 0..grid {
+        let bgn = j.saturating_sub(half_width);
+        let end = (j + half_width).min(grid - 1);
+        let mut density = 0.0;
+        for (i, count) in counts.iter().enumerate().take(end + 1).skip(bgn) {
+            density += count * weights[i.abs_diff(j)];
+        }
+
+        x.push(lo + (j as f32) * step);
+        y.push(density as f32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trapezoidal rule over the points of the curve
+    fn area(x: &[f32], y: &[f32]) -> f32 {
+        x.windows(2)
+            .zip(y.windows(2))
+            .map(|(x, y)| 0.5 * (x[1] - x[0]) * (y[0] + y[1]))
+            .sum()
+    }
+
+    #[test]
+    fn qq_and_pp_follow_the_diagonal_for_the_reference_itself() {
+        let reference = crate::math::Normal::new(1);
+        let sorted: Vec<f32> = (0..10_000)
+            .map(|i| reference.quantile((i as f32 + 0.5) / 10_000.0))
+            .collect();
+        let (mut x, mut y) = (Vec::new(), Vec::new());
+
+        qq(&sorted, &reference, 50, &mut x, &mut y);
+        assert_eq!(x.len(), 50);
+        assert!(x.iter().zip(&y).all(|(x, y)| (x - y).abs() < 1e-2));
+
+        pp(&sorted, &reference, 50, &mut x, &mut y);
+        assert_eq!(y.len(), 50);
+        assert!(x.iter().zip(&y).all(|(x, y)| (x - y).abs() < 1e-3));
+    }
+
+    #[test]
+    fn empty_sample_leaves_no_stale_order_statistics() {
+        let mut sorted = Vec::new();
+        SummaryStats::from_sample(&[3.0, 1.0, 2.0], &mut sorted);
+        assert_eq!(sorted, vec![1.0, 2.0, 3.0]);
+        assert!(SummaryStats::from_sample(&[], &mut sorted).mean.is_nan());
+        assert!(sorted.is_empty());
+    }
+
+    #[test]
+    fn running_moments_match_the_whole_sample() {
+        let sample = [2.0, -1.5, 7.25, 0.0, 3.0, 3.0, 11.0, -4.0];
+        let mut running = RunningStats::default();
+        for x in sample {
+            running.push(x);
+        }
+        let running = running.summary();
+        let whole = SummaryStats::from_sample(&sample, &mut Vec::new());
+        let pairs = [
+            (running.mean, whole.mean),
+            (running.variance, whole.variance),
+            (running.skewness, whole.skewness),
+            (running.kurtosis, whole.kurtosis),
+            (running.min, whole.min),
+            (running.max, whole.max),
+        ];
+        for (got, want) in pairs {
+            assert!((got - want).abs() < 1e-4, "{} != {}", got, want);
+        }
+        assert!(running.median.is_nan());
+        assert!(RunningStats::default().summary().mean.is_nan());
+    }
+
+    #[test]
+    fn silverman_bandwidth_matches_r() {
+        // bw.nrd0(1:10) in R
+        let sample: Vec<f32> = (1..=10).map(|i| i as f32).collect();
+        let stats = SummaryStats::from_sample(&sample, &mut Vec::new());
+        assert!((silverman_bandwidth(&stats, sample.len()) - 1.719_3).abs() < 1e-3);
+    }
+
+    #[test]
+    fn kde_integrates_to_one() {
+        let sample: Vec<f32> = (0..2000)
+            .map(|i| (i as f32 * 0.37).sin() * 3.0 + (i % 7) as f32)
+            .collect();
+        let stats = SummaryStats::from_sample(&sample, &mut Vec::new());
+        let bandwidth = silverman_bandwidth(&stats, sample.len());
+        for kernel in [Kernel::Gaussian, Kernel::Epanechnikov] {
+            let (mut x, mut y) = (Vec::new(), Vec::new());
+            kde(&sample, kernel, bandwidth, 512, &mut x, &mut y);
+            assert_eq!(x.len(), 512);
+            assert!((area(&x, &y) - 1.0).abs() < 1e-2, "{:?} integrates to {}", kernel, area(&x, &y));
+        }
+    }
+}
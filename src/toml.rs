@@ -0,0 +1,336 @@
+// The subset of TOML used by the configuration files: `[table]` headers, `key = value` pairs with dotted
+// keys, basic strings, integers, floats, booleans and single line arrays of those
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::String(_) => "a string",
+            Self::Integer(_) => "an integer",
+            Self::Float(_) => "a float",
+            Self::Boolean(_) => "a boolean",
+            Self::Array(_) => "an array",
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    // Integers are accepted where a float is expected, `height = 50` reads better than `height = 50.0`
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Float(f) => Some(*f),
+            Self::Integer(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+// Both 1-based, columns count characters rather than bytes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Error {
+    pub position: Position,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(position: Position, message: impl Into<String>) -> Self {
+        Self { position, message: message.into() }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.position.line, self.position.column, self.message)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub key: String, // Full dotted path, including the table
+    pub value: Value,
+    pub position: Position, // Of the value, where errors about it are reported
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Document {
+    pub entries: Vec<Entry>, // In file order
+}
+
+impl Document {
+    pub fn parse(src: &str) -> Result<Self, Error> {
+        let mut document = Self::default();
+        let mut table = String::new();
+        for (idx, line) in src.lines().enumerate() {
+            let mut cursor = Cursor { line, offset: 0, number: idx + 1 };
+            cursor.skip_whitespace();
+            match cursor.peek() {
+                None | Some('#') => continue,
+                Some('[') => {
+                    cursor.bump();
+                    if cursor.peek() == Some('[') {
+                        return Err(cursor.error("arrays of tables are not supported"));
+                    }
+                    table = cursor.key()?;
+                    cursor.skip_whitespace();
+                    cursor.expect(']')?;
+                }
+                Some(_) => {
+                    let start = cursor.position();
+                    let key = cursor.key()?;
+                    let key = if table.is_empty() {
+                        key
+                    } else {
+                        format!("{table}.{key}")
+                    };
+                    if document.get(&key).is_some() {
+                        return Err(Error::new(start, format!("duplicate key `{key}`")));
+                    }
+                    cursor.skip_whitespace();
+                    cursor.expect('=')?;
+                    cursor.skip_whitespace();
+                    let position = cursor.position();
+                    let value = cursor.value()?;
+                    document.entries.push(Entry { key, value, position });
+                }
+            }
+            cursor.end()?;
+        }
+        Ok(document)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.key == key)
+    }
+}
+
+struct Cursor<'a> {
+    line: &'a str,
+    offset: usize, // In bytes
+    number: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&self) -> Option<char> {
+        self.line[self.offset..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+        Some(c)
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            line: self.number,
+            column: self.line[..self.offset].chars().count() + 1,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> Error {
+        Error::new(self.position(), message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("expected `{expected}`, found `{c}`"))),
+            None => Err(self.error(format!("expected `{expected}`, found the end of the line"))),
+        }
+    }
+
+    // Only whitespace or a comment may follow a value or a table header
+    fn end(&mut self) -> Result<(), Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            None | Some('#') => Ok(()),
+            Some(c) => Err(self.error(format!("expected the end of the line, found `{c}`"))),
+        }
+    }
+
+    fn key(&mut self) -> Result<String, Error> {
+        let mut key = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('"') => key.push_str(&self.string()?),
+                Some(c) if is_bare_key(c) => {
+                    while let Some(c) = self.peek().filter(|c| is_bare_key(*c)) {
+                        key.push(c);
+                        self.bump();
+                    }
+                }
+                _ => return Err(self.error("expected a key")),
+            }
+            self.skip_whitespace();
+            if self.peek() != Some('.') {
+                return Ok(key);
+            }
+            self.bump();
+            key.push('.');
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        match self.peek() {
+            Some('"') => self.string().map(Value::String),
+            Some('[') => self.array(),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let start = self.position();
+                let word = self.take_while(|c| c.is_ascii_alphanumeric());
+                match word {
+                    "true" => Ok(Value::Boolean(true)),
+                    "false" => Ok(Value::Boolean(false)),
+                    "inf" => Ok(Value::Float(f64::INFINITY)),
+                    "nan" => Ok(Value::Float(f64::NAN)),
+                    _ => Err(Error::new(start, format!("unexpected `{word}`, strings must be quoted"))),
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '+' || c == '-' || c == '.' => self.number(),
+            Some(c) => Err(self.error(format!("expected a value, found `{c}`"))),
+            None => Err(self.error("expected a value, found the end of the line")),
+        }
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &str {
+        let start = self.offset;
+        while self.peek().is_some_and(&pred) {
+            self.bump();
+        }
+        &self.line[start..self.offset]
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.position();
+        let text = self
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | '_'))
+            .replace('_', "");
+        if let Ok(integer) = text.parse::<i64>() {
+            return Ok(Value::Integer(integer));
+        }
+        match text.trim_start_matches('+').parse::<f64>() {
+            Ok(float) => Ok(Value::Float(float)),
+            Err(_) => Err(Error::new(start, format!("invalid number `{text}`"))),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            let position = self.position();
+            match self.bump() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(out),
+                Some('\\') => match self.bump() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    Some(c) => return Err(Error::new(position, format!("unsupported escape `\\{c}`"))),
+                    None => return Err(self.error("unterminated string")),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(Value::Array(values));
+            }
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(']') => {}
+                Some(c) => return Err(self.error(format!("expected `,` or `]`, found `{c}`"))),
+                None => return Err(self.error("arrays must be closed on the line they start")),
+            }
+        }
+    }
+}
+
+fn is_bare_key(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(src: &str) -> (usize, usize) {
+        let err = Document::parse(src).unwrap_err();
+        (err.position.line, err.position.column)
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_character() {
+        assert_eq!(error_at("a = 1\nb = 2 3\n"), (2, 7));
+        assert_eq!(error_at("# comment\n\n[table]\nx = yes\n"), (4, 5));
+        assert_eq!(error_at("a = 1\na = 2\n"), (2, 1));
+        assert_eq!(error_at("x = [1, 2\n"), (1, 10));
+        assert_eq!(error_at("[[tables]]\n"), (1, 2));
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        assert_eq!(error_at("  s = \"é\\q\"\n"), (1, 9));
+    }
+}
@@ -0,0 +1,201 @@
+use super::palette::Prompt;
+use super::{consts, State};
+use crate::chart::ChartKind;
+
+// Everything the header and sidebar buttons do that makes sense on its own, shared by their handlers, the
+// keyboard shortcuts and the command palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Simulate,
+    ToggleStream,
+    Chart(ChartKind),
+    Distribution(&'static str), // One of `consts::DISTRIBUTIONS`
+    Kde(&'static str),          // One of `consts::KDE_KERNELS`
+    NarrowerBandwidth,
+    WiderBandwidth,
+    ResetBandwidth,
+    Reference(&'static str), // One of `consts::REFERENCES`
+    CycleXScale,
+    CycleYScale,
+    CycleEasing,
+    CycleReveal,
+    CycleProcess,
+    CyclePathCount,
+    CycleExperiment,
+    ToggleExperiment,
+    AddSeries,
+    ToggleSmallMultiples,
+    ToggleClt,
+    ToggleStats,
+    CycleTheme,
+    ToggleSidebar,
+    ToggleHelp,
+    CommandPalette,
+    SaveSession,
+    OpenSession,
+    Export,
+    ResetView,
+    Reset,
+    Exit,
+}
+
+impl Action {
+    // In the order they are listed to the user
+    pub fn all() -> impl Iterator<Item = Self> {
+        let charts = ChartKind::ALL.iter().copied().map(Self::Chart);
+        let distributions = consts::DISTRIBUTIONS.iter().copied().map(Self::Distribution);
+        let kernels = consts::KDE_KERNELS.iter().copied().map(Self::Kde);
+        let references = consts::REFERENCES.iter().copied().map(Self::Reference);
+        [Self::Simulate, Self::ToggleStream]
+            .iter()
+            .copied()
+            .chain(charts)
+            .chain(distributions)
+            .chain(kernels)
+            .chain([Self::NarrowerBandwidth, Self::WiderBandwidth, Self::ResetBandwidth])
+            .chain(references)
+            .chain([
+                Self::CycleXScale,
+                Self::CycleYScale,
+                Self::CycleEasing,
+                Self::CycleReveal,
+                Self::CycleProcess,
+                Self::CyclePathCount,
+                Self::CycleExperiment,
+                Self::ToggleExperiment,
+                Self::AddSeries,
+                Self::ToggleSmallMultiples,
+                Self::ToggleClt,
+                Self::ToggleStats,
+                Self::CycleTheme,
+                Self::ToggleSidebar,
+                Self::ToggleHelp,
+                Self::CommandPalette,
+                Self::SaveSession,
+                Self::OpenSession,
+                Self::Export,
+                Self::ResetView,
+                Self::Reset,
+                Self::Exit,
+            ])
+    }
+
+    // Name in the configuration files
+    pub fn key(&self) -> String {
+        match self {
+            Self::Simulate => "simulate".to_string(),
+            Self::ToggleStream => "stream".to_string(),
+            Self::Chart(kind) => format!("chart.{}", kind.key()),
+            Self::Distribution(name) => format!("distribution.{}", name.to_lowercase()),
+            Self::Kde(name) if *name == consts::DROP_DOWN_KDE_NONE => "kde.none".to_string(),
+            Self::Kde(name) => format!("kde.{}", name.to_lowercase()),
+            Self::NarrowerBandwidth => "bandwidth.narrower".to_string(),
+            Self::WiderBandwidth => "bandwidth.wider".to_string(),
+            Self::ResetBandwidth => "bandwidth.reset".to_string(),
+            Self::Reference(name) => format!("reference.{}", reference_name(name).to_lowercase()),
+            Self::CycleXScale => "scale.x".to_string(),
+            Self::CycleYScale => "scale.y".to_string(),
+            Self::CycleEasing => "easing".to_string(),
+            Self::CycleReveal => "reveal".to_string(),
+            Self::CycleProcess => "process".to_string(),
+            Self::CyclePathCount => "paths".to_string(),
+            Self::CycleExperiment => "monte_carlo.next".to_string(),
+            Self::ToggleExperiment => "monte_carlo.run".to_string(),
+            Self::AddSeries => "add_series".to_string(),
+            Self::ToggleSmallMultiples => "small_multiples".to_string(),
+            Self::ToggleClt => "clt".to_string(),
+            Self::ToggleStats => "stats".to_string(),
+            Self::CycleTheme => "theme".to_string(),
+            Self::ToggleSidebar => "sidebar".to_string(),
+            Self::ToggleHelp => "help".to_string(),
+            Self::CommandPalette => "palette".to_string(),
+            Self::SaveSession => "session.save".to_string(),
+            Self::OpenSession => "session.open".to_string(),
+            Self::Export => "export".to_string(),
+            Self::ResetView => "reset_view".to_string(),
+            Self::Reset => "reset".to_string(),
+            Self::Exit => "exit".to_string(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Simulate => "Simulate".to_string(),
+            Self::ToggleStream => "Start or stop streaming".to_string(),
+            Self::Chart(kind) => format!("Chart: {}", kind.name()),
+            Self::Distribution(name) => format!("Distribution: {name}"),
+            Self::Kde(name) if *name == consts::DROP_DOWN_KDE_NONE => "KDE: off".to_string(),
+            Self::Kde(name) => format!("KDE: {name}"),
+            Self::NarrowerBandwidth => "Narrower KDE bandwidth".to_string(),
+            Self::WiderBandwidth => "Wider KDE bandwidth".to_string(),
+            Self::ResetBandwidth => "Reset the KDE bandwidth".to_string(),
+            Self::Reference(name) if *name == consts::DROP_DOWN_REF_ACTIVE => {
+                "Reference: the active distribution".to_string()
+            }
+            Self::Reference(name) => format!("Reference: {}", reference_name(name)),
+            Self::CycleXScale => "Next x axis scale".to_string(),
+            Self::CycleYScale => "Next y axis scale".to_string(),
+            Self::CycleEasing => "Next animation easing".to_string(),
+            Self::CycleReveal => "Next animation reveal".to_string(),
+            Self::CycleProcess => "Next sample path process".to_string(),
+            Self::CyclePathCount => "Next number of sample paths".to_string(),
+            Self::CycleExperiment => "Next Monte Carlo experiment".to_string(),
+            Self::ToggleExperiment => "Start or pause the Monte Carlo experiment".to_string(),
+            Self::AddSeries => "Add a series".to_string(),
+            Self::ToggleSmallMultiples => "Overlay or small multiples".to_string(),
+            Self::ToggleClt => "Central limit theorem mode".to_string(),
+            Self::ToggleStats => "Show or hide the statistics".to_string(),
+            Self::CycleTheme => "Next theme".to_string(),
+            Self::ToggleSidebar => "Show or hide the sidebar".to_string(),
+            Self::ToggleHelp => "Show or hide the shortcuts".to_string(),
+            Self::CommandPalette => "Command palette".to_string(),
+            Self::SaveSession => "Save session as…".to_string(),
+            Self::OpenSession => "Open session…".to_string(),
+            Self::Export => "Export the sample as CSV…".to_string(),
+            Self::ResetView => "Reset the chart view".to_string(),
+            Self::Reset => "Reset".to_string(),
+            Self::Exit => "Exit".to_string(),
+        }
+    }
+
+    pub fn perform(&self, state: &mut State) {
+        match self {
+            Self::Simulate => state.simulate(),
+            Self::ToggleStream => state.toggle_stream(),
+            Self::Chart(kind) => state.select_chart_kind(*kind),
+            Self::Distribution(name) => state.select_distribution(name),
+            Self::Kde(name) => state.select_kde(name),
+            Self::NarrowerBandwidth => state.scale_bandwidth(0.8),
+            Self::WiderBandwidth => state.scale_bandwidth(1.25),
+            Self::ResetBandwidth => state.reset_bandwidth(),
+            Self::Reference(name) => state.select_reference(name),
+            Self::CycleXScale => state.cycle_xscale(),
+            Self::CycleYScale => state.cycle_yscale(),
+            Self::CycleEasing => state.cycle_easing(),
+            Self::CycleReveal => state.cycle_reveal(),
+            Self::CycleProcess => state.cycle_process(),
+            Self::CyclePathCount => state.cycle_path_count(),
+            Self::CycleExperiment => state.cycle_experiment(),
+            Self::ToggleExperiment => state.toggle_experiment(),
+            Self::AddSeries => state.add_series(),
+            Self::ToggleSmallMultiples => state.toggle_small_multiples(),
+            Self::ToggleClt => state.toggle_clt(),
+            Self::ToggleStats => state.show_stats = !state.show_stats,
+            Self::CycleTheme => state.cycle_theme(),
+            Self::ToggleSidebar => state.toggle_sidebar(),
+            Self::ToggleHelp => state.show_help = !state.show_help,
+            Self::CommandPalette => super::palette::toggle(state),
+            Self::SaveSession => super::palette::ask(state, Prompt::SaveSession),
+            Self::OpenSession => super::palette::ask(state, Prompt::OpenSession),
+            Self::Export => super::palette::ask(state, Prompt::Export),
+            Self::ResetView => state.reset_view(),
+            Self::Reset => state.reset(),
+            Self::Exit => state.should_close = true,
+        }
+    }
+}
+
+// "Ref: Normal" is listed as "Normal"
+fn reference_name(name: &str) -> &str {
+    name.strip_prefix("Ref: ").unwrap_or(name)
+}
@@ -1,12 +1,13 @@
+use super::actions::Action;
 use super::clay;
-use super::layout::chart;
-use super::layout::header::{BUTTON_EXIT, BUTTON_RESET};
-use super::math;
-use super::{is_mouse_pointer_over_element, DropDownState, MenuState, RandomGenerator, State};
+use super::consts;
+use super::layout::header::{BUTTON_EXIT, BUTTON_RESET, BUTTON_RESET_VIEW, BUTTON_STATS};
+use super::layout::{chart, sidebar};
+use super::palette;
+use super::render::ChartDrag;
+use super::{is_mouse_pointer_over_element, raylib, DropDownState, MenuState, Series, State};
 use crate::chart::ChartKind;
 
-use rand::Rng;
-
 pub type HandlerFn = unsafe extern "C" fn(clay::Clay_ElementId, clay::Clay_PointerData, isize);
 pub unsafe extern "C" fn handle_reset(
     id: clay::Clay_ElementId,
@@ -26,13 +27,174 @@ pub unsafe extern "C" fn handle_reset(
                 if is_mouse_pointer_over_element(ele_data, pointer_data)
                     && (pointer_data.state == clay::ClayPointerDataInteractionState::PressedThisFrame)
                 {
-                    state.reset();
+                    Action::Reset.perform(state);
+                }
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_reset_view(
+    id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    if id.stringId.length == 0 {
+        return;
+    }
+
+    let state: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    unsafe {
+        if let Some(state) = state.as_mut() {
+            let reset = clay::Clay_GetElementId(clay::Clay_String::from_str(BUTTON_RESET_VIEW));
+            if reset.id == id.id {
+                let ele_data = clay::Clay_GetElementData(reset);
+                if is_mouse_pointer_over_element(ele_data, pointer_data)
+                    && (pointer_data.state == clay::ClayPointerDataInteractionState::PressedThisFrame)
+                {
+                    Action::ResetView.perform(state);
+                }
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_stats(
+    id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    if id.stringId.length == 0 {
+        return;
+    }
+
+    let state: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    unsafe {
+        if let Some(state) = state.as_mut() {
+            let stats = clay::Clay_GetElementId(clay::Clay_String::from_str(BUTTON_STATS));
+            if stats.id == id.id {
+                let ele_data = clay::Clay_GetElementData(stats);
+                if is_mouse_pointer_over_element(ele_data, pointer_data)
+                    && (pointer_data.state == clay::ClayPointerDataInteractionState::PressedThisFrame)
+                {
+                    Action::ToggleStats.perform(state);
+                }
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_theme_cycle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::CycleTheme.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_help(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::ToggleHelp.perform(state);
+            }
+        }
+    }
+}
+
+// Entries are indexed by their position in the matches of the palette
+pub unsafe extern "C" fn handle_palette_entry(
+    id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                let action = state
+                    .palette
+                    .as_ref()
+                    .and_then(|palette| palette.matches.get(id.offset as usize).copied());
+                if let Some(action) = action {
+                    palette::run(state, action);
                 }
             }
         }
     }
 }
 
+pub unsafe extern "C" fn handle_sidebar_toggle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::ToggleSidebar.perform(state);
+            }
+        }
+    }
+}
+
+// Only grabs the splitter, `handle_sidebar_drag` follows the pointer even once it leaves the splitter
+pub unsafe extern "C" fn handle_splitter_grab(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == clay::ClayPointerDataInteractionState::PressedThisFrame {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                state.sidebar_drag = Some(pointer_data.position.x - state.sidebar_width);
+            }
+        }
+    }
+}
+
+// Called every frame with the pointer in layout pixels, releasing the button lets go of the splitter
+pub fn handle_sidebar_drag(state: &mut State, mouse: clay::Clay_Vector2) {
+    let Some(grab) = state.sidebar_drag else {
+        return;
+    };
+    if !unsafe { raylib::IsMouseButtonDown(0) } {
+        state.sidebar_drag = None;
+        return;
+    }
+    let window = state.layout_size().0;
+    state.sidebar_preferred = Some(sidebar::clamp_sidebar_width(mouse.x - grab, window));
+}
+
+pub unsafe extern "C" fn handle_markers_toggle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                state.show_markers = !state.show_markers;
+            }
+        }
+    }
+}
+
 pub unsafe extern "C" fn handle_exit(id: clay::Clay_ElementId, pointer_data: clay::Clay_PointerData, user_data: isize) {
     if id.stringId.length == 0 {
         return;
@@ -47,7 +209,7 @@ pub unsafe extern "C" fn handle_exit(id: clay::Clay_ElementId, pointer_data: cla
                 if super::is_mouse_pointer_over_element(ele_data, pointer_data)
                     && (pointer_data.state == clay::ClayPointerDataInteractionState::ReleasedThisFrame)
                 {
-                    state.should_close = true;
+                    Action::Exit.perform(state);
                 }
             }
         }
@@ -89,7 +251,21 @@ unsafe fn is_mouse_over_menus(state: &State, pointer_data: clay::Clay_PointerDat
             .map(|ele| super::is_mouse_pointer_over_element(ele, pointer_data))
             .unwrap_or(false);
 
-        is_mouse_over_chart_menu || is_mouse_over_dist_menu
+        let is_mouse_over_kde_menu = state
+            .kde_menu
+            .menuid
+            .map(|id| clay::Clay_GetElementData(id))
+            .map(|ele| super::is_mouse_pointer_over_element(ele, pointer_data))
+            .unwrap_or(false);
+
+        let is_mouse_over_reference_menu = state
+            .reference_menu
+            .menuid
+            .map(|id| clay::Clay_GetElementData(id))
+            .map(|ele| super::is_mouse_pointer_over_element(ele, pointer_data))
+            .unwrap_or(false);
+
+        is_mouse_over_chart_menu || is_mouse_over_dist_menu || is_mouse_over_kde_menu || is_mouse_over_reference_menu
     }
 }
 
@@ -167,16 +343,8 @@ pub unsafe extern "C" fn handle_chart_dropdown_menu_item_click(
     let data: *mut DropDownState = std::ptr::with_exposed_provenance_mut(user_data as usize);
     if pointer_data.state == 0 {
         unsafe {
-            let menu = (*data).menu;
-            (*menu).title = Some((*data).name);
-            (*menu).pressed = false;
-
-            let parent = (*menu).parent;
-            (*parent).animation.reset();
-            if let Some(ref mut chart_data) = (*parent).chart_data {
-                let kind = ChartKind::from_str((*data).name);
-                chart_data.kind = kind;
-            }
+            let parent = (*(*data).menu).parent;
+            Action::Chart(ChartKind::from_str((*data).name)).perform(&mut *parent);
         }
     }
 }
@@ -189,33 +357,381 @@ pub unsafe extern "C" fn handle_distribution_dropdown_menu_item_click(
     let data: *mut DropDownState = std::ptr::with_exposed_provenance_mut(user_data as usize);
     if pointer_data.state == 0 {
         unsafe {
-            let menu = (*data).menu;
-            (*menu).pressed = false;
-            let dist = (*data).name;
-            if dist == (*menu).title.unwrap_or("") {
+            let parent = (*(*data).menu).parent;
+            Action::Distribution((*data).name).perform(&mut *parent);
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_reference_dropdown_menu_item_click(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut DropDownState = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            let parent = (*(*data).menu).parent;
+            Action::Reference((*data).name).perform(&mut *parent);
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_kde_dropdown_menu_item_click(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut DropDownState = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            let parent = (*(*data).menu).parent;
+            Action::Kde((*data).name).perform(&mut *parent);
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_bandwidth_decrease(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::NarrowerBandwidth.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_bandwidth_increase(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::WiderBandwidth.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_bandwidth_reset(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::ResetBandwidth.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_add_series(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::AddSeries.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_small_multiples(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::ToggleSmallMultiples.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_primary_toggle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                state.primary_visible = !state.primary_visible;
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_series_toggle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut Series = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(series) = data.as_mut() {
+                series.visible = !series.visible;
+            }
+        }
+    }
+}
+
+// The series can be moved or dropped by these two, nothing should touch `data` after the call
+pub unsafe extern "C" fn handle_series_cycle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut Series = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(series) = data.as_mut() {
+                let index = series.index;
+                if let Some(state) = series.parent.as_mut() {
+                    state.cycle_series(index);
+                }
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_series_remove(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut Series = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(series) = data.as_mut() {
+                let index = series.index;
+                if let Some(state) = series.parent.as_mut() {
+                    state.remove_series(index);
+                }
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_xscale_cycle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::CycleXScale.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_yscale_cycle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::CycleYScale.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_easing_cycle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::CycleEasing.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_reveal_cycle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::CycleReveal.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_stream(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::ToggleStream.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_clt_toggle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::ToggleClt.perform(state);
+            }
+        }
+    }
+}
+
+// Pressing or dragging on the track picks the size under the pointer
+pub unsafe extern "C" fn handle_clt_slider(
+    id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    if pointer_data.state != clay::ClayPointerDataInteractionState::PressedThisFrame
+        && pointer_data.state != clay::ClayPointerDataInteractionState::Pressed
+    {
+        return;
+    }
+
+    let state: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    unsafe {
+        if let Some(state) = state.as_mut() {
+            let track = clay::Clay_GetElementData(id);
+            if !track.found || track.boundingBox.width <= 0.0 {
                 return;
             }
 
-            (*menu).title = Some((*data).name);
+            let fraction = (pointer_data.position.x - track.boundingBox.x) / track.boundingBox.width;
+            let size = 1.0 + fraction.clamp(0.0, 1.0) * ((consts::CLT_MAX_N - 1) as f32);
+            state.set_clt_size(size.round() as usize);
+        }
+    }
+}
 
-            let state = (*menu).parent;
-            (*state).animation.reset();
+// Same as the CLT slider, the track spans [-MAX_CORRELATION, MAX_CORRELATION]
+pub unsafe extern "C" fn handle_correlation_slider(
+    id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    if pointer_data.state != clay::ClayPointerDataInteractionState::PressedThisFrame
+        && pointer_data.state != clay::ClayPointerDataInteractionState::Pressed
+    {
+        return;
+    }
 
-            let seed = (*state).seeder.random::<u64>();
-            if dist == "Uniform" {
-                let gen_ = math::Uniform::new(seed);
-                (*state).generator = RandomGenerator::Uniform(gen_);
-                (*state).create_chart_data();
-            } else if dist == "Normal" {
-                let gen_ = math::Normal::new(seed);
-                (*state).generator = RandomGenerator::Normal(gen_);
-                (*state).create_chart_data();
-            } else if dist == "Gamma" {
-                let gen_ = math::Gamma::new(seed, 5, 2.0);
-                (*state).generator = RandomGenerator::Gamma(gen_);
-                (*state).create_chart_data();
-            } else {
-                eprintln!("ERROR: Does not know how to handle distribution: '{dist}'");
+    let state: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    unsafe {
+        if let Some(state) = state.as_mut() {
+            let track = clay::Clay_GetElementData(id);
+            if !track.found || track.boundingBox.width <= 0.0 {
+                return;
+            }
+
+            let fraction = (pointer_data.position.x - track.boundingBox.x) / track.boundingBox.width;
+            let max = consts::MAX_CORRELATION;
+            state.set_correlation(-max + fraction.clamp(0.0, 1.0) * 2.0 * max);
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_process_cycle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::CycleProcess.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_path_count_cycle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::CyclePathCount.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_experiment_cycle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::CycleExperiment.perform(state);
+            }
+        }
+    }
+}
+
+pub unsafe extern "C" fn handle_experiment_toggle(
+    _id: clay::Clay_ElementId,
+    pointer_data: clay::Clay_PointerData,
+    user_data: isize,
+) {
+    let data: *mut State = std::ptr::with_exposed_provenance_mut(user_data as usize);
+    if pointer_data.state == 0 {
+        unsafe {
+            if let Some(state) = data.as_mut() {
+                Action::ToggleExperiment.perform(state);
             }
         }
     }
@@ -230,8 +746,72 @@ pub unsafe extern "C" fn handle_simulate(
     if pointer_data.state == 0 {
         unsafe {
             if let Some(state) = data.as_mut() {
-                state.simulate();
+                Action::Simulate.perform(state);
             }
         }
     }
 }
+
+const ZOOM_STEP: f32 = 1.2;
+const MIN_BOX_ZOOM: f32 = 4.0; // Pixels, anything smaller is taken as a click
+
+// Wheel zoom, drag to pan and shift drag to zoom on a box, all driven from the last drawn chart.
+// Returns true when the wheel was used on the chart so that it does not scroll the page as well
+pub fn handle_chart_navigation(state: &mut State, mouse: raylib::Vector2, wheel: raylib::Vector2) -> bool {
+    let Some(view) = state.chart_view else {
+        state.drag = None;
+        return false;
+    };
+    let (dim, frame) = (view.dim, state.view_window.unwrap_or(view.frame));
+
+    let mut consumed = false;
+    if view.contains(mouse) && wheel.y != 0.0 && !state.is_menu_open() {
+        let factor = ZOOM_STEP.powf(-wheel.y);
+        state.view_window = Some(frame.zoomed(frame.data_x(&dim, mouse.x), frame.data_y(&dim, mouse.y), factor));
+        consumed = true;
+    }
+
+    let (pressed, down) = unsafe { (raylib::IsMouseButtonPressed(0), raylib::IsMouseButtonDown(0)) };
+    if pressed && view.contains(mouse) && !state.is_menu_open() {
+        let shift = unsafe {
+            raylib::IsKeyDown(raylib::KeyboardKey_KEY_LEFT_SHIFT as i32)
+                || raylib::IsKeyDown(raylib::KeyboardKey_KEY_RIGHT_SHIFT as i32)
+        };
+        state.drag = Some(if shift {
+            ChartDrag::BoxZoom { start: mouse, end: mouse }
+        } else {
+            ChartDrag::Pan { last: mouse }
+        });
+        return consumed;
+    }
+
+    match state.drag {
+        Some(ChartDrag::Pan { last }) if down => {
+            if last.x != mouse.x || last.y != mouse.y {
+                state.view_window = Some(frame.panned(&dim, last, mouse));
+            }
+            state.drag = Some(ChartDrag::Pan { last: mouse });
+        }
+        Some(ChartDrag::BoxZoom { start, .. }) if down => {
+            let end = raylib::Vector2 {
+                x: mouse.x.clamp(dim.xbgn, dim.xend),
+                y: mouse.y.clamp(dim.ybgn, dim.yend),
+            };
+            state.drag = Some(ChartDrag::BoxZoom { start, end });
+        }
+        Some(ChartDrag::BoxZoom { start, end }) => {
+            if (end.x - start.x).abs() > MIN_BOX_ZOOM && (end.y - start.y).abs() > MIN_BOX_ZOOM {
+                state.view_window = Some(frame.cropped(
+                    frame.data_x(&dim, start.x),
+                    frame.data_y(&dim, start.y),
+                    frame.data_x(&dim, end.x),
+                    frame.data_y(&dim, end.y),
+                ));
+            }
+            state.drag = None;
+        }
+        Some(ChartDrag::Pan { .. }) | None => state.drag = None,
+    }
+
+    consumed
+}
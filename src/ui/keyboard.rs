@@ -0,0 +1,486 @@
+use super::actions::Action;
+use super::handlers::HandlerFn;
+use super::{clay, raylib, State};
+use crate::error::AppError;
+use crate::toml::{Document, Entry, Error, Value};
+
+use std::path::Path;
+
+const KEYS_ENV: &str = "CLAYDASH_KEYS"; // Overrides the path in the configuration directory
+const KEYS_FILE: &str = "keys.toml";
+
+// `$CLAYDASH_KEYS`, otherwise `keys.toml` in the claydash directory of the user configuration
+pub fn default_path() -> Option<std::path::PathBuf> {
+    crate::config::path(KEYS_ENV, KEYS_FILE)
+}
+
+// A key and the modifiers that must be held with it, no more and no less
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shortcut {
+    pub key: i32,
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+const NAMED_KEYS: [(&str, raylib::KeyboardKey); 14] = [
+    ("Space", raylib::KeyboardKey_KEY_SPACE),
+    ("Enter", raylib::KeyboardKey_KEY_ENTER),
+    ("Escape", raylib::KeyboardKey_KEY_ESCAPE),
+    ("Tab", raylib::KeyboardKey_KEY_TAB),
+    ("Backspace", raylib::KeyboardKey_KEY_BACKSPACE),
+    ("Delete", raylib::KeyboardKey_KEY_DELETE),
+    ("Home", raylib::KeyboardKey_KEY_HOME),
+    ("End", raylib::KeyboardKey_KEY_END),
+    ("Up", raylib::KeyboardKey_KEY_UP),
+    ("Down", raylib::KeyboardKey_KEY_DOWN),
+    ("Left", raylib::KeyboardKey_KEY_LEFT),
+    ("Right", raylib::KeyboardKey_KEY_RIGHT),
+    ("=", raylib::KeyboardKey_KEY_EQUAL),
+    ("-", raylib::KeyboardKey_KEY_MINUS),
+];
+
+impl Shortcut {
+    pub const fn key(key: raylib::KeyboardKey) -> Self {
+        Self { key: key as i32, ctrl: false, shift: false }
+    }
+
+    pub const fn ctrl(key: raylib::KeyboardKey) -> Self {
+        Self { key: key as i32, ctrl: true, shift: false }
+    }
+
+    // `K`, `Ctrl+K`, `Shift+F1`... letters, digits, F1 to F12 and the keys in `NAMED_KEYS`, case insensitive
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut shortcut = Self { key: 0, ctrl: false, shift: false };
+        let mut parts = text.split('+').map(str::trim).peekable();
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                shortcut.key = parse_key(part)?;
+            } else if part.eq_ignore_ascii_case("ctrl") {
+                shortcut.ctrl = true;
+            } else if part.eq_ignore_ascii_case("shift") {
+                shortcut.shift = true;
+            } else {
+                return None;
+            }
+        }
+        Some(shortcut)
+    }
+
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        if self.ctrl {
+            name.push_str("Ctrl+");
+        }
+        if self.shift {
+            name.push_str("Shift+");
+        }
+        name.push_str(&key_name(self.key));
+        name
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        unsafe { raylib::IsKeyPressed(self.key) && is_ctrl_down() == self.ctrl && is_shift_down() == self.shift }
+    }
+}
+
+fn parse_key(text: &str) -> Option<i32> {
+    let mut chars = text.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        // raylib numbers letters and digits by their ASCII code
+        if c.is_ascii_alphanumeric() {
+            return Some(c.to_ascii_uppercase() as i32);
+        }
+    }
+    if let Some(n) = text.strip_prefix(['F', 'f']).and_then(|n| n.parse::<i32>().ok()) {
+        if (1..=12).contains(&n) {
+            return Some(raylib::KeyboardKey_KEY_F1 as i32 + n - 1);
+        }
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(text))
+        .map(|(_, key)| *key as i32)
+}
+
+fn key_name(key: i32) -> String {
+    let f1 = raylib::KeyboardKey_KEY_F1 as i32;
+    if (0..128).contains(&key) && (key as u8).is_ascii_alphanumeric() {
+        return char::from(key as u8).to_string();
+    }
+    if (f1..f1 + 12).contains(&key) {
+        return format!("F{}", key - f1 + 1);
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(_, named)| *named as i32 == key)
+        .map(|(name, _)| name.to_string())
+        .unwrap_or_else(|| format!("#{key}"))
+}
+
+pub fn is_ctrl_down() -> bool {
+    unsafe {
+        raylib::IsKeyDown(raylib::KeyboardKey_KEY_LEFT_CONTROL as i32)
+            || raylib::IsKeyDown(raylib::KeyboardKey_KEY_RIGHT_CONTROL as i32)
+    }
+}
+
+pub fn is_shift_down() -> bool {
+    unsafe {
+        raylib::IsKeyDown(raylib::KeyboardKey_KEY_LEFT_SHIFT as i32)
+            || raylib::IsKeyDown(raylib::KeyboardKey_KEY_RIGHT_SHIFT as i32)
+    }
+}
+
+// Global shortcuts, an action may have several or none
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    pub shortcuts: Vec<(Action, Shortcut)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use crate::chart::ChartKind;
+
+        let mut shortcuts = vec![
+            (Action::Simulate, Shortcut::key(raylib::KeyboardKey_KEY_S)),
+            (Action::ToggleStream, Shortcut::key(raylib::KeyboardKey_KEY_P)),
+        ];
+        // 1 to 9 then 0, in the order of the chart menu
+        for (idx, kind) in ChartKind::ALL.iter().copied().enumerate() {
+            let key = raylib::KeyboardKey_KEY_ZERO + ((idx + 1) % 10) as raylib::KeyboardKey;
+            shortcuts.push((Action::Chart(kind), Shortcut::key(key)));
+        }
+        shortcuts.extend([
+            (
+                Action::Distribution(super::consts::DROP_DOWN_UNIFORM),
+                Shortcut::key(raylib::KeyboardKey_KEY_U),
+            ),
+            (
+                Action::Distribution(super::consts::DROP_DOWN_NORMAL),
+                Shortcut::key(raylib::KeyboardKey_KEY_N),
+            ),
+            (
+                Action::Distribution(super::consts::DROP_DOWN_GAMMA),
+                Shortcut::key(raylib::KeyboardKey_KEY_G),
+            ),
+            (Action::ToggleStats, Shortcut::key(raylib::KeyboardKey_KEY_I)),
+            (Action::CycleTheme, Shortcut::key(raylib::KeyboardKey_KEY_T)),
+            (Action::ToggleSidebar, Shortcut::key(raylib::KeyboardKey_KEY_B)),
+            (Action::ToggleHelp, Shortcut::key(raylib::KeyboardKey_KEY_H)),
+            (Action::ToggleHelp, Shortcut::key(raylib::KeyboardKey_KEY_F1)),
+            (Action::CommandPalette, Shortcut::ctrl(raylib::KeyboardKey_KEY_K)),
+            (Action::SaveSession, Shortcut::ctrl(raylib::KeyboardKey_KEY_S)),
+            (Action::OpenSession, Shortcut::ctrl(raylib::KeyboardKey_KEY_O)),
+            (Action::ResetView, Shortcut::key(raylib::KeyboardKey_KEY_V)),
+            (Action::Reset, Shortcut::key(raylib::KeyboardKey_KEY_X)),
+            (Action::Exit, Shortcut::key(raylib::KeyboardKey_KEY_Q)),
+        ]);
+        Self { shortcuts }
+    }
+}
+
+impl Bindings {
+    pub fn of(&self, action: Action) -> impl Iterator<Item = Shortcut> + '_ {
+        self.shortcuts
+            .iter()
+            .filter(move |(bound, _)| *bound == action)
+            .map(|(_, shortcut)| *shortcut)
+    }
+
+    pub fn pressed(&self) -> Option<Action> {
+        self.shortcuts
+            .iter()
+            .find(|(_, shortcut)| shortcut.is_pressed())
+            .map(|(action, _)| *action)
+    }
+}
+
+// The defaults when the file does not exist
+pub fn load(path: &Path) -> Result<Bindings, AppError> {
+    let invalid =
+        |position, message| AppError::InvalidKeyBindings { path: path.display().to_string(), position, message };
+    let src = match std::fs::read_to_string(path) {
+        Ok(src) => src,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Bindings::default()),
+        Err(err) => return Err(invalid(None, err.to_string())),
+    };
+    parse(&src).map_err(|err| invalid(Some(err.position), err.message))
+}
+
+// Actions listed under `[shortcuts]` replace their default shortcuts, an empty array leaves them unbound
+pub fn parse(src: &str) -> Result<Bindings, Error> {
+    let document = Document::parse(src)?;
+    let mut bindings = Bindings::default();
+    for entry in &document.entries {
+        let action = entry
+            .key
+            .strip_prefix("shortcuts.")
+            .and_then(|key| Action::all().find(|action| action.key() == key))
+            .ok_or_else(|| Error::new(entry.position, format!("unknown key `{}`", entry.key)))?;
+
+        bindings.shortcuts.retain(|(bound, _)| *bound != action);
+        let values = match &entry.value {
+            Value::Array(values) => values.as_slice(),
+            value => std::slice::from_ref(value),
+        };
+        for value in values {
+            bindings.shortcuts.push((action, shortcut(entry, value)?));
+        }
+    }
+    Ok(bindings)
+}
+
+fn shortcut(entry: &Entry, value: &Value) -> Result<Shortcut, Error> {
+    let text = value.as_str().ok_or_else(|| {
+        let message = format!("`{}` must be a shortcut like \"Ctrl+K\", found {}", entry.key, value.kind());
+        Error::new(entry.position, message)
+    })?;
+    Shortcut::parse(text).ok_or_else(|| Error::new(entry.position, format!("invalid shortcut `{text}`")))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Activate {
+    Press(HandlerFn, isize),   // Clicked with the keyboard, Enter or Space
+    Step(fn(&mut State, i32)), // A slider, moved with the left and right arrows
+}
+
+// A control reachable with Tab, registered while the layout is built
+#[derive(Debug, Clone, Copy)]
+pub struct Control {
+    pub id: clay::Clay_ElementId,
+    pub activate: Activate,
+    pub menu: Option<clay::Clay_ElementId>, // Title of the dropdown the control is an entry of
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Focus {
+    pub focused: Option<clay::Clay_ElementId>,
+    pub visible: bool,      // The ring only shows while the keyboard is in use
+    controls: Vec<Control>, // In layout order
+}
+
+impl Focus {
+    // The keys act on the controls of the last frame, so they are only dropped when the new layout starts
+    pub fn begin_frame(&mut self) {
+        self.controls.clear();
+    }
+
+    pub fn is_focused(&self, id: clay::Clay_ElementId) -> bool {
+        self.focused.is_some_and(|focused| focused.id == id.id)
+    }
+
+    // Clicks and the keyboard both go through `handler`
+    pub fn on_press(&mut self, id: clay::Clay_ElementId, handler: HandlerFn, user_data: isize) {
+        unsafe { clay::Clay_OnHover(Some(handler), user_data) };
+        self.controls
+            .push(Control { id, activate: Activate::Press(handler, user_data), menu: None });
+    }
+
+    pub fn on_press_in_menu(
+        &mut self,
+        menu: clay::Clay_ElementId,
+        id: clay::Clay_ElementId,
+        handler: HandlerFn,
+        user_data: isize,
+    ) {
+        unsafe { clay::Clay_OnHover(Some(handler), user_data) };
+        self.controls
+            .push(Control { id, activate: Activate::Press(handler, user_data), menu: Some(menu) });
+    }
+
+    // Sliders take the pointer through `handler` and the arrows through `step`
+    pub fn on_slide(
+        &mut self,
+        id: clay::Clay_ElementId,
+        handler: HandlerFn,
+        user_data: isize,
+        step: fn(&mut State, i32),
+    ) {
+        unsafe { clay::Clay_OnHover(Some(handler), user_data) };
+        self.controls
+            .push(Control { id, activate: Activate::Step(step), menu: None });
+    }
+
+    fn position(&self) -> Option<usize> {
+        let focused = self.focused?;
+        self.controls.iter().position(|control| control.id.id == focused.id)
+    }
+
+    fn focused_control(&self) -> Option<Control> {
+        self.position().map(|idx| self.controls[idx])
+    }
+
+    // Through every control, or only the entries of the open dropdown, wrapping around at the ends
+    fn advance(&mut self, forward: bool, in_menu: bool) {
+        let candidates: Vec<usize> = (0..self.controls.len())
+            .filter(|idx| !in_menu || self.controls[*idx].menu.is_some())
+            .collect();
+        let (Some(first), Some(last)) = (candidates.first(), candidates.last()) else {
+            return;
+        };
+
+        let next = match self
+            .position()
+            .and_then(|idx| candidates.iter().position(|c| *c == idx))
+        {
+            None if forward => *first,
+            None => *last,
+            Some(at) if forward => candidates[(at + 1) % candidates.len()],
+            Some(at) => candidates[(at + candidates.len() - 1) % candidates.len()],
+        };
+        self.focused = Some(self.controls[next].id);
+        self.visible = true;
+    }
+}
+
+// Same as a click in the middle of the control, press then release so either kind of handler reacts
+unsafe fn click(control: Control, handler: HandlerFn, user_data: isize) {
+    let data = unsafe { clay::Clay_GetElementData(control.id) };
+    if !data.found {
+        return;
+    }
+    let bbox = data.boundingBox;
+    let position = clay::Clay_Vector2 { x: bbox.x + bbox.width / 2.0, y: bbox.y + bbox.height / 2.0 };
+    for state in [
+        clay::ClayPointerDataInteractionState::PressedThisFrame,
+        clay::ClayPointerDataInteractionState::ReleasedThisFrame,
+    ] {
+        let pointer_data = clay::Clay_PointerData { position, state: state as u8 };
+        unsafe { handler(control.id, pointer_data, user_data) };
+    }
+}
+
+fn pressed(key: raylib::KeyboardKey) -> bool {
+    unsafe { raylib::IsKeyPressed(key as i32) }
+}
+
+// Called once a frame before the layout, while the controls of the last frame are still known
+pub fn handle_keyboard(state: &mut State) {
+    if super::palette::handle_keys(state) {
+        return;
+    }
+    if unsafe { raylib::IsMouseButtonPressed(0) } {
+        state.focus.visible = false;
+    }
+
+    let menu_open = state.is_menu_open();
+    if pressed(raylib::KeyboardKey_KEY_ESCAPE) {
+        if state.show_help {
+            state.show_help = false;
+        } else if menu_open {
+            state.focus.focused = state
+                .focus
+                .focused_control()
+                .and_then(|c| c.menu)
+                .or(state.focus.focused);
+            state.unclick();
+        } else {
+            state.focus.focused = None;
+        }
+        return;
+    }
+
+    if pressed(raylib::KeyboardKey_KEY_TAB) && !is_ctrl_down() {
+        state.focus.advance(!is_shift_down(), false);
+        return;
+    }
+    if menu_open && (pressed(raylib::KeyboardKey_KEY_DOWN) || pressed(raylib::KeyboardKey_KEY_UP)) {
+        state.focus.advance(pressed(raylib::KeyboardKey_KEY_DOWN), true);
+        return;
+    }
+
+    if let Some(control) = state.focus.focused_control() {
+        match control.activate {
+            Activate::Press(handler, user_data) => {
+                let activate = pressed(raylib::KeyboardKey_KEY_ENTER)
+                    || pressed(raylib::KeyboardKey_KEY_KP_ENTER)
+                    || pressed(raylib::KeyboardKey_KEY_SPACE);
+                if activate {
+                    state.focus.visible = true;
+                    // Picking an entry closes its dropdown, the focus goes back to the dropdown
+                    if let Some(menu) = control.menu {
+                        state.focus.focused = Some(menu);
+                    }
+                    unsafe { click(control, handler, user_data) };
+                    return;
+                }
+            }
+            Activate::Step(step) => {
+                let steps = i32::from(pressed(raylib::KeyboardKey_KEY_RIGHT))
+                    - i32::from(pressed(raylib::KeyboardKey_KEY_LEFT));
+                if steps != 0 {
+                    state.focus.visible = true;
+                    step(state, steps);
+                    return;
+                }
+            }
+        }
+    }
+
+    if let Some(action) = state.bindings.pressed() {
+        action.perform(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::consts;
+
+    #[test]
+    fn parses_shortcuts_case_insensitively() {
+        let shift_f1 = Shortcut { key: raylib::KeyboardKey_KEY_F1 as i32, ctrl: false, shift: true };
+        assert_eq!(Shortcut::parse("k"), Some(Shortcut::key(raylib::KeyboardKey_KEY_K)));
+        assert_eq!(Shortcut::parse("Ctrl+K"), Some(Shortcut::ctrl(raylib::KeyboardKey_KEY_K)));
+        assert_eq!(Shortcut::parse("shift + f1"), Some(shift_f1));
+        assert_eq!(Shortcut::parse("Ctrl+="), Some(Shortcut::ctrl(raylib::KeyboardKey_KEY_EQUAL)));
+        assert_eq!(Shortcut::parse("7").map(|s| s.name()), Some("7".to_string()));
+        assert_eq!(
+            Shortcut::parse("Ctrl+Shift+space").map(|s| s.name()),
+            Some("Ctrl+Shift+Space".to_string())
+        );
+        for text in ["", "Ctrl+", "Alt+K", "F0", "F13", "KK", "Ctrl+Shift", "é"] {
+            assert_eq!(Shortcut::parse(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn every_action_has_its_own_key_and_label() {
+        let actions: Vec<Action> = Action::all().collect();
+        for (idx, action) in actions.iter().enumerate() {
+            for other in &actions[idx + 1..] {
+                assert_ne!(action.key(), other.key());
+                assert_ne!(action.label(), other.label());
+            }
+        }
+        let bindings = parse("[shortcuts]\nkde.none = \"K\"\nreference.normal = \"R\"\nexport = \"Ctrl+E\"\n").unwrap();
+        assert_eq!(bindings.of(Action::Kde(consts::DROP_DOWN_KDE_NONE)).count(), 1);
+        assert_eq!(bindings.of(Action::Reference(consts::DROP_DOWN_REF_NORMAL)).count(), 1);
+        assert_eq!(bindings.of(Action::Export).count(), 1);
+    }
+
+    #[test]
+    fn shortcuts_replace_the_defaults_of_their_action() {
+        let bindings = parse("[shortcuts]\nsimulate = [\"R\", \"Ctrl+R\"]\nexit = []\n").unwrap();
+        let simulate: Vec<_> = bindings.of(Action::Simulate).map(|s| s.name()).collect();
+        assert_eq!(simulate, vec!["R", "Ctrl+R"]);
+        assert_eq!(bindings.of(Action::Exit).count(), 0);
+        assert_eq!(bindings.of(Action::Reset).count(), Bindings::default().of(Action::Reset).count());
+    }
+
+    #[test]
+    fn reports_bad_entries_where_they_are() {
+        let error_at = |src: &str| {
+            let err = parse(src).unwrap_err();
+            (err.position.line, err.position.column, err.message)
+        };
+        let (line, column, message) = error_at("[shortcuts]\nsimulate = \"S\"\nfly = \"F\"\n");
+        assert_eq!((line, column), (3, 7));
+        assert!(message.contains("`shortcuts.fly`"), "{}", message);
+        assert_eq!(error_at("simulate = \"S\"\n").0, 1);
+        let (line, column, message) = error_at("[shortcuts]\n\nreset = \"Hyper+X\"\n");
+        assert_eq!((line, column), (3, 9));
+        assert!(message.contains("`Hyper+X`"), "{}", message);
+        assert_eq!(error_at("[shortcuts]\nreset = 4\n").0, 2);
+    }
+}
@@ -0,0 +1,63 @@
+use super::clay;
+use super::consts;
+use super::handlers::handle_correlation_slider;
+use super::misc::{define_slider_fill, define_slider_track};
+use super::scheme::SchemeUi;
+use super::State;
+
+const CORRELATION_PANEL_ELEMENT_ID: &str = "CorrelationPanel";
+const CORRELATION_SLIDER_ELEMENT_ID: &str = "CorrelationSlider";
+
+fn define_panel() -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_id(CORRELATION_PANEL_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_layout_direction(clay::ClayLayoutDirection::TopToBottom)
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_padding(clay::Clay_Padding { left: 8, right: 8, top: 0, bottom: 0 })
+                .with_child_gap(4)
+                .build(),
+        )
+        .build()
+}
+
+const CORRELATION_STEP: f32 = 0.05; // Per arrow key press
+
+fn step_correlation(state: &mut State, steps: i32) {
+    state.set_correlation(state.correlation + (steps as f32) * CORRELATION_STEP);
+}
+
+// Only shown with the scatter and heatmap charts, the slider covers the correlations the generator accepts
+pub fn create_correlation_panel(
+    state: &mut State,
+    scheme: &SchemeUi,
+    opaque_state_ptr: isize,
+    txt_cfg: clay::Clay_TextElementConfig,
+) {
+    if !state.chart_kind().is_bivariate() {
+        return;
+    }
+
+    let label = state.text.push(format!("Correlation ρ: {:.2}", state.correlation));
+    let max = consts::MAX_CORRELATION;
+    let fraction = (state.correlation + max) / (2.0 * max);
+
+    let slider = define_slider_track(CORRELATION_SLIDER_ELEMENT_ID, scheme);
+    unsafe {
+        clay::clay!(
+            define_panel(),
+            clay::clay_text!(label, txt_cfg),
+            clay::clay!(
+                slider,
+                state
+                    .focus
+                    .on_slide(slider.id, handle_correlation_slider, opaque_state_ptr, step_correlation),
+                clay::clay!(define_slider_fill(fraction, scheme)),
+            ),
+        );
+    }
+}
@@ -1,12 +1,34 @@
 use super::clay;
+use super::handlers::{handle_primary_toggle, handle_series_toggle, HandlerFn};
+use super::keyboard::Focus;
 use super::scheme::SchemeUi;
-use super::State;
+use super::{Series, State, Stream};
 use crate::chart;
+use crate::font::FONT_LABEL;
+use crate::montecarlo::{Experiment, MonteCarlo};
 
 pub const CHART_ELEMENT_ID: &str = "Main";
+pub const CHART_PLOT_ELEMENT_ID: &str = "MainPlot";
+pub const CHART_CELL_ELEMENT_ID: &str = "MainCell";
+const CHART_ROW_ELEMENT_ID: &str = "MainRow";
+const CHART_PATHS_ELEMENT_ID: &str = "MainPaths";
+const CHART_FINALS_ELEMENT_ID: &str = "MainFinals";
+const FINALS_WIDTH: f32 = 0.25; // Fraction of the chart taken by the histogram of the final values
+const CHART_STREAM_ELEMENT_ID: &str = "MainStream";
+const CHART_RUNNING_MEAN_ELEMENT_ID: &str = "MainRunningMean";
+const RUNNING_MEAN_WIDTH: f32 = 0.3; // Fraction of the chart taken by the running mean while streaming
+const CHART_MONTE_CARLO_ELEMENT_ID: &str = "MainMonteCarlo";
+const CHART_EXPERIMENT_ELEMENT_ID: &str = "MainExperiment";
+const CHART_ESTIMATE_ELEMENT_ID: &str = "MainEstimate";
+const ESTIMATE_WIDTH: f32 = 0.4; // Fraction of the chart taken by the running estimate
+const CHART_TITLE_ELEMENT_ID: &str = "MainTitle";
+const CHART_LEGEND_ELEMENT_ID: &str = "MainLegend";
+const CHART_LEGEND_ENTRY_ELEMENT_ID: &str = "MainLegendEntry";
+const GRID_COLUMNS: usize = 2;
 
-fn create_chart_element(scheme: &SchemeUi, data: chart::ClayCustomElement) -> clay::Clay_ElementDeclaration {
-    let mut ele = clay::ClayElementBuilder::new()
+// Title and legend sit on top of the plot, which is the element holding the custom chart data
+fn create_chart_element(scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
         .with_id(CHART_ELEMENT_ID)
         .with_background_color(scheme.chart.aes.background)
         .with_layout(
@@ -17,13 +39,35 @@ fn create_chart_element(scheme: &SchemeUi, data: chart::ClayCustomElement) -> cl
                 .with_sizing(clay::Clay_Sizing::grow(0.0))
                 .build(),
         )
+        .build()
+}
+
+fn create_plot_element(scheme: &SchemeUi, data: chart::ClayCustomElement) -> clay::Clay_ElementDeclaration {
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id(CHART_PLOT_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_child_gap(scheme.chart.layout.child_gap)
+                .with_layout_direction(clay::ClayLayoutDirection::TopToBottom)
+                .with_sizing(clay::Clay_Sizing::grow(0.0))
+                .build(),
+        )
         .build();
 
     ele.custom = data.to_custom_ele_config();
     ele
 }
 
+fn is_small_multiples(state: &State) -> bool {
+    state.small_multiples && state.chart_data.is_some()
+}
+
 fn create_chart_data_element(state: &mut State) -> chart::ClayCustomElement {
+    if is_small_multiples(state) {
+        state.custom_element = None;
+        return chart::ClayCustomElement::new(std::ptr::null_mut());
+    }
+
     match state.chart_data.as_ref() {
         None => {
             state.custom_element = None;
@@ -37,7 +81,433 @@ fn create_chart_data_element(state: &mut State) -> chart::ClayCustomElement {
     }
 }
 
-pub fn create_chart(state: &mut State, scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+pub fn create_chart(scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    create_chart_element(scheme)
+}
+
+// Side by side plots filling the chart
+fn define_plot_row(scheme: &SchemeUi, id: &'static str) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_id(id)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_child_gap(scheme.chart.layout.child_gap)
+                .with_sizing(clay::Clay_Sizing::grow(0.0))
+                .build(),
+        )
+        .build()
+}
+
+// Narrow plot on the right of the main one, e.g. the final values of the sample paths
+fn define_side_panel(id: &'static str, width: f32, data: chart::ClayCustomElement) -> clay::Clay_ElementDeclaration {
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id(id)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_percent(width),
+                    height: clay::Clay_SizingAxis::sizing_grow(0.0),
+                })
+                .build(),
+        )
+        .build();
+
+    ele.custom = data.to_custom_ele_config();
+    ele
+}
+
+fn create_finals_data_element(state: &mut State) -> Option<chart::ClayCustomElement> {
+    let data = state.chart_data.as_ref()?;
+    state.finals_element = Some(chart::CustomElementKind::Finals(data as *const chart::ChartData));
+    let ptr = state.finals_element.as_mut().unwrap() as *mut chart::CustomElementKind;
+    Some(chart::ClayCustomElement::new(ptr))
+}
+
+fn create_stream_data_element(state: &mut State) -> Option<chart::ClayCustomElement> {
+    let stream = state.stream.as_ref()?;
+    state.stream_element = Some(chart::CustomElementKind::StreamMean(stream as *const Stream));
+    let ptr = state.stream_element.as_mut().unwrap() as *mut chart::CustomElementKind;
+    Some(chart::ClayCustomElement::new(ptr))
+}
+
+fn define_monte_carlo_panel(
+    id: &'static str,
+    width: clay::Clay_SizingAxis,
+    data: chart::ClayCustomElement,
+) -> clay::Clay_ElementDeclaration {
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id(id)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing { width, height: clay::Clay_SizingAxis::sizing_grow(0.0) })
+                .build(),
+        )
+        .build();
+
+    ele.custom = data.to_custom_ele_config();
+    ele
+}
+
+// The trials of the experiment on the left and the estimate they give so far on the right
+fn create_monte_carlo_plot(state: &mut State, scheme: &SchemeUi) {
+    let Some(mc) = state.monte_carlo.as_ref() else {
+        return;
+    };
+
+    // Filled completely before taking any pointer, so the elements do not move while clay holds them
+    let ptr = mc as *const MonteCarlo;
+    let scenario = match mc.experiment {
+        Experiment::Pi => chart::CustomElementKind::MonteCarloPi(ptr),
+        Experiment::Integral => chart::CustomElementKind::MonteCarloIntegral(ptr),
+        Experiment::Buffon => chart::CustomElementKind::MonteCarloBuffon(ptr),
+    };
+    state.monte_carlo_elements.clear();
+    state.monte_carlo_elements.push(scenario);
+    state
+        .monte_carlo_elements
+        .push(chart::CustomElementKind::MonteCarloEstimate(ptr));
+
+    let scenario = &mut state.monte_carlo_elements[0] as *mut chart::CustomElementKind;
+    let estimate = &mut state.monte_carlo_elements[1] as *mut chart::CustomElementKind;
+    unsafe {
+        clay::clay!(
+            define_plot_row(scheme, CHART_MONTE_CARLO_ELEMENT_ID),
+            clay::clay!(define_monte_carlo_panel(
+                CHART_EXPERIMENT_ELEMENT_ID,
+                clay::Clay_SizingAxis::sizing_grow(0.0),
+                chart::ClayCustomElement::new(scenario),
+            )),
+            clay::clay!(define_monte_carlo_panel(
+                CHART_ESTIMATE_ELEMENT_ID,
+                clay::Clay_SizingAxis::sizing_percent(ESTIMATE_WIDTH),
+                chart::ClayCustomElement::new(estimate),
+            )),
+        );
+    }
+}
+
+pub fn create_chart_plot(state: &mut State, scheme: &SchemeUi) {
+    if state.monte_carlo.is_some() {
+        state.custom_element = None;
+        create_monte_carlo_plot(state, scheme);
+        return;
+    }
+
     let chart_data = create_chart_data_element(state);
-    create_chart_element(scheme, chart_data)
+    let side = if is_small_multiples(state) {
+        None
+    } else if state.chart_kind() == chart::ChartKind::Paths {
+        create_finals_data_element(state).map(|finals| {
+            (
+                CHART_PATHS_ELEMENT_ID,
+                define_side_panel(CHART_FINALS_ELEMENT_ID, FINALS_WIDTH, finals),
+            )
+        })
+    } else {
+        create_stream_data_element(state).map(|stream| {
+            let panel = define_side_panel(CHART_RUNNING_MEAN_ELEMENT_ID, RUNNING_MEAN_WIDTH, stream);
+            (CHART_STREAM_ELEMENT_ID, panel)
+        })
+    };
+
+    unsafe {
+        match side {
+            None => clay::clay!(create_plot_element(scheme, chart_data), create_chart_cells(state, scheme)),
+            Some((row_id, panel)) => clay::clay!(
+                define_plot_row(scheme, row_id),
+                clay::clay!(create_plot_element(scheme, chart_data)),
+                clay::clay!(panel),
+            ),
+        }
+    }
+}
+
+fn legend_text_config(scheme: &SchemeUi, size: u16) -> clay::Clay_TextElementConfig {
+    clay::Clay_TextElementConfig {
+        fontId: FONT_LABEL,
+        fontSize: size,
+        textColor: scheme.chart.legend.text,
+        ..Default::default()
+    }
+}
+
+fn chart_title(state: &State, data: &chart::ChartData) -> String {
+    if data.kind.is_bivariate() {
+        let r = crate::stats::correlation(&data.scatter.x, &data.scatter.y);
+        return format!(
+            "{} - Bivariate Normal(ρ = {:.2}), r = {r:.3}, n = {}",
+            data.kind.name(),
+            state.correlation,
+            data.scatter.x.len()
+        );
+    }
+
+    let shown = usize::from(state.primary_visible) + state.series.iter().filter(|s| s.visible).count();
+    let subject = if let Some(n) = state.clt {
+        format!("means of {n} x {}", state.generator.describe())
+    } else if state.series.is_empty() {
+        state.generator.describe()
+    } else {
+        format!("{shown} of {} series", state.series.len() + 1)
+    };
+    match state.stream {
+        Some(ref stream) => format!(
+            "Streaming - {subject}, n = {}, mean = {:.4} (true {:.4})",
+            stream.moments.count, stream.moments.mean, data.theory.mean
+        ),
+        None => format!("{} - {subject}, n = {}", data.kind.name(), data.sample.len()),
+    }
+}
+
+fn monte_carlo_title(mc: &MonteCarlo) -> String {
+    let exact = mc.experiment.exact();
+    match mc.interval() {
+        Some((estimate, lower, upper)) if lower.is_finite() => format!(
+            "Monte Carlo - {}, n = {}, estimate = {estimate:.5}, 95% CI [{lower:.5}, {upper:.5}] (exact {exact:.5})",
+            mc.experiment.name(),
+            mc.count
+        ),
+        Some((estimate, _, _)) => format!(
+            "Monte Carlo - {}, n = {}, estimate = {estimate:.5} (exact {exact:.5})",
+            mc.experiment.name(),
+            mc.count
+        ),
+        None => format!("Monte Carlo - {}, n = {} (exact {exact:.5})", mc.experiment.name(), mc.count),
+    }
+}
+
+pub fn create_chart_title(state: &mut State, scheme: &SchemeUi) {
+    let title = match (state.monte_carlo.as_ref(), state.chart_data.as_ref()) {
+        (Some(mc), _) => monte_carlo_title(mc),
+        (None, Some(data)) => chart_title(state, data),
+        (None, None) => return,
+    };
+    let title = state.text.push(title);
+
+    let ele = clay::ClayElementBuilder::new()
+        .with_id(CHART_TITLE_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .build(),
+        )
+        .build();
+
+    let cfg = legend_text_config(scheme, scheme.chart.legend.title_size);
+    unsafe {
+        clay::clay!(ele, clay::clay_text!(title, cfg));
+    }
+}
+
+fn define_swatch(colour: clay::Clay_Color, width: f32, height: f32) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_fixed(width),
+                    height: clay::Clay_SizingAxis::sizing_fixed(height),
+                })
+                .build(),
+        )
+        .with_background_color(colour)
+        .build()
+}
+
+struct LegendEntry {
+    label: clay::Clay_String,
+    fill: clay::Clay_Color,
+    colour: clay::Clay_Color,
+    visible: bool,
+    kind: chart::ChartKind, // Decides which swatches to show
+    action: Option<(HandlerFn, isize)>,
+}
+
+fn create_legend_entry(focus: &mut Focus, index: usize, entry: LegendEntry, scheme: &SchemeUi) {
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id_index(CHART_LEGEND_ENTRY_ELEMENT_ID, index as u32)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_padding(clay::Clay_Padding { left: 6, right: 6, top: 4, bottom: 4 })
+                .with_child_gap(6)
+                .with_child_alignment(clay::Clay_ChildAlignment { x: 0, y: clay::ClayChildAlignmentY::Center as u8 })
+                .build(),
+        )
+        .with_corner_radius(clay::Clay_CornerRadius::all(5.0))
+        .build();
+
+    if entry.action.is_some() && unsafe { clay::Clay_PointerOver(ele.id) } {
+        ele.backgroundColor = scheme.chart.legend.hover;
+    }
+
+    // Hidden series keep their entry, faded, so they can be switched back on
+    let (mut fill, mut colour) = (entry.fill, entry.colour);
+    let mut cfg = legend_text_config(scheme, scheme.chart.legend.font_size);
+    if !entry.visible {
+        fill.a *= 0.25;
+        colour.a *= 0.25;
+        cfg.textColor.a *= 0.5;
+    }
+
+    let size = scheme.chart.legend.swatch;
+    let id = ele.id;
+    unsafe {
+        clay::clay!(
+            ele,
+            {
+                if let Some((handler, user_data)) = entry.action {
+                    focus.on_press(id, handler, user_data);
+                }
+            },
+            {
+                if entry.kind.uses_fill() {
+                    clay::clay!(define_swatch(fill, size, size));
+                }
+                if entry.kind.uses_colour() {
+                    clay::clay!(define_swatch(colour, 1.5 * size, 0.25 * size));
+                }
+            },
+            clay::clay_text!(entry.label, cfg),
+        );
+    }
+}
+
+pub fn create_chart_legend(state: &mut State, scheme: &SchemeUi, opaque_state_ptr: isize) {
+    let Some(kind) = state.chart_data.as_ref().map(|data| data.kind) else {
+        return;
+    };
+    if state.monte_carlo.is_some() {
+        return;
+    }
+
+    let mut entries = Vec::with_capacity(state.series.len() + 2);
+    entries.push(LegendEntry {
+        label: state.text.push(state.generator.describe()),
+        fill: scheme.chart.aes.fill,
+        colour: scheme.chart.aes.colour,
+        visible: state.primary_visible,
+        kind,
+        action: Some((handle_primary_toggle, opaque_state_ptr)),
+    });
+
+    let palette = &scheme.chart.aes.series;
+    for idx in 0..state.series.len() {
+        let series = &mut state.series[idx] as *mut Series;
+        let (label, colour, visible) = unsafe {
+            let s = &*series;
+            (s.generator.describe(), palette[s.colour % palette.len()], s.visible)
+        };
+        entries.push(LegendEntry {
+            label: state.text.push(label),
+            fill: colour,
+            colour,
+            visible,
+            kind,
+            action: Some((handle_series_toggle, series.expose_provenance() as isize)),
+        });
+    }
+
+    // Not a series, but it is drawn on the chart so it deserves an entry
+    if state.kde.is_some()
+        && matches!(kind, chart::ChartKind::Hist | chart::ChartKind::Line | chart::ChartKind::HistLine)
+    {
+        entries.push(LegendEntry {
+            label: clay::Clay_String::from_str("KDE"),
+            fill: scheme.chart.aes.kde,
+            colour: scheme.chart.aes.kde,
+            visible: true,
+            kind: chart::ChartKind::Line,
+            action: None,
+        });
+    }
+
+    let row = clay::ClayElementBuilder::new()
+        .with_id(CHART_LEGEND_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_child_gap(12)
+                .build(),
+        )
+        .build();
+
+    unsafe {
+        clay::clay!(row, {
+            for (index, entry) in entries.into_iter().enumerate() {
+                create_legend_entry(&mut state.focus, index, entry, scheme);
+            }
+        });
+    }
+}
+
+fn create_cell_element(
+    scheme: &SchemeUi,
+    index: usize,
+    data: chart::ClayCustomElement,
+) -> clay::Clay_ElementDeclaration {
+    let border = clay::Clay_BorderElementConfig {
+        color: scheme.chart.aes.reference,
+        width: clay::Clay_BorderWidth { left: 1, right: 1, top: 1, bottom: 1, betweenChildren: 0 },
+    };
+
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id_index(CHART_CELL_ELEMENT_ID, index as u32)
+        .with_background_color(scheme.chart.aes.background)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing::grow(0.0))
+                .build(),
+        )
+        .with_border(border)
+        .build();
+
+    ele.custom = data.to_custom_ele_config();
+    ele
+}
+
+fn define_grid_row(index: usize, scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_id_index(CHART_ROW_ELEMENT_ID, index as u32)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_child_gap(scheme.chart.layout.child_gap)
+                .with_sizing(clay::Clay_Sizing::grow(0.0))
+                .build(),
+        )
+        .build()
+}
+
+// Small multiples: one chart per visible series, laid out on a grid inside the main chart element
+fn create_chart_cells(state: &mut State, scheme: &SchemeUi) {
+    if !is_small_multiples(state) {
+        return;
+    }
+
+    // Filled completely before taking any pointer, so the elements do not move while clay holds them
+    state.cell_elements.clear();
+    if state.primary_visible {
+        state.cell_elements.push(chart::CustomElementKind::Cell(0));
+    }
+    for (index, series) in state.series.iter().enumerate() {
+        if series.visible {
+            state.cell_elements.push(chart::CustomElementKind::Cell(index + 1));
+        }
+    }
+
+    let cells = state.cell_elements.len();
+    for row in 0..cells.div_ceil(GRID_COLUMNS) {
+        unsafe {
+            clay::clay!(define_grid_row(row, scheme), {
+                for idx in (row * GRID_COLUMNS)..((row + 1) * GRID_COLUMNS).min(cells) {
+                    let ptr = &mut state.cell_elements[idx] as *mut chart::CustomElementKind;
+                    clay::clay!(create_cell_element(scheme, idx, chart::ClayCustomElement::new(ptr)));
+                }
+            });
+        }
+    }
 }
@@ -0,0 +1,92 @@
+use super::clay;
+use super::consts;
+use super::handlers::{handle_clt_slider, handle_clt_toggle};
+use super::misc::{define_slider_fill, define_slider_track};
+use super::scheme::SchemeUi;
+use super::State;
+
+const CLT_PANEL_ELEMENT_ID: &str = "CltPanel";
+const CLT_TOGGLE_ELEMENT_ID: &str = "CltToggle";
+const CLT_SLIDER_ELEMENT_ID: &str = "CltSlider";
+
+fn define_panel() -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_id(CLT_PANEL_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_layout_direction(clay::ClayLayoutDirection::TopToBottom)
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_child_gap(4)
+                .build(),
+        )
+        .build()
+}
+
+fn define_toggle(scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id(CLT_TOGGLE_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_padding(clay::Clay_Padding { left: 8, right: 8, top: 8, bottom: 8 })
+                .build(),
+        )
+        .with_background_color(scheme.sidebar.button.default)
+        .with_corner_radius(clay::Clay_CornerRadius::all(8.0))
+        .build();
+
+    if unsafe { clay::Clay_PointerOver(ele.id) } {
+        ele.backgroundColor = scheme.sidebar.button.hover;
+    }
+    ele
+}
+
+fn step_clt_size(state: &mut State, steps: i32) {
+    if let Some(n) = state.clt {
+        state.set_clt_size((n as i32 + steps).max(1) as usize);
+    }
+}
+
+// Toggle for the central limit theorem mode, and once on a slider for the number of draws in each mean
+pub fn create_clt_panel(
+    state: &mut State,
+    scheme: &SchemeUi,
+    opaque_state_ptr: isize,
+    txt_cfg: clay::Clay_TextElementConfig,
+) {
+    let label = match state.clt {
+        None => clay::Clay_String::from_str("CLT: Off"),
+        Some(n) => state.text.push(format!("CLT: means of n = {n}")),
+    };
+
+    let toggle = define_toggle(scheme);
+    let slider = define_slider_track(CLT_SLIDER_ELEMENT_ID, scheme);
+    unsafe {
+        clay::clay!(
+            define_panel(),
+            clay::clay!(
+                toggle,
+                state.focus.on_press(toggle.id, handle_clt_toggle, opaque_state_ptr),
+                clay::clay_text!(label, txt_cfg),
+            ),
+            {
+                if let Some(n) = state.clt {
+                    let fraction = ((n - 1) as f32) / ((consts::CLT_MAX_N - 1) as f32);
+                    clay::clay!(
+                        slider,
+                        state
+                            .focus
+                            .on_slide(slider.id, handle_clt_slider, opaque_state_ptr, step_clt_size),
+                        clay::clay!(define_slider_fill(fraction, scheme)),
+                    );
+                }
+            },
+        );
+    }
+}
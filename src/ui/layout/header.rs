@@ -1,14 +1,27 @@
 use super::clay;
-use super::handlers::{handle_chart_click, handle_exit, handle_reset};
+use super::handlers::{
+    handle_chart_click, handle_exit, handle_help, handle_reset, handle_reset_view, handle_sidebar_toggle, handle_stats,
+    handle_theme_cycle,
+};
+use super::keyboard::Focus;
 use super::misc;
 use super::scheme::SchemeUi;
-use super::sidebar::{create_chart_menu, create_dist_menu, create_sim_button};
+use super::sidebar::{
+    create_animation_control, create_bandwidth_control, create_chart_menu, create_dist_menu, create_kde_menu,
+    create_monte_carlo_control, create_process_control, create_reference_menu, create_scale_control, create_sim_button,
+    create_stream_button,
+};
 use super::HoverCallback;
 use super::State;
-use crate::math;
+use crate::font::FONT_LABEL;
 
 pub const BUTTON_RESET: &str = "Reset";
+pub const BUTTON_RESET_VIEW: &str = "Reset view";
 pub const BUTTON_EXIT: &str = "Exit";
+pub const BUTTON_STATS: &str = "Stats";
+pub const BUTTON_THEME: &str = "Theme";
+pub const BUTTON_SIDEBAR: &str = "Sidebar";
+pub const BUTTON_HELP: &str = "?";
 
 fn define_header_component(scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
     let mut border = clay::Clay_BorderElementConfig::default();
@@ -64,29 +77,43 @@ fn define_button(id: &'static str, scheme: &SchemeUi) -> clay::Clay_ElementDecla
         .build()
 }
 
-fn header_text_config() -> clay::Clay_TextElementConfig {
+fn header_text_config(scheme: &SchemeUi) -> clay::Clay_TextElementConfig {
     let mut cfg = clay::Clay_TextElementConfig::default();
-    cfg.fontId = 0;
+    cfg.fontId = FONT_LABEL;
     cfg.fontSize = 36;
-    cfg.textColor = clay::Clay_Color { r: 255.0, g: 0.0, b: 0.0, a: 255.0 };
-    cfg.textColor = clay::CLAY_WHITE;
+    cfg.textColor = scheme.header.text;
     cfg
 }
 
-fn create_header_button(name: &'static str, action: Option<(HoverCallback, isize)>, scheme: &SchemeUi) {
-    let cfg = header_text_config();
+fn create_header_button(
+    focus: &mut Focus,
+    name: &'static str,
+    action: Option<(HoverCallback, isize)>,
+    scheme: &SchemeUi,
+) {
+    create_labelled_header_button(focus, name, clay::Clay_String::from_str(name), action, scheme);
+}
+
+// Same as `create_header_button`, for labels that change while the id stays
+fn create_labelled_header_button(
+    focus: &mut Focus,
+    id: &'static str,
+    txt: clay::Clay_String,
+    action: Option<(HoverCallback, isize)>,
+    scheme: &SchemeUi,
+) {
+    let cfg = header_text_config(scheme);
 
-    let id = name;
     let mut button = define_button(id, scheme);
     if unsafe { clay::Clay_PointerOver(button.id) } {
         button.backgroundColor = scheme.header.button.hover;
     }
 
-    let txt = clay::Clay_String::from_str(name);
+    let button_id = button.id;
     unsafe {
         clay::clay!(button, clay::clay_text!(txt, cfg), {
             if let Some((fun, param)) = action {
-                clay::Clay_OnHover(Some(fun), param);
+                focus.on_press(button_id, fun, param);
             }
         });
     }
@@ -95,23 +122,69 @@ fn create_header_button(name: &'static str, action: Option<(HoverCallback, isize
 pub unsafe fn create_header(state: &mut State, scheme: &SchemeUi, opaque_state_ptr: isize) {
     // Hate this opaque ptr but what can we do...
 
-    let txt_cfg = header_text_config();
+    let txt_cfg = header_text_config(scheme);
+    let theme = state.text.push(format!("Theme: {}", state.theme.name()));
+    let sidebar = clay::Clay_String::from_str(if state.is_sidebar_shown() { "«" } else { "»" });
     let text_alignment = clay::ClayChildAlignmentX::Center;
     let within_sidebar = false;
     unsafe {
         clay::clay!(
             define_header_component(scheme),
             clay::Clay_OnHover(Some(handle_chart_click), opaque_state_ptr),
-            create_header_button(BUTTON_RESET, Some((handle_reset, opaque_state_ptr)), scheme),
+            create_labelled_header_button(
+                &mut state.focus,
+                BUTTON_SIDEBAR,
+                sidebar,
+                Some((handle_sidebar_toggle, opaque_state_ptr)),
+                scheme
+            ),
+            create_header_button(&mut state.focus, BUTTON_RESET, Some((handle_reset, opaque_state_ptr)), scheme),
+            create_header_button(
+                &mut state.focus,
+                BUTTON_RESET_VIEW,
+                Some((handle_reset_view, opaque_state_ptr)),
+                scheme
+            ),
+            create_header_button(&mut state.focus, BUTTON_STATS, Some((handle_stats, opaque_state_ptr)), scheme),
+            create_labelled_header_button(
+                &mut state.focus,
+                BUTTON_THEME,
+                theme,
+                Some((handle_theme_cycle, opaque_state_ptr)),
+                scheme
+            ),
+            create_header_button(&mut state.focus, BUTTON_HELP, Some((handle_help, opaque_state_ptr)), scheme),
             {
-                if state.sidebar_width < math::EPS {
+                if !state.is_sidebar_shown() {
                     create_chart_menu(state, scheme, txt_cfg, text_alignment, within_sidebar);
                     create_dist_menu(state, scheme, txt_cfg, text_alignment, within_sidebar);
-                    create_sim_button(scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar);
+                    create_reference_menu(state, scheme, txt_cfg, text_alignment, within_sidebar);
+                    create_kde_menu(state, scheme, txt_cfg, text_alignment, within_sidebar);
+                    create_bandwidth_control(state, scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar);
+                    create_scale_control(state, scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar);
+                    create_animation_control(state, scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar);
+                    create_process_control(state, scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar);
+                    create_sim_button(
+                        &mut state.focus,
+                        scheme,
+                        opaque_state_ptr,
+                        txt_cfg,
+                        text_alignment,
+                        within_sidebar,
+                    );
+                    create_stream_button(state, scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar);
+                    create_monte_carlo_control(
+                        state,
+                        scheme,
+                        opaque_state_ptr,
+                        txt_cfg,
+                        text_alignment,
+                        within_sidebar,
+                    );
                 }
             },
             clay::clay!(misc::empty_element()),
-            create_header_button(BUTTON_EXIT, Some((handle_exit, opaque_state_ptr)), scheme),
+            create_header_button(&mut state.focus, BUTTON_EXIT, Some((handle_exit, opaque_state_ptr)), scheme),
         )
     }
 }
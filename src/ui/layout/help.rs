@@ -0,0 +1,128 @@
+use super::clay;
+use super::scheme::SchemeUi;
+use super::stats::card_border;
+use super::State;
+use crate::font::{FONT_LABEL, FONT_MONO};
+use crate::ui::actions::Action;
+
+const HELP_ELEMENT_ID: &str = "Help";
+const KEY_WIDTH: f32 = 200.0;
+
+// Fixed bindings, the global shortcuts come from the state and may be changed in `keys.toml`
+const NAVIGATION: [(&str, &str); 7] = [
+    ("Tab / Shift+Tab", "Move the focus"),
+    ("Enter / Space", "Press the focused control"),
+    ("Up / Down", "Move through an open dropdown"),
+    ("Left / Right", "Move the focused slider"),
+    ("Escape", "Close the dropdown or this list"),
+    ("Ctrl+= / Ctrl+-", "Make the UI larger or smaller"),
+    ("Ctrl+0", "Reset the UI scale"),
+];
+
+fn text_config(scheme: &SchemeUi, colour: clay::Clay_Color, font: u16) -> clay::Clay_TextElementConfig {
+    clay::Clay_TextElementConfig {
+        fontId: font,
+        fontSize: scheme.stats.font_size,
+        textColor: colour,
+        ..Default::default()
+    }
+}
+
+fn define_card(scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_id(HELP_ELEMENT_ID)
+        .with_floating(
+            clay::ClayFloatingBuilder::new()
+                .with_attach_to(clay::ClayFloatingAttachToElement::Root)
+                .with_attach_points(
+                    clay::ClayFloatingAttachPointType::CenterCenter,
+                    clay::ClayFloatingAttachPointType::CenterCenter,
+                )
+                .with_zindex(20)
+                .build(),
+        )
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_padding(clay::Clay_Padding::padding_all(scheme.stats.padding))
+                .with_child_gap(3 * scheme.stats.padding)
+                .build(),
+        )
+        .with_background_color(scheme.stats.background)
+        .with_corner_radius(clay::Clay_CornerRadius::all(8.0))
+        .with_border(card_border(scheme))
+        .build()
+}
+
+fn define_column() -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_layout_direction(clay::ClayLayoutDirection::TopToBottom)
+                .with_child_gap(4)
+                .build(),
+        )
+        .build()
+}
+
+fn define_key_cell() -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_fixed(KEY_WIDTH),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .build(),
+        )
+        .build()
+}
+
+fn create_row(keys: clay::Clay_String, label: clay::Clay_String, scheme: &SchemeUi) {
+    let key_cfg = text_config(scheme, scheme.stats.title, FONT_MONO);
+    let cfg = text_config(scheme, scheme.stats.text, FONT_LABEL);
+    unsafe {
+        clay::clay!(
+            clay::ClayElementBuilder::new().build(),
+            clay::clay!(define_key_cell(), clay::clay_text!(keys, key_cfg)),
+            clay::clay_text!(label, cfg),
+        );
+    }
+}
+
+// Every binding, toggled with the help action and closed with Escape
+pub fn create_help_overlay(state: &mut State, scheme: &SchemeUi) {
+    if !state.show_help {
+        return;
+    }
+
+    let title_cfg = text_config(scheme, scheme.stats.title, FONT_LABEL);
+    unsafe {
+        clay::clay!(
+            define_card(scheme),
+            clay::clay!(
+                define_column(),
+                clay::clay_text!(clay::Clay_String::from_str("Shortcuts"), title_cfg),
+                {
+                    for action in Action::all() {
+                        let keys: Vec<String> = state.bindings.of(action).map(|shortcut| shortcut.name()).collect();
+                        if keys.is_empty() {
+                            continue;
+                        }
+                        let keys = state.text.push(keys.join(" / "));
+                        let label = state.text.push(action.label());
+                        create_row(keys, label, scheme);
+                    }
+                }
+            ),
+            clay::clay!(
+                define_column(),
+                clay::clay_text!(clay::Clay_String::from_str("Navigation"), title_cfg),
+                {
+                    for (keys, label) in NAVIGATION {
+                        create_row(clay::Clay_String::from_str(keys), clay::Clay_String::from_str(label), scheme);
+                    }
+                }
+            ),
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use super::clay;
+use super::scheme::SchemeUi;
 
 pub const LAYOUT_EXPAND: clay::Clay_Sizing = clay::Clay_Sizing::grow(0.0);
 
@@ -26,3 +27,37 @@ pub fn hline_separator(colour: clay::Clay_Color, min_height: f32, max_height: f3
         .with_background_color(colour)
         .build()
 }
+
+pub const SLIDER_HEIGHT: f32 = 20.0;
+
+// Track of a horizontal slider, the handler reads the pointer position against its bounding box
+pub fn define_slider_track(id: &'static str, scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_id(id)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::sizing_fixed(SLIDER_HEIGHT),
+                })
+                .build(),
+        )
+        .with_background_color(scheme.sidebar.button.default)
+        .with_corner_radius(clay::Clay_CornerRadius::all(SLIDER_HEIGHT / 2.0))
+        .build()
+}
+
+pub fn define_slider_fill(fraction: f32, scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_percent(fraction),
+                    height: clay::Clay_SizingAxis::sizing_grow(0.0),
+                })
+                .build(),
+        )
+        .with_background_color(scheme.chart.aes.fill)
+        .with_corner_radius(clay::Clay_CornerRadius::all(SLIDER_HEIGHT / 2.0))
+        .build()
+}
@@ -1,34 +1,98 @@
+pub mod bivariate;
 mod canvas;
 pub mod chart;
+pub mod clt;
 pub mod header;
+pub mod help;
 pub mod misc;
+pub mod palette;
+pub mod series;
 pub mod sidebar;
+pub mod stats;
+pub mod tooltip;
 
-use super::handlers;
+use super::{handlers, keyboard};
 
-use super::{scheme, DropDownState, HoverCallback, MenuState, State};
+use super::{consts, scheme, DropDownState, HoverCallback, MenuState, Series, State, Stream, TextArena};
 
-use crate::chart::{ChartData, ChartDataHistogram, ChartDataLine, CustomElementKind};
+use crate::chart::{ChartData, CustomElementKind};
 use crate::ffi::{clay, raylib};
 use crate::math::{self, cut};
+use crate::stats::SummaryStats;
 
 pub type CreateLayoutSignature =
     fn(*mut clay::Clay_Context, std::pin::Pin<&mut crate::ui::State>) -> clay::Clay_RenderCommandArray;
 
-pub fn create_chart_data<R>(gen_: &mut R, hist: &mut ChartDataHistogram, line: &mut ChartDataLine)
+pub fn create_chart_data<R>(gen_: &mut R, count: usize, bins: u16, data: &mut ChartData)
 where
     R: math::Distribution,
     <R as math::Distribution>::Value: Into<f32> + Copy,
 {
-    let n = 150;
-    let bins = 50;
-    line.clear();
-    hist.data.clear();
+    data.sample.clear();
+    data.sample
+        .extend(gen_.random_owned(count as u32).into_iter().map(|x| x.into()));
+    summarise_sample(gen_, bins, data);
+}
 
-    let sim = gen_.random_owned(n * 1000);
-    cut(&sim, bins as u16, &mut hist.data);
+// A sample read from a file, compared with the distribution of `gen_`
+pub fn create_input_data<R>(gen_: &R, input: &[f32], bins: u16, data: &mut ChartData)
+where
+    R: math::Distribution,
+{
+    data.sample.clear();
+    data.sample.extend_from_slice(input);
+    summarise_sample(gen_, bins, data);
+}
 
-    gen_.pdf(&mut line.x, &mut line.y);
+fn summarise_sample<R>(gen_: &R, bins: u16, data: &mut ChartData)
+where
+    R: math::Distribution,
+{
+    data.line.clear();
+    data.previous.clone_from(&data.hist);
+    data.hist.data.clear();
+    cut(&data.sample, bins, &mut data.hist.data);
+
+    data.stats = SummaryStats::from_sample(&data.sample, &mut data.sorted);
+    data.theory = SummaryStats::from_distribution(gen_);
+    data.hist.min = data.stats.min;
+    data.hist.max = data.stats.max;
+    data.boxplot.update(&data.sorted, &data.stats);
+
+    gen_.pdf(&mut data.line.x, &mut data.line.y);
+}
+
+// Means of `n` draws each, next to the Normal the central limit theorem says they tend to
+pub fn create_clt_data<R>(gen_: &mut R, n: usize, bins: u16, data: &mut ChartData)
+where
+    R: math::Distribution<Value = f32>,
+{
+    let samples = consts::CLT_SAMPLES;
+    let n = n.max(1);
+    data.line.clear();
+    data.previous.clone_from(&data.hist);
+    data.hist.data.clear();
+
+    let mut draws = vec![0.0; n];
+    data.sample.clear();
+    for _ in 0..samples {
+        gen_.random_batch(&mut draws);
+        data.sample.push(draws.iter().sum::<f32>() / (n as f32));
+    }
+    cut(&data.sample, bins, &mut data.hist.data);
+
+    data.stats = SummaryStats::from_sample(&data.sample, &mut data.sorted);
+    data.theory = SummaryStats::from_distribution(gen_).of_mean(n);
+    data.hist.min = data.stats.min;
+    data.hist.max = data.stats.max;
+    data.boxplot.update(&data.sorted, &data.stats);
+
+    let (mean, std_dev) = (data.theory.mean, data.theory.std_dev);
+    let points = 200;
+    for idx in 0..points {
+        let x = mean + std_dev * math::remap(idx as f32, 0.0, (points - 1) as f32, -4.0, 4.0);
+        data.line.push(x, math::normal_pdf(x, mean, std_dev));
+    }
 }
 
 unsafe fn init_layout(ctx: *mut clay::Clay_Context, state: &mut State, frame_time: f32) -> bool {
@@ -40,30 +104,32 @@ unsafe fn init_layout(ctx: *mut clay::Clay_Context, state: &mut State, frame_tim
                 clay::Clay_SetDebugModeEnabled(crate::DEBUGABLE.load(std::sync::atomic::Ordering::Acquire));
             }
         }
-        clay::Clay_SetLayoutDimensions(clay::Clay_Dimensions {
-            width: state.width as f32,
-            height: state.height as f32,
-        });
+        let (width, height) = state.layout_size();
+        clay::Clay_SetLayoutDimensions(clay::Clay_Dimensions { width, height });
 
         cfg_if::cfg_if! {
             if #[cfg(feature = "hot_reload")] {
-                match state.font.as_mut() {
-                    None => {
-                        eprintln!("Font is not defined, cannot initialize the layout");
-                        return false;
-                    },
-                    Some(font) => {
-                        let font_config = font as *mut raylib::Font as *mut std::ffi::c_void;
-                        clay::Clay_SetMeasureTextFunction(state.measure, font_config);
-                    }
+                if !state.fonts.is_loaded() {
+                    eprintln!("Font is not defined, cannot initialize the layout");
+                    return false;
                 }
+                let fonts = &mut state.fonts as *mut crate::font::FontRegistry as *mut std::ffi::c_void;
+                clay::Clay_SetMeasureTextFunction(state.measure, fonts);
             }
         }
 
+        // The chart view is in window pixels, clay works in layout pixels
         let mouse_position = raylib::GetMousePosition();
-        let scroll = raylib::GetMouseWheelMoveV();
-        clay::Clay_SetPointerState(mouse_position.into(), raylib::IsMouseButtonDown(0));
+        let scale = state.scale();
+        let mut scroll = raylib::GetMouseWheelMoveV();
+        if handlers::handle_chart_navigation(state, mouse_position, scroll) {
+            scroll = raylib::Vector2 { x: 0.0, y: 0.0 };
+        }
+        let layout_position = clay::Clay_Vector2 { x: mouse_position.x / scale, y: mouse_position.y / scale };
+        handlers::handle_sidebar_drag(state, layout_position);
+        clay::Clay_SetPointerState(layout_position, raylib::IsMouseButtonDown(0));
         clay::Clay_UpdateScrollContainers(true, scroll.into(), frame_time);
+        keyboard::handle_keyboard(state);
     }
     true
 }
@@ -82,6 +148,9 @@ pub fn create_layout(
         }
     }
     state_mut_ref.init();
+    state_mut_ref.text.clear();
+    state_mut_ref.step_stream();
+    state_mut_ref.step_monte_carlo();
 
     // ------------------------------------------------------------------------------------------------------
     // SAFETY: we should not create a &mut from this pointer until the end of the function
@@ -100,7 +169,7 @@ pub fn create_layout(
     let addr = addr as isize;
 
     let section_canvas = canvas::create_canvas(scheme);
-    let section_chart = chart::create_chart(state_mut_ref, scheme);
+    let section_chart = chart::create_chart(scheme);
 
     let content = clay::ClayElementBuilder::new()
         .with_id("Content")
@@ -113,6 +182,7 @@ pub fn create_layout(
         .build();
 
     sidebar::compute_sidebar_width(state_mut_ref);
+    state_mut_ref.focus.begin_frame();
     unsafe {
         clay::Clay_BeginLayout();
         clay::clay!(
@@ -122,8 +192,19 @@ pub fn create_layout(
             clay::clay!(
                 content,
                 sidebar::create_sidebar(state_mut_ref, scheme, addr),
-                clay::clay!(section_chart, clay::Clay_OnHover(Some(handlers::handle_chart_click), addr),)
-            )
+                sidebar::create_sidebar_splitter(state_mut_ref, scheme, addr),
+                clay::clay!(
+                    section_chart,
+                    clay::Clay_OnHover(Some(handlers::handle_chart_click), addr),
+                    chart::create_chart_title(state_mut_ref, scheme),
+                    chart::create_chart_legend(state_mut_ref, scheme, addr),
+                    chart::create_chart_plot(state_mut_ref, scheme),
+                    tooltip::create_tooltip(state_mut_ref, scheme),
+                    stats::create_stats_card(state_mut_ref, scheme, addr),
+                )
+            ),
+            help::create_help_overlay(state_mut_ref, scheme),
+            palette::create_command_palette(state_mut_ref, scheme, addr),
         );
         clay::Clay_EndLayout()
     }
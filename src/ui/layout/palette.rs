@@ -0,0 +1,167 @@
+use super::clay;
+use super::handlers::handle_palette_entry;
+use super::scheme::SchemeUi;
+use super::stats::card_border;
+use super::State;
+use crate::font::{FONT_LABEL, FONT_MONO};
+use crate::ui::palette::PALETTE_ELEMENT_ID;
+
+const ENTRY_ELEMENT_ID: &str = "PaletteEntry";
+const PALETTE_WIDTH: f32 = 560.0;
+const PALETTE_TOP: f32 = 80.0; // Below the header
+const MAX_ROWS: usize = 10; // Entries shown at once, the list scrolls with the selection
+
+fn text_config(scheme: &SchemeUi, colour: clay::Clay_Color, font: u16) -> clay::Clay_TextElementConfig {
+    clay::Clay_TextElementConfig {
+        fontId: font,
+        fontSize: scheme.stats.font_size,
+        textColor: colour,
+        ..Default::default()
+    }
+}
+
+fn define_card(scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_id(PALETTE_ELEMENT_ID)
+        .with_floating(
+            clay::ClayFloatingBuilder::new()
+                .with_attach_to(clay::ClayFloatingAttachToElement::Root)
+                .with_attach_points(
+                    clay::ClayFloatingAttachPointType::CenterTop,
+                    clay::ClayFloatingAttachPointType::CenterTop,
+                )
+                .with_offset(0.0, PALETTE_TOP)
+                .with_zindex(30)
+                .build(),
+        )
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_fixed(PALETTE_WIDTH),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_layout_direction(clay::ClayLayoutDirection::TopToBottom)
+                .with_padding(clay::Clay_Padding::padding_all(scheme.stats.padding))
+                .with_child_gap(4)
+                .build(),
+        )
+        .with_background_color(scheme.stats.background)
+        .with_corner_radius(clay::Clay_CornerRadius::all(8.0))
+        .with_border(card_border(scheme))
+        .build()
+}
+
+fn define_field(scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_padding(clay::Clay_Padding { left: 8, right: 8, top: 6, bottom: 6 })
+                .build(),
+        )
+        .with_background_color(scheme.sidebar.button.default)
+        .with_corner_radius(clay::Clay_CornerRadius::all(5.0))
+        .build()
+}
+
+fn define_entry(index: usize, selected: bool, scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id_index(ENTRY_ELEMENT_ID, index as u32)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_padding(clay::Clay_Padding { left: 8, right: 8, top: 4, bottom: 4 })
+                .build(),
+        )
+        .with_corner_radius(clay::Clay_CornerRadius::all(5.0))
+        .build();
+
+    if selected {
+        ele.backgroundColor = scheme.sidebar.button.pressed;
+    } else if unsafe { clay::Clay_PointerOver(ele.id) } {
+        ele.backgroundColor = scheme.sidebar.button.hover;
+    }
+    ele
+}
+
+// Pushes the shortcut to the right end of the entry
+fn define_spacer() -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .build(),
+        )
+        .build()
+}
+
+// Opened with the palette action (Ctrl+K), lists the matching actions under the query
+pub fn create_command_palette(state: &mut State, scheme: &SchemeUi, opaque_state_ptr: isize) {
+    let Some(palette) = state.palette.as_ref() else {
+        return;
+    };
+    let (query, matches, selected) = (palette.query.clone(), palette.matches.clone(), palette.selected);
+    let (prompt, error) = (palette.prompt, palette.error.clone());
+
+    let cfg = text_config(scheme, scheme.stats.text, FONT_LABEL);
+    let hint_cfg = text_config(scheme, scheme.stats.title, FONT_LABEL);
+    let key_cfg = text_config(scheme, scheme.stats.title, FONT_MONO);
+
+    // Keeps the selection in view
+    let first = (selected + 1).saturating_sub(MAX_ROWS);
+    unsafe {
+        clay::clay!(
+            define_card(scheme),
+            {
+                if let Some(prompt) = prompt {
+                    clay::clay_text!(clay::Clay_String::from_str(prompt.title()), hint_cfg);
+                }
+            },
+            clay::clay!(define_field(scheme), {
+                if query.is_empty() {
+                    let hint = if prompt.is_some() {
+                        "Type a file path"
+                    } else {
+                        "Type a command"
+                    };
+                    clay::clay_text!(clay::Clay_String::from_str(hint), hint_cfg);
+                } else {
+                    let txt = state.text.push(format!("{query}_"));
+                    clay::clay_text!(txt, cfg);
+                }
+            }),
+            {
+                if prompt.is_some() {
+                    let txt = match error {
+                        Some(error) => state.text.push(error),
+                        None => clay::Clay_String::from_str("Enter to confirm, Escape to cancel"),
+                    };
+                    clay::clay_text!(txt, hint_cfg);
+                } else if matches.is_empty() {
+                    clay::clay_text!(clay::Clay_String::from_str("No matching command"), hint_cfg);
+                }
+                for (index, action) in matches.iter().enumerate().skip(first).take(MAX_ROWS) {
+                    let label = state.text.push(action.label());
+                    let keys: Vec<String> = state.bindings.of(*action).map(|shortcut| shortcut.name()).collect();
+                    let keys = state.text.push(keys.join(" / "));
+                    clay::clay!(
+                        define_entry(index, index == selected, scheme),
+                        clay::Clay_OnHover(Some(handle_palette_entry), opaque_state_ptr),
+                        clay::clay_text!(label, cfg),
+                        clay::clay!(define_spacer()),
+                        clay::clay_text!(keys, key_cfg),
+                    );
+                }
+            },
+        );
+    }
+}
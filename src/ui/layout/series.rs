@@ -0,0 +1,251 @@
+use super::clay;
+use super::consts;
+use super::handlers::{
+    handle_add_series, handle_primary_toggle, handle_series_cycle, handle_series_remove, handle_series_toggle,
+    handle_small_multiples, HandlerFn,
+};
+use super::keyboard::Focus;
+use super::scheme::SchemeUi;
+use super::{Series, State};
+
+const SERIES_PANEL_ELEMENT_ID: &str = "SeriesPanel";
+const SERIES_PRIMARY_ELEMENT_ID: &str = "SeriesPrimary";
+const SERIES_TOGGLE_ELEMENT_ID: &str = "SeriesToggle";
+const SERIES_CYCLE_ELEMENT_ID: &str = "SeriesCycle";
+const SERIES_REMOVE_ELEMENT_ID: &str = "SeriesRemove";
+const SERIES_ADD_ELEMENT_ID: &str = "SeriesAdd";
+const SERIES_LAYOUT_ELEMENT_ID: &str = "SeriesLayout";
+
+const SWATCH_SIZE: f32 = 16.0;
+const SMALL_BUTTON_WIDTH: f32 = 32.0;
+
+fn define_panel() -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_id(SERIES_PANEL_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_layout_direction(clay::ClayLayoutDirection::TopToBottom)
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_child_gap(4)
+                .build(),
+        )
+        .build()
+}
+
+fn define_row() -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_child_gap(4)
+                .build(),
+        )
+        .build()
+}
+
+fn define_button(
+    id: clay::Clay_ElementId,
+    width: clay::Clay_SizingAxis,
+    scheme: &SchemeUi,
+) -> clay::Clay_ElementDeclaration {
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing { width, height: clay::Clay_SizingAxis::default() })
+                .with_padding(clay::Clay_Padding { left: 8, right: 8, top: 8, bottom: 8 })
+                .with_child_gap(8)
+                .with_child_alignment(clay::Clay_ChildAlignment {
+                    x: clay::ClayChildAlignmentX::Left as u8,
+                    y: clay::ClayChildAlignmentY::Center as u8,
+                })
+                .build(),
+        )
+        .with_background_color(scheme.sidebar.button.default)
+        .with_corner_radius(clay::Clay_CornerRadius::all(8.0))
+        .build();
+
+    ele.id = id;
+    if unsafe { clay::Clay_PointerOver(ele.id) } {
+        ele.backgroundColor = scheme.sidebar.button.hover;
+    }
+    ele
+}
+
+fn indexed_id(id: &'static str, index: usize) -> clay::Clay_ElementId {
+    clay::ClayElementBuilder::new()
+        .with_id_index(id, index as u32)
+        .build()
+        .id
+}
+
+fn swatch(colour: clay::Clay_Color, visible: bool) -> clay::Clay_ElementDeclaration {
+    let mut colour = colour;
+    if !visible {
+        colour.a = 60.0;
+    }
+
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing::fixed(SWATCH_SIZE))
+                .build(),
+        )
+        .with_background_color(colour)
+        .with_corner_radius(clay::Clay_CornerRadius::all(3.0))
+        .build()
+}
+
+// What a series toggle shows, the name of the distribution next to a swatch of the series colour
+#[derive(Clone, Copy)]
+struct ToggleConfig {
+    name: &'static str,
+    colour: clay::Clay_Color,
+    visible: bool, // Fades the swatch out when false
+}
+
+fn create_toggle(
+    focus: &mut Focus,
+    id: clay::Clay_ElementId,
+    toggle: ToggleConfig,
+    action: (HandlerFn, isize),
+    scheme: &SchemeUi,
+    cfg: clay::Clay_TextElementConfig,
+) {
+    let txt = clay::Clay_String::from_str(toggle.name);
+    let (handler, user_data) = action;
+    unsafe {
+        clay::clay!(
+            define_button(id, clay::Clay_SizingAxis::sizing_grow(0.0), scheme),
+            focus.on_press(id, handler, user_data),
+            clay::clay!(swatch(toggle.colour, toggle.visible)),
+            clay::clay_text!(txt, cfg),
+        );
+    }
+}
+
+fn create_button(
+    focus: &mut Focus,
+    id: clay::Clay_ElementId,
+    width: clay::Clay_SizingAxis,
+    label: &'static str,
+    action: (HandlerFn, isize),
+    scheme: &SchemeUi,
+    cfg: clay::Clay_TextElementConfig,
+) {
+    let txt = clay::Clay_String::from_str(label);
+    let (handler, user_data) = action;
+    unsafe {
+        clay::clay!(
+            define_button(id, width, scheme),
+            focus.on_press(id, handler, user_data),
+            clay::clay_text!(txt, cfg),
+        );
+    }
+}
+
+fn create_series_row(focus: &mut Focus, series: *mut Series, scheme: &SchemeUi, cfg: clay::Clay_TextElementConfig) {
+    // SAFETY: the cycle and remove handlers may move the series, so everything is read up front
+    let (index, name, colour, visible) = match unsafe { series.as_ref() } {
+        None => return,
+        Some(s) => (s.index, s.generator.name(), s.colour, s.visible),
+    };
+    let colour = scheme.chart.aes.series[colour % scheme.chart.aes.series.len()];
+    let addr = series.expose_provenance() as isize;
+
+    unsafe {
+        clay::clay!(
+            define_row(),
+            create_toggle(
+                focus,
+                indexed_id(SERIES_TOGGLE_ELEMENT_ID, index),
+                ToggleConfig { name, colour, visible },
+                (handle_series_toggle, addr),
+                scheme,
+                cfg,
+            ),
+            create_button(
+                focus,
+                indexed_id(SERIES_CYCLE_ELEMENT_ID, index),
+                clay::Clay_SizingAxis::sizing_fixed(SMALL_BUTTON_WIDTH),
+                ">",
+                (handle_series_cycle, addr),
+                scheme,
+                cfg,
+            ),
+            create_button(
+                focus,
+                indexed_id(SERIES_REMOVE_ELEMENT_ID, index),
+                clay::Clay_SizingAxis::sizing_fixed(SMALL_BUTTON_WIDTH),
+                "x",
+                (handle_series_remove, addr),
+                scheme,
+                cfg,
+            ),
+        );
+    }
+}
+
+pub fn create_series_panel(
+    state: &mut State,
+    scheme: &SchemeUi,
+    opaque_state_ptr: isize,
+    txt_cfg: clay::Clay_TextElementConfig,
+) {
+    let layout_name = if state.small_multiples { "Grid" } else { "Overlay" };
+    let can_add = state.series.len() + 1 < consts::MAX_SERIES;
+
+    unsafe {
+        clay::clay!(
+            define_panel(),
+            create_toggle(
+                &mut state.focus,
+                indexed_id(SERIES_PRIMARY_ELEMENT_ID, 0),
+                ToggleConfig {
+                    name: state.generator.name(),
+                    colour: scheme.chart.aes.fill,
+                    visible: state.primary_visible,
+                },
+                (handle_primary_toggle, opaque_state_ptr),
+                scheme,
+                txt_cfg,
+            ),
+            {
+                // The handlers can drop a series while we iterate, hence the bounds check on every step
+                let mut index = 0;
+                while index < state.series.len() {
+                    let series = &mut state.series[index] as *mut Series;
+                    create_series_row(&mut state.focus, series, scheme, txt_cfg);
+                    index += 1;
+                }
+            },
+            clay::clay!(define_row(), {
+                if can_add {
+                    create_button(
+                        &mut state.focus,
+                        indexed_id(SERIES_ADD_ELEMENT_ID, 0),
+                        clay::Clay_SizingAxis::sizing_grow(0.0),
+                        "Add series",
+                        (handle_add_series, opaque_state_ptr),
+                        scheme,
+                        txt_cfg,
+                    );
+                }
+                create_button(
+                    &mut state.focus,
+                    indexed_id(SERIES_LAYOUT_ELEMENT_ID, 0),
+                    clay::Clay_SizingAxis::sizing_grow(0.0),
+                    layout_name,
+                    (handle_small_multiples, opaque_state_ptr),
+                    scheme,
+                    txt_cfg,
+                );
+            }),
+        );
+    }
+}
@@ -1,21 +1,54 @@
+use super::bivariate::create_correlation_panel;
 use super::clay;
+use super::clt::create_clt_panel;
 use super::handlers::{
-    HandlerFn, handle_chart_dropdown_menu_item_click, handle_distribution_dropdown_menu_item_click, handle_menu_click,
-    handle_sidebar_click, handle_simulate,
+    handle_bandwidth_decrease, handle_bandwidth_increase, handle_bandwidth_reset,
+    handle_chart_dropdown_menu_item_click, handle_distribution_dropdown_menu_item_click, handle_easing_cycle,
+    handle_experiment_cycle, handle_experiment_toggle, handle_kde_dropdown_menu_item_click, handle_menu_click,
+    handle_path_count_cycle, handle_process_cycle, handle_reference_dropdown_menu_item_click, handle_reveal_cycle,
+    handle_sidebar_click, handle_simulate, handle_splitter_grab, handle_stream, handle_xscale_cycle,
+    handle_yscale_cycle, HandlerFn,
 };
-use super::math;
+use super::keyboard::Focus;
 use super::misc::hline_separator;
 use super::scheme::SchemeUi;
+use super::series::create_series_panel;
 use super::{DropDownState, MenuState, State};
+use crate::chart::ChartKind;
+use crate::font::FONT_LABEL;
+use crate::stats::Bandwidth;
 
 const CHART_MENU_TITLE_ELEMENT_ID: &str = "ChartMenuTitle";
 const CHART_MENU_ELEMENT_ID: &str = "ChartMenu";
 const DIST_MENU_TITLE_ELEMENT_ID: &str = "DistMenuTitle";
 const DIST_MENU_ELEMENT_ID: &str = "DistMenu";
+const KDE_MENU_TITLE_ELEMENT_ID: &str = "KdeMenuTitle";
+const KDE_MENU_ELEMENT_ID: &str = "KdeMenu";
+const REFERENCE_MENU_TITLE_ELEMENT_ID: &str = "ReferenceMenuTitle";
+const REFERENCE_MENU_ELEMENT_ID: &str = "ReferenceMenu";
+const BANDWIDTH_ELEMENT_ID: &str = "Bandwidth";
+const BANDWIDTH_DECREASE_ELEMENT_ID: &str = "BandwidthDecrease";
+const BANDWIDTH_INCREASE_ELEMENT_ID: &str = "BandwidthIncrease";
+const BANDWIDTH_RESET_ELEMENT_ID: &str = "BandwidthReset";
+const SCALE_ELEMENT_ID: &str = "Scale";
+const SCALE_X_ELEMENT_ID: &str = "ScaleX";
+const SCALE_Y_ELEMENT_ID: &str = "ScaleY";
+const ANIMATION_ELEMENT_ID: &str = "Animation";
+const EASING_ELEMENT_ID: &str = "Easing";
+const REVEAL_ELEMENT_ID: &str = "Reveal";
+const PROCESS_ELEMENT_ID: &str = "Process";
+const PROCESS_KIND_ELEMENT_ID: &str = "ProcessKind";
+const PROCESS_PATHS_ELEMENT_ID: &str = "ProcessPaths";
+const MONTE_CARLO_ELEMENT_ID: &str = "MonteCarlo";
+const MONTE_CARLO_EXPERIMENT_ELEMENT_ID: &str = "MonteCarloExperiment";
+const MONTE_CARLO_RUN_ELEMENT_ID: &str = "MonteCarloRun";
 
 pub const MIN_SIDEBAR_WIDTH: f32 = 220.0;
 pub const MAX_SIDEBAR_WIDTH: f32 = 300.0;
 pub const MAX_SIDEBAR_SPACE_RATIO: f32 = 0.15;
+pub const MAX_SIDEBAR_DRAG_RATIO: f32 = 0.5; // Of the window, the splitter cannot go further
+const SPLITTER_ELEMENT_ID: &str = "SidebarSplitter";
+const SPLITTER_WIDTH: f32 = 8.0;
 
 fn define_side_bar(scheme: &SchemeUi, width: f32) -> clay::Clay_ElementDeclaration {
     clay::ClayElementBuilder::new()
@@ -92,12 +125,78 @@ pub fn create_chart_menu(
 ) {
     let menu_title = state.chart.title.unwrap_or(state.chart.dropdown[0].name);
     let menu_ptr = &mut state.chart as *mut MenuState;
+    let menu = DropdownConfig {
+        title: menu_title,
+        title_id: CHART_MENU_TITLE_ELEMENT_ID,
+        menu_id: CHART_MENU_ELEMENT_ID,
+        item_handler: handle_chart_dropdown_menu_item_click,
+    };
+    create_dropdown_menu(&mut state.focus, menu_ptr, menu, scheme, txt_cfg, text_alignment, within_sidebar);
+}
+
+pub fn create_dist_menu(
+    state: &mut State,
+    scheme: &SchemeUi,
+    txt_cfg: clay::Clay_TextElementConfig,
+    text_alignment: clay::ClayChildAlignmentX,
+    within_sidebar: bool,
+) {
+    let dist_title = state.dist.title.unwrap_or(state.dist.dropdown[0].name);
+    let dist_ptr = &mut state.dist as *mut MenuState;
+    let menu = DropdownConfig {
+        title: dist_title,
+        title_id: DIST_MENU_TITLE_ELEMENT_ID,
+        menu_id: DIST_MENU_ELEMENT_ID,
+        item_handler: handle_distribution_dropdown_menu_item_click,
+    };
+    create_dropdown_menu(&mut state.focus, dist_ptr, menu, scheme, txt_cfg, text_alignment, within_sidebar);
+}
+
+pub fn create_kde_menu(
+    state: &mut State,
+    scheme: &SchemeUi,
+    txt_cfg: clay::Clay_TextElementConfig,
+    text_alignment: clay::ClayChildAlignmentX,
+    within_sidebar: bool,
+) {
+    let kde_title = state.kde_menu.title.unwrap_or(state.kde_menu.dropdown[0].name);
+    let kde_ptr = &mut state.kde_menu as *mut MenuState;
+    let menu = DropdownConfig {
+        title: kde_title,
+        title_id: KDE_MENU_TITLE_ELEMENT_ID,
+        menu_id: KDE_MENU_ELEMENT_ID,
+        item_handler: handle_kde_dropdown_menu_item_click,
+    };
+    create_dropdown_menu(&mut state.focus, kde_ptr, menu, scheme, txt_cfg, text_alignment, within_sidebar);
+}
+
+pub fn create_reference_menu(
+    state: &mut State,
+    scheme: &SchemeUi,
+    txt_cfg: clay::Clay_TextElementConfig,
+    text_alignment: clay::ClayChildAlignmentX,
+    within_sidebar: bool,
+) {
+    let kind = state.chart_data.as_ref().map(|data| data.kind).unwrap_or_default();
+    if !matches!(kind, ChartKind::QQ | ChartKind::PP) {
+        return;
+    }
+
+    let reference_title = state
+        .reference_menu
+        .title
+        .unwrap_or(state.reference_menu.dropdown[0].name);
+    let reference_ptr = &mut state.reference_menu as *mut MenuState;
+    let menu = DropdownConfig {
+        title: reference_title,
+        title_id: REFERENCE_MENU_TITLE_ELEMENT_ID,
+        menu_id: REFERENCE_MENU_ELEMENT_ID,
+        item_handler: handle_reference_dropdown_menu_item_click,
+    };
     create_dropdown_menu(
-        menu_ptr,
-        menu_title,
-        CHART_MENU_TITLE_ELEMENT_ID,
-        CHART_MENU_ELEMENT_ID,
-        handle_chart_dropdown_menu_item_click,
+        &mut state.focus,
+        reference_ptr,
+        menu,
         scheme,
         txt_cfg,
         text_alignment,
@@ -105,22 +204,280 @@ pub fn create_chart_menu(
     );
 }
 
-pub fn create_dist_menu(
+fn define_bandwidth_button(id: &'static str, scheme: &SchemeUi, within_sidebar: bool) -> clay::Clay_ElementDeclaration {
+    let height = if within_sidebar {
+        clay::Clay_SizingAxis::default()
+    } else {
+        clay::Clay_SizingAxis::sizing_grow(0.0)
+    };
+
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id(id)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing { width: clay::Clay_SizingAxis::sizing_fixed(40.0), height })
+                .with_padding(clay::Clay_Padding { left: 0, right: 0, top: 16, bottom: 16 })
+                .with_child_alignment(clay::Clay_ChildAlignment {
+                    x: clay::ClayChildAlignmentX::Center as u8,
+                    y: clay::ClayChildAlignmentY::Center as u8,
+                })
+                .build(),
+        )
+        .with_background_color(scheme.sidebar.button.default)
+        .with_corner_radius(clay::Clay_CornerRadius::all(8.0))
+        .build();
+
+    if unsafe { clay::Clay_PointerOver(ele.id) } {
+        ele.backgroundColor = scheme.sidebar.button.hover;
+    }
+    ele
+}
+
+pub fn create_bandwidth_control(
     state: &mut State,
     scheme: &SchemeUi,
+    opaque_state_ptr: isize,
     txt_cfg: clay::Clay_TextElementConfig,
     text_alignment: clay::ClayChildAlignmentX,
     within_sidebar: bool,
 ) {
-    let dist_title = state.dist.title.unwrap_or(state.dist.dropdown[0].name);
-    let dist_ptr = &mut state.dist as *mut MenuState;
-    create_dropdown_menu(
-        dist_ptr,
-        dist_title,
-        DIST_MENU_TITLE_ELEMENT_ID,
-        DIST_MENU_ELEMENT_ID,
-        handle_distribution_dropdown_menu_item_click,
+    if state.kde.is_none() {
+        return;
+    }
+
+    let bandwidth = state.chart_data.as_ref().map(|data| data.kde_bandwidth).unwrap_or(0.0);
+    let label = match state.kde_bandwidth {
+        Bandwidth::Silverman => format!("h: {bandwidth:.3} (auto)"),
+        Bandwidth::Manual(h) => format!("h: {h:.3}"),
+    };
+    let label = state.text.push(label);
+
+    let (mut reset, _) =
+        define_sidebar_menu_front(scheme, "", BANDWIDTH_RESET_ELEMENT_ID, text_alignment, within_sidebar);
+    reset.layout.sizing.width = clay::Clay_SizingAxis::sizing_grow(0.0);
+
+    let sizing = if within_sidebar {
+        clay::Clay_Sizing {
+            width: clay::Clay_SizingAxis::sizing_grow(0.0),
+            height: clay::Clay_SizingAxis::default(),
+        }
+    } else {
+        clay::Clay_Sizing {
+            width: clay::Clay_SizingAxis::default(),
+            height: clay::Clay_SizingAxis::sizing_grow(0.0),
+        }
+    };
+
+    let row = clay::ClayElementBuilder::new()
+        .with_id(BANDWIDTH_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(sizing)
+                .with_child_gap(4)
+                .build(),
+        )
+        .build();
+
+    let minus = clay::Clay_String::from_str("-");
+    let plus = clay::Clay_String::from_str("+");
+    let decrease = define_bandwidth_button(BANDWIDTH_DECREASE_ELEMENT_ID, scheme, within_sidebar);
+    let increase = define_bandwidth_button(BANDWIDTH_INCREASE_ELEMENT_ID, scheme, within_sidebar);
+    let focus = &mut state.focus;
+    unsafe {
+        clay::clay!(
+            row,
+            clay::clay!(
+                decrease,
+                focus.on_press(decrease.id, handle_bandwidth_decrease, opaque_state_ptr),
+                clay::clay_text!(minus, txt_cfg),
+            ),
+            clay::clay!(
+                reset,
+                focus.on_press(reset.id, handle_bandwidth_reset, opaque_state_ptr),
+                clay::clay_text!(label, txt_cfg),
+            ),
+            clay::clay!(
+                increase,
+                focus.on_press(increase.id, handle_bandwidth_increase, opaque_state_ptr),
+                clay::clay_text!(plus, txt_cfg),
+            ),
+        );
+    }
+}
+
+// A row of buttons each cycling through the values of one setting on click
+struct CycleRowConfig {
+    id: &'static str,
+    buttons: [(&'static str, clay::Clay_String, HandlerFn); 2], // Id, label and handler of each button
+}
+
+fn create_cycle_row(
+    focus: &mut Focus,
+    row: CycleRowConfig,
+    scheme: &SchemeUi,
+    opaque_state_ptr: isize,
+    txt_cfg: clay::Clay_TextElementConfig,
+    text_alignment: clay::ClayChildAlignmentX,
+    within_sidebar: bool,
+) {
+    let sizing = if within_sidebar {
+        clay::Clay_Sizing {
+            width: clay::Clay_SizingAxis::sizing_grow(0.0),
+            height: clay::Clay_SizingAxis::default(),
+        }
+    } else {
+        clay::Clay_Sizing {
+            width: clay::Clay_SizingAxis::default(),
+            height: clay::Clay_SizingAxis::sizing_grow(0.0),
+        }
+    };
+
+    let ele = clay::ClayElementBuilder::new()
+        .with_id(row.id)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(sizing)
+                .with_child_gap(4)
+                .build(),
+        )
+        .build();
+
+    unsafe {
+        clay::clay!(ele, {
+            for (id, label, handler) in row.buttons {
+                let (mut button, _) = define_sidebar_menu_front(scheme, "", id, text_alignment, within_sidebar);
+                if within_sidebar {
+                    button.layout.sizing.width = clay::Clay_SizingAxis::sizing_grow(0.0);
+                }
+                let id = button.id;
+                clay::clay!(
+                    button,
+                    focus.on_press(id, handler, opaque_state_ptr),
+                    clay::clay_text!(label, txt_cfg),
+                );
+            }
+        });
+    }
+}
+
+// One button per axis, a click moves on to the next scale
+pub fn create_scale_control(
+    state: &mut State,
+    scheme: &SchemeUi,
+    opaque_state_ptr: isize,
+    txt_cfg: clay::Clay_TextElementConfig,
+    text_alignment: clay::ClayChildAlignmentX,
+    within_sidebar: bool,
+) {
+    let xlabel = state.text.push(format!("x: {}", state.xscale.name()));
+    let ylabel = state.text.push(format!("y: {}", state.yscale.name()));
+    create_cycle_row(
+        &mut state.focus,
+        CycleRowConfig {
+            id: SCALE_ELEMENT_ID,
+            buttons: [
+                (SCALE_X_ELEMENT_ID, xlabel, handle_xscale_cycle),
+                (SCALE_Y_ELEMENT_ID, ylabel, handle_yscale_cycle),
+            ],
+        },
         scheme,
+        opaque_state_ptr,
+        txt_cfg,
+        text_alignment,
+        within_sidebar,
+    );
+}
+
+pub fn create_animation_control(
+    state: &mut State,
+    scheme: &SchemeUi,
+    opaque_state_ptr: isize,
+    txt_cfg: clay::Clay_TextElementConfig,
+    text_alignment: clay::ClayChildAlignmentX,
+    within_sidebar: bool,
+) {
+    let easing = state.text.push(state.animation.easing.name().to_string());
+    let reveal = state.text.push(state.animation.reveal.name().to_string());
+    create_cycle_row(
+        &mut state.focus,
+        CycleRowConfig {
+            id: ANIMATION_ELEMENT_ID,
+            buttons: [
+                (EASING_ELEMENT_ID, easing, handle_easing_cycle),
+                (REVEAL_ELEMENT_ID, reveal, handle_reveal_cycle),
+            ],
+        },
+        scheme,
+        opaque_state_ptr,
+        txt_cfg,
+        text_alignment,
+        within_sidebar,
+    );
+}
+
+// Only shown with the sample paths chart
+pub fn create_process_control(
+    state: &mut State,
+    scheme: &SchemeUi,
+    opaque_state_ptr: isize,
+    txt_cfg: clay::Clay_TextElementConfig,
+    text_alignment: clay::ClayChildAlignmentX,
+    within_sidebar: bool,
+) {
+    if state.chart_kind() != ChartKind::Paths {
+        return;
+    }
+
+    let process = clay::Clay_String::from_str(state.process.name());
+    let paths = state.text.push(format!("Paths: {}", state.path_count));
+    create_cycle_row(
+        &mut state.focus,
+        CycleRowConfig {
+            id: PROCESS_ELEMENT_ID,
+            buttons: [
+                (PROCESS_KIND_ELEMENT_ID, process, handle_process_cycle),
+                (PROCESS_PATHS_ELEMENT_ID, paths, handle_path_count_cycle),
+            ],
+        },
+        scheme,
+        opaque_state_ptr,
+        txt_cfg,
+        text_alignment,
+        within_sidebar,
+    );
+}
+
+// Picks the Monte Carlo experiment, or none, and pauses the trials
+pub fn create_monte_carlo_control(
+    state: &mut State,
+    scheme: &SchemeUi,
+    opaque_state_ptr: isize,
+    txt_cfg: clay::Clay_TextElementConfig,
+    text_alignment: clay::ClayChildAlignmentX,
+    within_sidebar: bool,
+) {
+    let (experiment, run) = match state.monte_carlo {
+        None => (clay::Clay_String::from_str("MC: Off"), "Run"),
+        Some(ref mc) => (
+            state.text.push(format!("MC: {}", mc.experiment.name())),
+            if mc.running { "Pause" } else { "Resume" },
+        ),
+    };
+    create_cycle_row(
+        &mut state.focus,
+        CycleRowConfig {
+            id: MONTE_CARLO_ELEMENT_ID,
+            buttons: [
+                (MONTE_CARLO_EXPERIMENT_ELEMENT_ID, experiment, handle_experiment_cycle),
+                (
+                    MONTE_CARLO_RUN_ELEMENT_ID,
+                    clay::Clay_String::from_str(run),
+                    handle_experiment_toggle,
+                ),
+            ],
+        },
+        scheme,
+        opaque_state_ptr,
         txt_cfg,
         text_alignment,
         within_sidebar,
@@ -143,18 +500,24 @@ fn define_sidebar_menu_front(
     (ele, clay_str)
 }
 
+// A title button opening a floating list of items, all sharing the same click handler
+struct DropdownConfig {
+    title: &'static str,
+    title_id: &'static str,
+    menu_id: &'static str,
+    item_handler: HandlerFn,
+}
+
 fn create_dropdown_menu(
+    focus: &mut Focus,
     state: *mut MenuState,
-    menu_title: &'static str,
-    menu_title_id: &'static str,
-    menu_id: &'static str,
-    dropdown_item_handler: HandlerFn,
+    menu: DropdownConfig,
     scheme: &SchemeUi,
     txt_cfg: clay::Clay_TextElementConfig,
     text_alignment: clay::ClayChildAlignmentX,
     within_sidebar: bool,
 ) {
-    let (ele, clay_str) = define_sidebar_menu_front(scheme, menu_title, menu_title_id, text_alignment, within_sidebar);
+    let (ele, clay_str) = define_sidebar_menu_front(scheme, menu.title, menu.title_id, text_alignment, within_sidebar);
 
     unsafe {
         if let Some(s) = state.as_mut() {
@@ -165,8 +528,8 @@ fn create_dropdown_menu(
         }
     }
 
-    let menu = clay::ClayElementBuilder::new()
-        .with_id(menu_id)
+    let menu_ele = clay::ClayElementBuilder::new()
+        .with_id(menu.menu_id)
         .with_floating(
             clay::ClayFloatingBuilder::new()
                 .with_attach_to(clay::ClayFloatingAttachToElement::Parent)
@@ -199,18 +562,19 @@ fn create_dropdown_menu(
 
     let user_data = state.expose_provenance();
     let user_data = user_data as isize;
+    let title_id = ele.id;
     unsafe {
         clay::clay!(
             ele,
-            clay::Clay_OnHover(Some(handle_menu_click), user_data),
+            focus.on_press(title_id, handle_menu_click, user_data),
             clay::clay_text!(clay_str, txt_cfg),
             {
                 if (*state).pressed {
                     clay::clay!(
-                        menu,
+                        menu_ele,
                         clay::clay!(menu_options, {
                             for dd in (*state).dropdown.iter_mut() {
-                                create_dropdown_item(dd, dd.name, dropdown_item_handler, scheme);
+                                create_dropdown_item(focus, title_id, dd, dd.name, menu.item_handler, scheme);
                             }
                         })
                     )
@@ -220,12 +584,19 @@ fn create_dropdown_menu(
     }
 }
 
-fn create_dropdown_item(state: &mut DropDownState, name: &'static str, handler: HandlerFn, scheme: &SchemeUi) {
+fn create_dropdown_item(
+    focus: &mut Focus,
+    menu: clay::Clay_ElementId,
+    state: &mut DropDownState,
+    name: &'static str,
+    handler: HandlerFn,
+    scheme: &SchemeUi,
+) {
     let clay_str = clay::Clay_String::from_str(name);
     let mut txt_cfg = clay::Clay_TextElementConfig::default();
-    txt_cfg.fontId = 0;
+    txt_cfg.fontId = FONT_LABEL;
     txt_cfg.fontSize = 24;
-    txt_cfg.textColor = clay::CLAY_WHITE;
+    txt_cfg.textColor = scheme.sidebar.text;
 
     let mut ele = clay::ClayElementBuilder::new()
         .with_id(name)
@@ -250,10 +621,18 @@ fn create_dropdown_item(state: &mut DropDownState, name: &'static str, handler:
 
     let addr = (state as *mut DropDownState).expose_provenance();
     let addr = addr as isize;
-    unsafe { clay::clay!(ele, clay::Clay_OnHover(Some(handler), addr), clay::clay_text!(clay_str, txt_cfg),) }
+    let id = ele.id;
+    unsafe {
+        clay::clay!(
+            ele,
+            focus.on_press_in_menu(menu, id, handler, addr),
+            clay::clay_text!(clay_str, txt_cfg),
+        )
+    }
 }
 
 pub fn create_sim_button(
+    focus: &mut Focus,
     scheme: &SchemeUi,
     opaque_state_ptr: isize,
     cfg: clay::Clay_TextElementConfig,
@@ -261,34 +640,95 @@ pub fn create_sim_button(
     within_sidebar: bool,
 ) {
     let (ele, txt) = define_sidebar_menu_front(scheme, "Simulate", "SimulateID", text_alignment, within_sidebar);
+    let id = ele.id;
     unsafe {
         clay::clay!(
             ele,
             clay::clay_text!(txt, cfg),
-            clay::Clay_OnHover(Some(handle_simulate), opaque_state_ptr)
+            focus.on_press(id, handle_simulate, opaque_state_ptr)
         );
     }
 }
 
+pub fn create_stream_button(
+    state: &mut State,
+    scheme: &SchemeUi,
+    opaque_state_ptr: isize,
+    cfg: clay::Clay_TextElementConfig,
+    text_alignment: clay::ClayChildAlignmentX,
+    within_sidebar: bool,
+) {
+    let label = state.stream.as_ref().map(|stream| stream.label()).unwrap_or("Stream");
+    let (ele, txt) = define_sidebar_menu_front(scheme, label, "StreamID", text_alignment, within_sidebar);
+    let id = ele.id;
+    unsafe {
+        clay::clay!(
+            ele,
+            clay::clay_text!(txt, cfg),
+            state.focus.on_press(id, handle_stream, opaque_state_ptr)
+        );
+    }
+}
+
+// Narrow windows hide the sidebar and move its menus into the header, unless the user opened or closed it
 pub fn compute_sidebar_width(state: &mut State) {
-    let sidebar_width = (state.width as f32) * MAX_SIDEBAR_SPACE_RATIO;
+    let window = state.layout_size().0;
+    let responsive = (window * MAX_SIDEBAR_SPACE_RATIO).min(MAX_SIDEBAR_WIDTH);
+    let shown = match state.sidebar_collapsed {
+        Some(collapsed) => !collapsed,
+        None => responsive >= MIN_SIDEBAR_WIDTH,
+    };
+
+    state.sidebar_width = if shown {
+        clamp_sidebar_width(state.sidebar_preferred.unwrap_or(responsive), window)
+    } else {
+        0.0
+    };
+}
+
+pub fn clamp_sidebar_width(width: f32, window: f32) -> f32 {
+    width.min(window * MAX_SIDEBAR_DRAG_RATIO).max(MIN_SIDEBAR_WIDTH)
+}
+
+// Handle between the sidebar and the chart, dragging it resizes the sidebar
+pub fn create_sidebar_splitter(state: &mut State, scheme: &SchemeUi, opaque_state_ptr: isize) {
+    if !state.is_sidebar_shown() {
+        return;
+    }
+
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id(SPLITTER_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_fixed(SPLITTER_WIDTH),
+                    height: clay::Clay_SizingAxis::sizing_grow(0.0),
+                })
+                .build(),
+        )
+        .with_corner_radius(clay::Clay_CornerRadius::all(SPLITTER_WIDTH / 2.0))
+        .build();
 
-    state.sidebar_width = sidebar_width.min(MAX_SIDEBAR_WIDTH);
-    if state.sidebar_width < MIN_SIDEBAR_WIDTH {
-        state.sidebar_width = 0.0;
+    if state.sidebar_drag.is_some() || unsafe { clay::Clay_PointerOver(ele.id) } {
+        ele.backgroundColor = scheme.sidebar.line;
     }
+
+    unsafe { clay::clay!(ele, clay::Clay_OnHover(Some(handle_splitter_grab), opaque_state_ptr)) }
 }
 
 pub unsafe fn create_sidebar(state: &mut State, scheme: &SchemeUi, opaque_state_ptr: isize) {
-    if state.sidebar_width < math::EPS {
+    if !state.is_sidebar_shown() {
         return;
     }
 
     let within_sidebar = true;
     let mut txt_cfg = clay::Clay_TextElementConfig::default();
-    txt_cfg.fontId = 0;
+    txt_cfg.fontId = FONT_LABEL;
     txt_cfg.fontSize = 26;
-    txt_cfg.textColor = clay::CLAY_WHITE;
+    txt_cfg.textColor = scheme.sidebar.text;
+
+    let mut scale_cfg = txt_cfg;
+    scale_cfg.fontSize = 22;
 
     let text_alignment = clay::ClayChildAlignmentX::Left;
     let (menu_header, title_str) =
@@ -301,7 +741,21 @@ pub unsafe fn create_sidebar(state: &mut State, scheme: &SchemeUi, opaque_state_
             clay::clay!(hline_separator(scheme.sidebar.line, 4.0, 10.0)),
             create_chart_menu(state, scheme, txt_cfg, text_alignment, within_sidebar),
             create_dist_menu(state, scheme, txt_cfg, text_alignment, within_sidebar),
-            create_sim_button(scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar),
+            create_reference_menu(state, scheme, txt_cfg, text_alignment, within_sidebar),
+            create_kde_menu(state, scheme, txt_cfg, text_alignment, within_sidebar),
+            create_bandwidth_control(state, scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar),
+            create_scale_control(state, scheme, opaque_state_ptr, scale_cfg, text_alignment, within_sidebar),
+            create_animation_control(state, scheme, opaque_state_ptr, scale_cfg, text_alignment, within_sidebar),
+            create_process_control(state, scheme, opaque_state_ptr, scale_cfg, text_alignment, within_sidebar),
+            create_correlation_panel(state, scheme, opaque_state_ptr, scale_cfg),
+            create_sim_button(&mut state.focus, scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar),
+            create_stream_button(state, scheme, opaque_state_ptr, txt_cfg, text_alignment, within_sidebar),
+            clay::clay!(hline_separator(scheme.sidebar.line, 4.0, 10.0)),
+            create_series_panel(state, scheme, opaque_state_ptr, scale_cfg),
+            clay::clay!(hline_separator(scheme.sidebar.line, 4.0, 10.0)),
+            create_clt_panel(state, scheme, opaque_state_ptr, scale_cfg),
+            clay::clay!(hline_separator(scheme.sidebar.line, 4.0, 10.0)),
+            create_monte_carlo_control(state, scheme, opaque_state_ptr, scale_cfg, text_alignment, within_sidebar),
             clay::Clay_OnHover(Some(handle_sidebar_click), opaque_state_ptr),
         )
     }
@@ -0,0 +1,217 @@
+use super::clay;
+use super::handlers::handle_markers_toggle;
+use super::scheme::SchemeUi;
+use super::{State, TextArena};
+use crate::font::{FONT_LABEL, FONT_MONO};
+
+pub const STATS_CARD_ELEMENT_ID: &str = "StatsCard";
+const STATS_MARKERS_ELEMENT_ID: &str = "StatsMarkers";
+
+const LABEL_WIDTH: f32 = 130.0;
+const VALUE_WIDTH: f32 = 110.0;
+
+fn format_stat(value: f32) -> String {
+    if value.is_nan() {
+        return "-".to_string();
+    }
+    format!("{value:.4}")
+}
+
+fn stats_text_config(scheme: &SchemeUi, colour: clay::Clay_Color, font: u16) -> clay::Clay_TextElementConfig {
+    clay::Clay_TextElementConfig {
+        fontId: font,
+        fontSize: scheme.stats.font_size,
+        textColor: colour,
+        ..Default::default()
+    }
+}
+
+// Shared by the floating cards drawn over the chart
+pub fn card_border(scheme: &SchemeUi) -> clay::Clay_BorderElementConfig {
+    clay::Clay_BorderElementConfig {
+        color: scheme.stats.border,
+        width: clay::Clay_BorderWidth { left: 2, right: 2, top: 2, bottom: 2, betweenChildren: 0 },
+    }
+}
+
+fn define_card(scheme: &SchemeUi) -> clay::Clay_ElementDeclaration {
+    let pad = scheme.stats.padding as f32;
+
+    clay::ClayElementBuilder::new()
+        .with_id(STATS_CARD_ELEMENT_ID)
+        .with_floating(
+            clay::ClayFloatingBuilder::new()
+                .with_attach_to(clay::ClayFloatingAttachToElement::Parent)
+                .with_attach_points(
+                    clay::ClayFloatingAttachPointType::RightTop,
+                    clay::ClayFloatingAttachPointType::RightTop,
+                )
+                .with_offset(-pad, pad)
+                .with_zindex(5)
+                .build(),
+        )
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_layout_direction(clay::ClayLayoutDirection::TopToBottom)
+                .with_padding(clay::Clay_Padding::padding_all(scheme.stats.padding))
+                .with_child_gap(4)
+                .build(),
+        )
+        .with_background_color(scheme.stats.background)
+        .with_corner_radius(clay::Clay_CornerRadius::all(8.0))
+        .with_border(card_border(scheme))
+        .build()
+}
+
+fn define_row() -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_layout_direction(clay::ClayLayoutDirection::LeftToRight)
+                .build(),
+        )
+        .build()
+}
+
+fn define_cell(width: f32, alignment: clay::ClayChildAlignmentX) -> clay::Clay_ElementDeclaration {
+    clay::ClayElementBuilder::new()
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_fixed(width),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_child_alignment(clay::Clay_ChildAlignment { x: alignment as u8, y: 0 })
+                .build(),
+        )
+        .build()
+}
+
+fn create_row(
+    cells: [clay::Clay_String; 3],
+    cfg: clay::Clay_TextElementConfig,
+    value_cfg: clay::Clay_TextElementConfig,
+) {
+    let [label, sample, theory] = cells;
+    unsafe {
+        clay::clay!(
+            define_row(),
+            clay::clay!(
+                define_cell(LABEL_WIDTH, clay::ClayChildAlignmentX::Left),
+                clay::clay_text!(label, cfg)
+            ),
+            clay::clay!(
+                define_cell(VALUE_WIDTH, clay::ClayChildAlignmentX::Right),
+                clay::clay_text!(sample, value_cfg)
+            ),
+            clay::clay!(
+                define_cell(VALUE_WIDTH, clay::ClayChildAlignmentX::Right),
+                clay::clay_text!(theory, value_cfg)
+            ),
+        );
+    }
+}
+
+fn create_stat_row(
+    text: &mut TextArena,
+    label: &'static str,
+    sample: f32,
+    theory: f32,
+    cfg: clay::Clay_TextElementConfig,
+    value_cfg: clay::Clay_TextElementConfig,
+) {
+    let cells = [
+        clay::Clay_String::from_str(label),
+        text.push(format_stat(sample)),
+        text.push(format_stat(theory)),
+    ];
+    create_row(cells, cfg, value_cfg);
+}
+
+fn create_markers_toggle(state: &mut State, scheme: &SchemeUi, opaque_state_ptr: isize) {
+    let name = if state.show_markers {
+        "Markers: on"
+    } else {
+        "Markers: off"
+    };
+    let cfg = stats_text_config(scheme, scheme.stats.text, FONT_LABEL);
+
+    let mut ele = clay::ClayElementBuilder::new()
+        .with_id(STATS_MARKERS_ELEMENT_ID)
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_sizing(clay::Clay_Sizing {
+                    width: clay::Clay_SizingAxis::sizing_grow(0.0),
+                    height: clay::Clay_SizingAxis::default(),
+                })
+                .with_padding(clay::Clay_Padding { left: 8, right: 8, top: 4, bottom: 4 })
+                .build(),
+        )
+        .with_background_color(scheme.sidebar.button.default)
+        .with_corner_radius(clay::Clay_CornerRadius::all(5.0))
+        .build();
+
+    if unsafe { clay::Clay_PointerOver(ele.id) } {
+        ele.backgroundColor = scheme.sidebar.button.hover;
+    }
+
+    let txt = clay::Clay_String::from_str(name);
+    let id = ele.id;
+    unsafe {
+        clay::clay!(
+            ele,
+            state.focus.on_press(id, handle_markers_toggle, opaque_state_ptr),
+            clay::clay_text!(txt, cfg),
+        );
+    }
+}
+
+pub fn create_stats_card(state: &mut State, scheme: &SchemeUi, opaque_state_ptr: isize) {
+    if !state.show_stats {
+        return;
+    }
+
+    let (stats, theory) = match state.chart_data.as_ref() {
+        None => return,
+        Some(data) => (data.stats, data.theory),
+    };
+
+    let cfg = stats_text_config(scheme, scheme.stats.text, FONT_LABEL);
+    let value_cfg = stats_text_config(scheme, scheme.stats.text, FONT_MONO);
+    let title_cfg = stats_text_config(scheme, scheme.stats.title, FONT_LABEL);
+
+    let rows: [(&'static str, f32, f32); 10] = [
+        ("Mean", stats.mean, theory.mean),
+        ("Variance", stats.variance, theory.variance),
+        ("Std. dev.", stats.std_dev, theory.std_dev),
+        ("Skewness", stats.skewness, theory.skewness),
+        ("Ex. kurtosis", stats.kurtosis, theory.kurtosis),
+        ("Min", stats.min, theory.min),
+        ("Max", stats.max, theory.max),
+        ("Median", stats.median, theory.median),
+        ("Q1", stats.q1, theory.q1),
+        ("Q3", stats.q3, theory.q3),
+    ];
+
+    unsafe {
+        clay::clay!(
+            define_card(scheme),
+            clay::clay_text!(clay::Clay_String::from_str("Summary statistics"), title_cfg),
+            create_row(
+                [
+                    clay::Clay_String::from_str(" "),
+                    clay::Clay_String::from_str("Sample"),
+                    clay::Clay_String::from_str("Theory"),
+                ],
+                title_cfg,
+                title_cfg,
+            ),
+            {
+                for (label, sample, theory) in rows {
+                    create_stat_row(&mut state.text, label, sample, theory, cfg, value_cfg);
+                }
+            },
+            create_markers_toggle(state, scheme, opaque_state_ptr),
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use super::clay;
+use super::raylib;
+use super::scheme::SchemeUi;
+use super::stats::card_border;
+use super::State;
+use crate::chart::{ChartData, ChartKind};
+use crate::font::FONT_MONO;
+use crate::ui::render::{overlay_layers, ChartView};
+
+const TOOLTIP_ELEMENT_ID: &str = "ChartTooltip";
+const TOOLTIP_OFFSET: f32 = 16.0;
+
+fn describe_bar(data: &ChartData, x: f32, lines: &mut Vec<String>) -> bool {
+    let hist = &data.hist;
+    let Some(bin) = hist.bin_at(x) else {
+        return false;
+    };
+
+    let width = hist.bin_width();
+    let lo = hist.min + (bin as f32) * width;
+    let count = hist.data[bin];
+    let total: u32 = hist.data.iter().sum();
+    lines.push(format!("Bin: [{:.3}, {:.3})", lo, lo + width));
+    lines.push(format!("Count: {count}"));
+    lines.push(format!("Rel. freq.: {:.4}", (count as f32) / (total.max(1) as f32)));
+    true
+}
+
+fn describe_density(data: &ChartData, x: f32, lines: &mut Vec<String>) -> bool {
+    let Some(pdf) = data.line.interpolate(x) else {
+        return false;
+    };
+
+    lines.push(format!("x: {x:.3}"));
+    lines.push(format!("pdf: {pdf:.4}"));
+    true
+}
+
+fn describe_point(view: &ChartView, data: &ChartData, pos: raylib::Vector2, lines: &mut Vec<String>) {
+    let x = view.frame.data_x(&view.dim, pos.x);
+    let y = view.frame.data_y(&view.dim, pos.y);
+    match view.kind {
+        ChartKind::Hist => {
+            describe_bar(data, x, lines);
+        }
+        ChartKind::Line => {
+            describe_density(data, x, lines);
+        }
+        ChartKind::HistLine => {
+            // Above the bar the pointer is looking at the density
+            let bar_top = data
+                .hist
+                .bin_at(x)
+                .map(|bin| (data.hist.data[bin] as f32) * data.hist.density_scale())
+                .unwrap_or(0.0);
+            if y > bar_top {
+                describe_density(data, x, lines);
+            } else {
+                describe_bar(data, x, lines);
+            }
+        }
+        ChartKind::QQ => {
+            lines.push(format!("Theoretical: {x:.3}"));
+            lines.push(format!("Sample: {y:.3}"));
+        }
+        ChartKind::PP => {
+            lines.push(format!("Theoretical p: {x:.3}"));
+            lines.push(format!("Empirical p: {y:.3}"));
+        }
+        ChartKind::Box | ChartKind::Violin => {
+            lines.push(format!("x: {x:.3}"));
+        }
+        ChartKind::Paths => {
+            lines.push(format!("t: {x:.3}"));
+            lines.push(format!("Value: {y:.3}"));
+        }
+        ChartKind::Scatter => {
+            lines.push(format!("x: {x:.3}"));
+            lines.push(format!("y: {y:.3}"));
+        }
+        ChartKind::Heatmap => {
+            let heatmap = &data.heatmap;
+            if let Some((col, row)) = heatmap.cell_at(x, y) {
+                let (width, height) = (heatmap.cell_width(), heatmap.cell_height());
+                let (x0, y0) = (heatmap.xmin + (col as f32) * width, heatmap.ymin + (row as f32) * height);
+                lines.push(format!("x: [{:.3}, {:.3})", x0, x0 + width));
+                lines.push(format!("y: [{:.3}, {:.3})", y0, y0 + height));
+                lines.push(format!("Count: {}", heatmap.count(col, row)));
+            }
+        }
+    }
+}
+
+pub fn create_tooltip(state: &mut State, scheme: &SchemeUi) {
+    let Some(view) = state.chart_view else {
+        return;
+    };
+
+    let pos = unsafe { raylib::GetMousePosition() };
+    if !view.contains(pos) {
+        return;
+    }
+
+    let mut lines = Vec::with_capacity(3);
+    if let Some(primary) = state.chart_data.as_ref() {
+        if let Some(layer) = overlay_layers(state, primary, scheme).first() {
+            describe_point(&view, layer.data, pos, &mut lines);
+        }
+    }
+    if lines.is_empty() {
+        return;
+    }
+
+    let pad = scheme.stats.padding;
+    let ele = clay::ClayElementBuilder::new()
+        .with_id(TOOLTIP_ELEMENT_ID)
+        .with_floating(
+            clay::ClayFloatingBuilder::new()
+                .with_attach_to(clay::ClayFloatingAttachToElement::Root)
+                .with_offset(pos.x / scheme.scale + TOOLTIP_OFFSET, pos.y / scheme.scale + TOOLTIP_OFFSET)
+                .with_zindex(10)
+                .build(),
+        )
+        .with_layout(
+            clay::ClayLayoutBuilder::new()
+                .with_layout_direction(clay::ClayLayoutDirection::TopToBottom)
+                .with_padding(clay::Clay_Padding { left: pad, right: pad, top: pad / 2, bottom: pad / 2 })
+                .with_child_gap(2)
+                .build(),
+        )
+        .with_background_color(scheme.stats.background)
+        .with_corner_radius(clay::Clay_CornerRadius::all(6.0))
+        .with_border(card_border(scheme))
+        .build();
+
+    let cfg = clay::Clay_TextElementConfig {
+        fontId: FONT_MONO,
+        fontSize: scheme.stats.font_size,
+        textColor: scheme.stats.text,
+        ..Default::default()
+    };
+
+    unsafe {
+        clay::clay!(ele, {
+            for line in lines {
+                clay::clay_text!(state.text.push(line), cfg);
+            }
+        });
+    }
+}
@@ -1,11 +1,20 @@
+pub mod actions;
 mod handlers;
+pub mod keyboard;
 pub mod layout;
+pub mod palette;
 pub mod render;
 pub mod scheme;
+pub mod session;
+pub mod theme;
 
-use crate::chart::{ChartData, ChartDataHistogram, ChartDataLine, ChartKind, CustomElementKind};
+use crate::chart::{ChartData, ChartDataLine, ChartDataStreamHistogram, ChartKind, CustomElementKind};
 use crate::ffi::{clay, raylib};
+use crate::font::{FontRegistry, FontSource, FONT_COUNT, FONT_LABEL};
 use crate::math::{self, Distribution};
+use crate::montecarlo::{Experiment, MonteCarlo};
+use crate::process::Process;
+use crate::stats::{self, Bandwidth, Kernel};
 pub use layout::create_layout;
 pub use render::render_layout;
 
@@ -21,6 +30,68 @@ pub mod consts {
     pub const DROP_DOWN_HISTOGRAM: &str = "Histogram";
     pub const DROP_DOWN_LINE: &str = "Line";
     pub const DROP_DOWN_HIST_LINE: &str = "Hist+Line";
+    pub const DROP_DOWN_QQ: &str = "Q-Q";
+    pub const DROP_DOWN_PP: &str = "P-P";
+    pub const DROP_DOWN_BOX: &str = "Box";
+    pub const DROP_DOWN_VIOLIN: &str = "Violin";
+    pub const DROP_DOWN_PATHS: &str = "Paths";
+    pub const DROP_DOWN_SCATTER: &str = "Scatter";
+    pub const DROP_DOWN_HEATMAP: &str = "Heatmap";
+
+    pub const DROP_DOWN_UNIFORM: &str = "Uniform";
+    pub const DROP_DOWN_NORMAL: &str = "Normal";
+    pub const DROP_DOWN_GAMMA: &str = "Gamma";
+    pub const DISTRIBUTIONS: [&str; 3] = [DROP_DOWN_UNIFORM, DROP_DOWN_NORMAL, DROP_DOWN_GAMMA];
+
+    // Including the active series, the extra ones take their colours from `SchemeChartAesthetics::series`
+    pub const MAX_SERIES: usize = 4;
+
+    pub const DROP_DOWN_REF_ACTIVE: &str = "Ref: Active";
+    pub const DROP_DOWN_REF_UNIFORM: &str = "Ref: Uniform";
+    pub const DROP_DOWN_REF_NORMAL: &str = "Ref: Normal";
+    pub const DROP_DOWN_REF_GAMMA: &str = "Ref: Gamma";
+
+    pub const DROP_DOWN_KDE_NONE: &str = "No KDE";
+    pub const DROP_DOWN_KDE_GAUSSIAN: &str = "Gaussian";
+    pub const DROP_DOWN_KDE_EPANECHNIKOV: &str = "Epanechnikov";
+    pub const KDE_KERNELS: [&str; 3] = [DROP_DOWN_KDE_NONE, DROP_DOWN_KDE_GAUSSIAN, DROP_DOWN_KDE_EPANECHNIKOV];
+    pub const REFERENCES: [&str; 4] = [
+        DROP_DOWN_REF_ACTIVE,
+        DROP_DOWN_REF_UNIFORM,
+        DROP_DOWN_REF_NORMAL,
+        DROP_DOWN_REF_GAMMA,
+    ];
+
+    pub const SAMPLE_COUNT: usize = 150_000; // Default size of the simulated samples
+    pub const BIN_COUNT: u16 = 50; // Default number of histogram bars
+
+    pub const STREAM_BATCH: usize = 50; // Samples drawn per frame while streaming
+    pub const STREAM_BINS: usize = 50;
+    pub const STREAM_TAIL: f32 = 0.001; // Probability left out on each side of unbounded supports
+    pub const STREAM_CHECKPOINT_GROWTH: f64 = 1.05; // Running means are recorded at log spaced sample counts
+
+    pub const CLT_SAMPLES: usize = 20_000; // Number of means drawn
+    pub const CLT_DEFAULT_N: usize = 1;
+    pub const CLT_MAX_N: usize = 50;
+
+    pub const PATH_COUNTS: [usize; 6] = [1, 5, 10, 20, 50, 100];
+    pub const PATH_SEED: u64 = 0x9e37_79b9_7f4a_7c15; // Mixed into the seed so paths do not replay the sample
+
+    pub const BIVARIATE_POINTS: usize = 5000;
+    pub const BIVARIATE_SEED: u64 = 0xc2b2_ae3d_27d4_eb4f; // Mixed into the seed so pairs do not replay the sample
+    pub const HEATMAP_BINS: usize = 40;
+    pub const MARGINAL_BINS: u16 = 40;
+    pub const DEFAULT_CORRELATION: f32 = 0.6;
+    pub const MAX_CORRELATION: f32 = 0.95; // In absolute value, the slider covers [-MAX, MAX]
+
+    pub const MONTE_CARLO_BATCH: usize = 25; // Trials per frame while an experiment runs
+
+    pub const MIN_UI_SCALE: f32 = 0.5;
+    pub const MAX_UI_SCALE: f32 = 3.0;
+    pub const UI_SCALE_STEP: f32 = 0.1; // Per Ctrl+= / Ctrl+- press
+
+    pub const KDE_GRID_SIZE: usize = 512;
+    pub const PROBABILITY_PLOT_POINTS: usize = 200;
 
     pub const BUTTONS: [(&str, clay::Clay_Color); 2] = [
         ("Lorem", clay::Clay_Color { r: 90.0, g: 200.0, b: 90.0, a: 255.0 }),
@@ -42,22 +113,156 @@ pub enum RandomGenerator {
     Gamma(math::Gamma),
 }
 
+macro_rules! dispatch {
+    ($gen: expr, $inner: ident => $body: expr) => {
+        match $gen {
+            RandomGenerator::Uniform($inner) => $body,
+            RandomGenerator::Normal($inner) => $body,
+            RandomGenerator::Gamma($inner) => $body,
+        }
+    };
+}
+
 impl RandomGenerator {
-    pub fn reseed(&mut self, seed: u64) {
+    pub fn from_name(name: &str, seed: u64) -> Option<Self> {
+        if name == consts::DROP_DOWN_UNIFORM {
+            Some(Self::Uniform(math::Uniform::new(seed)))
+        } else if name == consts::DROP_DOWN_NORMAL {
+            Some(Self::Normal(math::Normal::new(seed)))
+        } else if name == consts::DROP_DOWN_GAMMA {
+            Some(Self::Gamma(math::Gamma::new(seed, 5, 2.0)))
+        } else {
+            None
+        }
+    }
+
+    // The generators fall back to their defaults on invalid parameters, better to say so than to draw something
+    // else. Shared by the command line and the session file
+    pub fn check_params(name: &str, [first, second]: [f32; 2]) -> Result<(), String> {
+        if !first.is_finite() || !second.is_finite() {
+            Err(format!("the parameters must be finite numbers, found {first},{second}"))
+        } else if name == consts::DROP_DOWN_UNIFORM && first >= second {
+            Err(format!(
+                "the bounds of the uniform distribution must be increasing, found {first},{second}"
+            ))
+        } else if name == consts::DROP_DOWN_NORMAL && second <= 0.0 {
+            Err(format!(
+                "the standard deviation of the normal distribution must be positive, found {second}"
+            ))
+        } else if name == consts::DROP_DOWN_GAMMA && (first.fract() != 0.0 || !(1.0..=255.0).contains(&first)) {
+            Err(format!(
+                "the shape of the gamma distribution must be a whole number from 1 to 255, found {first}"
+            ))
+        } else if name == consts::DROP_DOWN_GAMMA && second <= 0.0 {
+            Err(format!("the rate of the gamma distribution must be positive, found {second}"))
+        } else {
+            Ok(())
+        }
+    }
+
+    // `from_name` with the two parameters shown by `describe`, in the same order
+    pub fn with_params(name: &str, seed: u64, [first, second]: [f32; 2]) -> Option<Self> {
+        if name == consts::DROP_DOWN_UNIFORM {
+            Some(Self::Uniform(math::Uniform::with_bounds(seed, first, second)))
+        } else if name == consts::DROP_DOWN_NORMAL {
+            Some(Self::Normal(math::Normal::with_params(seed, first, second)))
+        } else if name == consts::DROP_DOWN_GAMMA {
+            Some(Self::Gamma(math::Gamma::new(seed, first as u8, second)))
+        } else {
+            None
+        }
+    }
+
+    pub fn params(&self) -> [f32; 2] {
         match self {
-            Self::Uniform(u) => u.reseed(seed),
-            Self::Normal(n) => n.reseed(seed),
-            Self::Gamma(n) => n.reseed(seed),
+            Self::Uniform(u) => [u.a, u.b],
+            Self::Normal(n) => [n.mu, n.sigma],
+            Self::Gamma(g) => [f32::from(g.alpha), g.beta],
         }
     }
 
     pub fn seed(&self) -> u64 {
+        self.get_seed()
+    }
+
+    pub fn name(&self) -> &'static str {
         match self {
-            Self::Uniform(u) => u.get_seed(),
-            Self::Normal(n) => n.get_seed(),
-            Self::Gamma(n) => n.get_seed(),
+            Self::Uniform(_) => consts::DROP_DOWN_UNIFORM,
+            Self::Normal(_) => consts::DROP_DOWN_NORMAL,
+            Self::Gamma(_) => consts::DROP_DOWN_GAMMA,
         }
     }
+
+    // Name and parameters, as shown in the chart title and legend
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Uniform(u) => format!("{}(a = {}, b = {})", self.name(), u.a, u.b),
+            Self::Normal(n) => format!("{}(μ = {}, σ = {})", self.name(), n.mu, n.sigma),
+            Self::Gamma(g) => format!("{}(α = {}, β = {})", self.name(), g.alpha, g.beta),
+        }
+    }
+
+    pub fn next_name(&self) -> &'static str {
+        let name = self.name();
+        let idx = consts::DISTRIBUTIONS.iter().position(|n| *n == name).unwrap_or(0);
+        consts::DISTRIBUTIONS[(idx + 1) % consts::DISTRIBUTIONS.len()]
+    }
+}
+
+impl Distribution for RandomGenerator {
+    type Value = f32;
+
+    fn random(&mut self, size: u32, output: &mut Vec<f32>) {
+        dispatch!(self, g => g.random(size, output))
+    }
+
+    fn sample(&mut self) -> f32 {
+        dispatch!(self, g => g.sample())
+    }
+
+    fn random_batch(&mut self, output: &mut [f32]) {
+        dispatch!(self, g => g.random_batch(output))
+    }
+
+    fn pdf(&self, x: &mut Vec<f32>, y: &mut Vec<f32>) {
+        dispatch!(self, g => g.pdf(x, y))
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        dispatch!(self, g => g.reseed(seed))
+    }
+
+    fn get_seed(&self) -> u64 {
+        dispatch!(self, g => g.get_seed())
+    }
+
+    fn mean(&self) -> f32 {
+        dispatch!(self, g => g.mean())
+    }
+
+    fn variance(&self) -> f32 {
+        dispatch!(self, g => g.variance())
+    }
+
+    fn skewness(&self) -> f32 {
+        dispatch!(self, g => g.skewness())
+    }
+
+    fn kurtosis(&self) -> f32 {
+        dispatch!(self, g => g.kurtosis())
+    }
+
+    fn support(&self) -> (f32, f32) {
+        dispatch!(self, g => g.support())
+    }
+
+    fn cdf(&self, x: f32) -> f32 {
+        dispatch!(self, g => g.cdf(x))
+    }
+
+    fn quantile(&self, p: f32) -> f32 {
+        dispatch!(self, g => g.quantile(p))
+    }
 }
 
 impl Default for RandomGenerator {
@@ -70,7 +275,7 @@ impl Default for RandomGenerator {
 pub struct MenuState {
     pub title: Option<&'static str>,
     pub menuid: Option<clay::Clay_ElementId>,
-    pub dropdown: [DropDownState; 3],
+    pub dropdown: Vec<DropDownState>,
     pub pressed: bool,
     pub parent: *mut State,
 }
@@ -82,14 +287,27 @@ impl MenuState {
             s.menu = ptr;
         }
     }
-    fn dist() -> Self {
-        let mut menu = Self::default();
-        menu.dropdown[0] = DropDownState::new("Uniform");
-        menu.dropdown[1] = DropDownState::new("Normal");
-        menu.dropdown[2] = DropDownState::new("Gamma");
+    fn with_items(names: &[&'static str]) -> Self {
+        let mut menu = Self {
+            menuid: None,
+            pressed: false,
+            title: None,
+            dropdown: names.iter().map(|name| DropDownState::new(name)).collect(),
+            parent: std::ptr::null_mut(),
+        };
+
         menu.init();
         menu
     }
+    fn dist() -> Self {
+        Self::with_items(&consts::DISTRIBUTIONS)
+    }
+    fn kde() -> Self {
+        Self::with_items(&consts::KDE_KERNELS)
+    }
+    fn reference() -> Self {
+        Self::with_items(&consts::REFERENCES)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -106,20 +324,161 @@ impl DropDownState {
 
 impl Default for MenuState {
     fn default() -> Self {
-        let mut menu = Self {
-            menuid: None,
-            pressed: false,
-            title: None,
-            dropdown: [
-                DropDownState::new(consts::DROP_DOWN_HISTOGRAM),
-                DropDownState::new(consts::DROP_DOWN_LINE),
-                DropDownState::new(consts::DROP_DOWN_HIST_LINE),
-            ],
-            parent: std::ptr::null_mut(),
+        Self::with_items(&[
+            consts::DROP_DOWN_HISTOGRAM,
+            consts::DROP_DOWN_LINE,
+            consts::DROP_DOWN_HIST_LINE,
+            consts::DROP_DOWN_QQ,
+            consts::DROP_DOWN_PP,
+            consts::DROP_DOWN_BOX,
+            consts::DROP_DOWN_VIOLIN,
+            consts::DROP_DOWN_PATHS,
+            consts::DROP_DOWN_SCATTER,
+            consts::DROP_DOWN_HEATMAP,
+        ])
+    }
+}
+
+// Owns the dynamic strings handed to clay during a frame, they must stay alive until the render
+// commands are drawn. Moving a `String` does not move its heap buffer, so the pointers remain valid
+#[derive(Debug, Clone, Default)]
+pub struct TextArena {
+    text: Vec<String>,
+}
+
+impl TextArena {
+    pub fn clear(&mut self) {
+        self.text.clear();
+    }
+
+    pub fn push(&mut self, s: String) -> clay::Clay_String {
+        let clay_str = clay::Clay_String::from_string(&s);
+        self.text.push(s);
+        clay_str
+    }
+}
+
+// A distribution shown next to the active one (`State::generator` and `State::chart_data`)
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub generator: RandomGenerator,
+    pub data: ChartData,
+    pub colour: usize, // Index into `SchemeChartAesthetics::series`
+    pub visible: bool,
+    pub index: usize,
+    pub parent: *mut State,
+}
+
+fn fill_kde(data: &mut ChartData, kde: Option<Kernel>, bandwidth: Bandwidth) {
+    data.kde.clear();
+    data.kde_bandwidth = bandwidth.value(&data.stats, data.sample.len());
+
+    // The violin always needs a density, it falls back to the default kernel when the overlay is off
+    let kernel = kde.unwrap_or_default();
+    let (x, y) = (&mut data.violin.x, &mut data.violin.y);
+    stats::kde(&data.sample, kernel, data.kde_bandwidth, consts::KDE_GRID_SIZE, x, y);
+    if kde.is_some() {
+        data.kde.clone_from(&data.violin);
+    }
+}
+
+fn fill_paths(data: &mut ChartData, generator: &RandomGenerator, process: Process, count: usize) {
+    let seed = generator.seed() ^ consts::PATH_SEED;
+    let mut steps = generator.clone();
+    steps.reseed(seed);
+    process.simulate(&mut steps, seed, count, &mut data.paths, &mut data.finals);
+}
+
+// The pairs do not depend on the distribution of the series, only its seed so each series gets its own
+fn fill_bivariate(data: &mut ChartData, generator: &RandomGenerator, rho: f32) {
+    let mut pairs = math::BivariateNormal::new(generator.seed() ^ consts::BIVARIATE_SEED, rho);
+    pairs.random(consts::BIVARIATE_POINTS, &mut data.scatter.x, &mut data.scatter.y);
+    data.heatmap
+        .fill(&data.scatter.x, &data.scatter.y, consts::HEATMAP_BINS);
+
+    let (x, y) = (&data.scatter.x, &data.scatter.y);
+    math::cut(x, consts::MARGINAL_BINS, &mut data.marginal_x.data);
+    data.marginal_x.min = x.iter().copied().fold(f32::MAX, f32::min);
+    data.marginal_x.max = x.iter().copied().fold(f32::MIN, f32::max);
+    math::cut(y, consts::MARGINAL_BINS, &mut data.marginal_y.data);
+    data.marginal_y.min = y.iter().copied().fold(f32::MAX, f32::min);
+    data.marginal_y.max = y.iter().copied().fold(f32::MIN, f32::max);
+}
+
+fn fill_probability_plots(data: &mut ChartData, reference: &RandomGenerator) {
+    let points = consts::PROBABILITY_PLOT_POINTS;
+    stats::qq(&data.sorted, reference, points, &mut data.qq.x, &mut data.qq.y);
+    stats::pp(&data.sorted, reference, points, &mut data.pp.x, &mut data.pp.y);
+}
+
+// Samples arriving a batch per frame, to watch the histogram and the mean settle down
+#[derive(Debug, Clone)]
+pub struct Stream {
+    pub hist: ChartDataStreamHistogram,
+    pub moments: stats::RunningStats, // Over every sample, including the ones outside of the bins
+    pub target: f32,                  // True mean of the distribution, which the running mean converges to
+    pub running_mean: ChartDataLine,  // Against the number of samples
+    pub running: bool,
+    batch: Vec<f32>,
+    checkpoint: f64,
+}
+
+impl Stream {
+    fn new(generator: &RandomGenerator) -> Self {
+        let (lo, hi) = generator.support();
+        let lo = if lo.is_finite() {
+            lo
+        } else {
+            generator.quantile(consts::STREAM_TAIL)
+        };
+        let hi = if hi.is_finite() {
+            hi
+        } else {
+            generator.quantile(1.0 - consts::STREAM_TAIL)
         };
 
-        menu.init();
-        menu
+        let mut hist = ChartDataStreamHistogram::default();
+        hist.reset(lo, hi, consts::STREAM_BINS);
+        Self {
+            hist,
+            moments: stats::RunningStats::default(),
+            target: generator.mean(),
+            running_mean: ChartDataLine::default(),
+            running: true,
+            batch: vec![0.0; consts::STREAM_BATCH],
+            checkpoint: consts::STREAM_BATCH as f64,
+        }
+    }
+
+    fn step(&mut self, generator: &mut RandomGenerator) {
+        generator.random_batch(&mut self.batch);
+        self.hist.extend(&self.batch);
+        for x in self.batch.iter() {
+            self.moments.push(*x);
+        }
+
+        let count = self.moments.count as f64;
+        if count >= self.checkpoint {
+            self.running_mean.push(count as f32, self.moments.mean as f32);
+            self.checkpoint = count * consts::STREAM_CHECKPOINT_GROWTH;
+        }
+    }
+
+    // The quartiles come from the bins, so they are only as precise as the bin width
+    pub fn summary(&self) -> stats::SummaryStats {
+        let mut output = self.moments.summary();
+        output.q1 = self.hist.quantile(0.25);
+        output.median = self.hist.quantile(0.5);
+        output.q3 = self.hist.quantile(0.75);
+        output
+    }
+
+    pub fn label(&self) -> &'static str {
+        match (self.running, self.moments.count) {
+            (true, _) => "Pause",
+            (false, 0) => "Stream",
+            (false, _) => "Resume",
+        }
     }
 }
 
@@ -128,34 +487,106 @@ pub struct State {
     pub height: i32,
     pub width: i32,
     pub sidebar_width: f32,
+    pub sidebar_collapsed: Option<bool>, // User's choice, None follows the window width
+    pub sidebar_preferred: Option<f32>,  // Width the splitter was dragged to, None follows the window width
+    pub sidebar_drag: Option<f32>,       // Pointer x minus the sidebar width when the splitter was grabbed
     pub measure: Option<MeasureFun>,
-    pub font: Option<raylib::Font>,
+    pub fonts: FontRegistry,
     pub chart: MenuState,
     pub dist: MenuState,
+    pub kde_menu: MenuState,
+    pub kde: Option<Kernel>,
+    pub kde_bandwidth: Bandwidth,
+    pub reference_menu: MenuState,
+    pub reference: Option<RandomGenerator>,
     pub generator: RandomGenerator,
     pub chart_data: Option<crate::chart::ChartData>,
+    pub stream: Option<Stream>,
+    pub clt: Option<usize>, // Size of the means shown by the central limit theorem mode
+    pub sample_count: usize,
+    pub bin_count: u16,
+    pub input: Option<Vec<f32>>, // Sample read from a data file, shown instead of the simulated one
+    pub font_file: Option<std::path::PathBuf>, // Replaces the label font, over the theme
+    pub process: Process,
+    pub path_count: usize,
+    pub correlation: f32, // Of the bivariate Normal shown by the scatter and heatmap charts
+    pub finals_element: Option<crate::chart::CustomElementKind>,
+    pub stream_element: Option<crate::chart::CustomElementKind>,
+    pub monte_carlo: Option<MonteCarlo>, // Takes over the chart area while an experiment is picked
+    pub monte_carlo_elements: Vec<crate::chart::CustomElementKind>,
+    pub primary_visible: bool,
+    pub series: Vec<Series>,
+    pub small_multiples: bool,
     pub custom_element: Option<crate::chart::CustomElementKind>,
+    pub chart_view: Option<render::ChartView>,
+    pub view_window: Option<render::ChartFrame>, // Zoomed or panned window, None follows the data
+    pub drag: Option<render::ChartDrag>,
+    pub xscale: math::AxisScale,
+    pub yscale: math::AxisScale,
+    pub cell_elements: Vec<crate::chart::CustomElementKind>,
     pub should_close: bool,
     pub animation: Animation,
     pub seeder: rand::rngs::SmallRng,
     pub seed: u64,
-    pub text_array: Vec<i8>,
     pub chart_x: Vec<f32>,
     pub chart_y: Vec<f32>,
-    _pin: std::marker::PhantomPinned, // State is self referential as field menu points to state
+    pub text: TextArena,
+    pub show_stats: bool,
+    pub show_markers: bool,
+    pub show_help: bool,
+    pub focus: keyboard::Focus,
+    pub palette: Option<palette::Palette>,        // Open command palette
+    pub recent: Vec<actions::Action>,             // Commands run from the palette, latest first, kept on reset
+    pub session_path: Option<std::path::PathBuf>, // Last session saved or opened by the user
+    pub export_path: Option<std::path::PathBuf>,  // Last file the sample was exported to
+    pub bindings: keyboard::Bindings,             // Kept on reset, like the theme
+    pub theme: scheme::Theme,                     // Kept on reset, it is a preference rather than part of the analysis
+    pub theme_file: Option<theme::FileTheme>,     // Last valid theme read from the theme file
+    pub dpi_scale: f32,                           // Of the monitor the window is on
+    pub ui_scale: f32,                            // User override on top of the DPI scale, kept on reset
+    _pin: std::marker::PhantomPinned,             // State is self referential as field menu points to state
 }
 
 impl State {
     pub fn unclick(&mut self) {
         self.chart.pressed = false;
         self.dist.pressed = false;
+        self.kde_menu.pressed = false;
+        self.reference_menu.pressed = false;
+    }
+    pub fn is_menu_open(&self) -> bool {
+        self.chart.pressed || self.dist.pressed || self.kde_menu.pressed || self.reference_menu.pressed
+    }
+    pub fn axis_scales(&self) -> (math::AxisScale, math::AxisScale) {
+        (self.xscale, self.yscale)
+    }
+    pub fn reset_view(&mut self) {
+        self.view_window = None;
+        self.drag = None;
     }
     pub fn reset(&mut self) {
         self.chart = MenuState::default();
+        self.kde_menu = MenuState::kde();
+        self.kde = None;
+        self.kde_bandwidth = Bandwidth::default();
+        self.reference_menu = MenuState::reference();
+        self.reference = None;
         self.chart_data = None;
+        self.stream = None;
+        self.clt = None;
+        self.process = Process::default();
+        self.path_count = consts::PATH_COUNTS[3];
+        self.correlation = consts::DEFAULT_CORRELATION;
+        self.monte_carlo = None;
+        self.chart_view = None;
+        self.reset_view();
+        self.xscale = math::AxisScale::default();
+        self.yscale = math::AxisScale::default();
+        self.primary_visible = true;
+        self.series.clear();
+        self.small_multiples = false;
         self.unclick();
         self.animation.reset();
-        self.text_array.clear();
         self.chart_x.clear();
         self.chart_y.clear();
     }
@@ -166,69 +597,486 @@ impl State {
         self.chart.init();
         self.dist.parent = ptr;
         self.dist.init();
+        self.kde_menu.parent = ptr;
+        self.kde_menu.init();
+        self.reference_menu.parent = ptr;
+        self.reference_menu.init();
+        for (index, series) in self.series.iter_mut().enumerate() {
+            series.parent = ptr;
+            series.index = index;
+        }
         self.measure = Some(raylib::raylib_measure_text);
     }
 
+    pub fn scheme(&self) -> &scheme::SchemeUi {
+        self.theme.scheme(self.theme_file.as_ref().map(|file| &file.scheme))
+    }
+
+    // Physical pixels per layout pixel
+    pub fn scale(&self) -> f32 {
+        (self.dpi_scale * self.ui_scale).clamp(consts::MIN_UI_SCALE, consts::MAX_UI_SCALE)
+    }
+
+    // Steps the user scale up or down, zero goes back to the monitor's own scale
+    pub fn zoom_ui(&mut self, steps: i32) {
+        self.ui_scale = if steps == 0 {
+            1.0
+        } else {
+            let scale = self.ui_scale + (steps as f32) * consts::UI_SCALE_STEP;
+            // Rounded so repeated steps do not drift away from the tenths
+            ((scale * 10.0).round() / 10.0).clamp(consts::MIN_UI_SCALE, consts::MAX_UI_SCALE)
+        };
+    }
+
+    // Size of the window in layout pixels, what clay lays the UI out in
+    pub fn layout_size(&self) -> (f32, f32) {
+        let scale = self.scale();
+        ((self.width as f32) / scale, (self.height as f32) / scale)
+    }
+
+    // Glyphs are rasterised at the size they are drawn at on screen, so they stay crisp when scaled
+    pub fn font_config(&self) -> scheme::SchemeFont {
+        let mut config = self.scheme().font_config;
+        config.base_size = ((config.base_size as f32) * self.scale()).round() as i32;
+        config
+    }
+
+    // The embedded fonts, unless the theme file in use names others
+    pub fn font_sources(&self) -> [FontSource; FONT_COUNT] {
+        let files = match (self.theme, self.theme_file.as_ref()) {
+            (scheme::Theme::File, Some(file)) => Some(&file.fonts),
+            _ => None,
+        };
+        std::array::from_fn(|id| {
+            let file = match self.font_file.as_ref() {
+                Some(path) if id == FONT_LABEL as usize => Some(path.clone()),
+                _ => files.and_then(|fonts| fonts[id].clone()),
+            };
+            match file {
+                Some(path) => FontSource::File(path),
+                None => FontSource::Embedded(id as u16),
+            }
+        })
+    }
+
+    pub fn is_sidebar_shown(&self) -> bool {
+        self.sidebar_width >= math::EPS
+    }
+
+    // Overrides the breakpoints from then on, the dragged width is kept for when it opens again
+    pub fn toggle_sidebar(&mut self) {
+        self.sidebar_collapsed = Some(self.is_sidebar_shown());
+        self.sidebar_drag = None;
+    }
+
+    pub fn cycle_theme(&mut self) {
+        self.theme = self.theme.next();
+        if self.theme == scheme::Theme::File && self.theme_file.is_none() {
+            self.theme = self.theme.next();
+        }
+    }
+
+    // A valid file replaces the scheme in use, an invalid one is reported and the previous scheme is kept
+    pub fn apply_theme_file(&mut self, theme: Result<theme::FileTheme, crate::error::AppError>) {
+        match theme {
+            Ok(theme) => {
+                self.theme_file = Some(theme);
+                self.theme = scheme::Theme::File;
+            }
+            Err(err) => eprint!("{err}"),
+        }
+    }
+
     pub fn reseed(&mut self, seed: u64) {
         self.generator.reseed(seed);
     }
 
+    // Always drawn from the start of the seed, so that the seed alone brings back the sample on screen
     pub fn create_chart_data(&mut self) {
+        self.reseed(self.generator.seed());
+        self.stream = None;
+        let is_new = self.chart_data.is_none();
+        let chart_data = self.chart_data.get_or_insert_with(ChartData::default);
+        match (self.input.as_deref(), self.clt) {
+            (Some(input), _) => layout::create_input_data(&self.generator, input, self.bin_count, chart_data),
+            (None, None) => {
+                layout::create_chart_data(&mut self.generator, self.sample_count, self.bin_count, chart_data)
+            }
+            (None, Some(n)) => layout::create_clt_data(&mut self.generator, n, self.bin_count, chart_data),
+        }
+
+        if is_new {
+            if let Some(CustomElementKind::Chart(ref mut ptr)) = self.custom_element {
+                *ptr = chart_data as *const ChartData;
+            }
+        }
+
+        self.update_kde();
+        self.update_probability_plots();
+        self.update_paths();
+        self.update_bivariate();
+    }
+
+    pub fn cycle_process(&mut self) {
+        self.process = self.process.next();
+        self.reset_view();
+        self.animation.reset();
+        self.update_paths();
+    }
+
+    pub fn cycle_path_count(&mut self) {
+        let idx = consts::PATH_COUNTS
+            .iter()
+            .position(|n| *n == self.path_count)
+            .unwrap_or(0);
+        self.path_count = consts::PATH_COUNTS[(idx + 1) % consts::PATH_COUNTS.len()];
+        self.animation.reset();
+        self.update_paths();
+    }
+
+    pub fn update_paths(&mut self) {
+        if let Some(ref mut data) = self.chart_data {
+            fill_paths(data, &self.generator, self.process, self.path_count);
+        }
+        for series in self.series.iter_mut() {
+            fill_paths(&mut series.data, &series.generator, self.process, self.path_count);
+        }
+    }
+
+    pub fn update_bivariate(&mut self) {
+        if let Some(ref mut data) = self.chart_data {
+            fill_bivariate(data, &self.generator, self.correlation);
+        }
+        for series in self.series.iter_mut() {
+            fill_bivariate(&mut series.data, &series.generator, self.correlation);
+        }
+    }
+
+    // Redraws the same pairs with the new correlation, so dragging the slider reshapes the cloud smoothly
+    pub fn set_correlation(&mut self, rho: f32) {
+        let rho = rho.clamp(-consts::MAX_CORRELATION, consts::MAX_CORRELATION);
+        if (rho - self.correlation).abs() < math::EPS {
+            return;
+        }
+        self.correlation = rho;
+        self.update_bivariate();
+    }
+
+    pub fn toggle_clt(&mut self) {
+        self.clt = match self.clt {
+            None => Some(consts::CLT_DEFAULT_N),
+            Some(_) => None,
+        };
+
+        self.animation.reset();
+        self.create_chart_data();
+        // The Normal it converges to is drawn as the line
+        self.chart.title = Some(consts::DROP_DOWN_HIST_LINE);
+        self.set_chart_kind(ChartKind::HistLine);
+    }
+
+    pub fn set_clt_size(&mut self, n: usize) {
+        let n = n.clamp(1, consts::CLT_MAX_N);
+        if self.clt.is_none() || self.clt == Some(n) {
+            return;
+        }
+
+        self.clt = Some(n);
+        self.create_chart_data();
+        self.reset_view();
+        self.animation.replay(render::Reveal::Morph);
+    }
+
+    // Starts streaming on the first call, then pauses and resumes
+    pub fn toggle_stream(&mut self) {
+        if let Some(ref mut stream) = self.stream {
+            stream.running = !stream.running;
+            return;
+        }
+
+        if self.chart_data.is_none() || self.clt.is_some() {
+            self.clt = None;
+            self.create_chart_data();
+        }
+        self.chart.title = Some(consts::DROP_DOWN_HIST_LINE);
+        self.set_chart_kind(ChartKind::HistLine);
+        self.stream = Some(Stream::new(&self.generator));
+    }
+
+    // Draws the next batch and shows the counts so far in place of the primary histogram
+    pub fn step_stream(&mut self) {
+        let Some(ref mut stream) = self.stream else {
+            return;
+        };
+        if !stream.running {
+            return;
+        }
+
+        stream.step(&mut self.generator);
+        if let Some(ref mut data) = self.chart_data {
+            stream.hist.fill(&mut data.hist);
+            data.settle();
+            data.stats = stream.summary();
+        }
+    }
+
+    // Off, then each experiment in turn, then off again
+    pub fn cycle_experiment(&mut self) {
+        let next = match self.monte_carlo {
+            None => Some(Experiment::default()),
+            Some(ref mc) => mc.experiment.next(),
+        };
+        let seed = self.seeder.random::<u64>();
+        self.monte_carlo = next.map(|experiment| MonteCarlo::new(experiment, seed));
+    }
+
+    // Starts the first experiment when none is picked, then pauses and resumes
+    pub fn toggle_experiment(&mut self) {
+        match self.monte_carlo {
+            None => self.cycle_experiment(),
+            Some(ref mut mc) => mc.running = !mc.running,
+        }
+    }
+
+    pub fn step_monte_carlo(&mut self) {
+        if let Some(ref mut mc) = self.monte_carlo {
+            if mc.running {
+                mc.step(consts::MONTE_CARLO_BATCH);
+            }
+        }
+    }
+
+    pub fn update_kde(&mut self) {
+        if let Some(ref mut data) = self.chart_data {
+            fill_kde(data, self.kde, self.kde_bandwidth);
+        }
+        for series in self.series.iter_mut() {
+            fill_kde(&mut series.data, self.kde, self.kde_bandwidth);
+        }
+    }
+
+    pub fn update_probability_plots(&mut self) {
+        if let Some(ref mut data) = self.chart_data {
+            fill_probability_plots(data, self.reference.as_ref().unwrap_or(&self.generator));
+        }
+        for series in self.series.iter_mut() {
+            fill_probability_plots(&mut series.data, self.reference.as_ref().unwrap_or(&series.generator));
+        }
+    }
+
+    pub fn chart_kind(&self) -> ChartKind {
+        match self.chart.title {
+            None => ChartKind::default(),
+            Some(name) => ChartKind::from_str(name),
+        }
+    }
+
+    pub fn set_chart_kind(&mut self, kind: ChartKind) {
+        // The data space of a Q-Q plot has nothing to do with the one of a histogram, and switching the
+        // chart replays the same data so there is nothing to morph from
+        if self.chart_data.as_ref().is_some_and(|data| data.kind != kind) {
+            self.reset_view();
+            if let Some(ref mut chart_data) = self.chart_data {
+                chart_data.settle();
+            }
+            for series in self.series.iter_mut() {
+                series.data.settle();
+            }
+        }
         if let Some(ref mut chart_data) = self.chart_data {
-            match &mut self.generator {
-                RandomGenerator::Normal(n) => {
-                    layout::create_chart_data(n, &mut chart_data.hist, &mut chart_data.line);
-                }
-                RandomGenerator::Uniform(u) => {
-                    layout::create_chart_data(u, &mut chart_data.hist, &mut chart_data.line);
-                }
-                RandomGenerator::Gamma(g) => {
-                    layout::create_chart_data(g, &mut chart_data.hist, &mut chart_data.line);
-                }
+            chart_data.kind = kind;
+        }
+        for series in self.series.iter_mut() {
+            series.data.kind = kind;
+        }
+    }
+
+    // What picking an entry of the chart menu does
+    pub fn select_chart_kind(&mut self, kind: ChartKind) {
+        self.chart.title = Some(kind.name());
+        self.chart.pressed = false;
+        self.animation.reset();
+        self.set_chart_kind(kind);
+    }
+
+    // What picking an entry of the distribution menu does, `name` is one of `consts::DISTRIBUTIONS`
+    pub fn select_distribution(&mut self, name: &'static str) {
+        self.dist.pressed = false;
+        if name == self.dist.title.unwrap_or("") {
+            return;
+        }
+
+        self.dist.title = Some(name);
+        self.animation.reset();
+        self.reset_view();
+
+        let seed = self.seeder.random::<u64>();
+        match RandomGenerator::from_name(name, seed) {
+            Some(gen_) => {
+                self.generator = gen_;
+                self.create_chart_data();
             }
+            None => eprintln!("ERROR: Does not know how to handle distribution: '{name}'"),
+        }
+    }
+
+    // What picking an entry of the KDE menu does, `name` is one of `consts::KDE_KERNELS`
+    pub fn select_kde(&mut self, name: &'static str) {
+        self.kde_menu.title = Some(name);
+        self.kde_menu.pressed = false;
+        self.kde = if name == consts::DROP_DOWN_KDE_GAUSSIAN {
+            Some(Kernel::Gaussian)
+        } else if name == consts::DROP_DOWN_KDE_EPANECHNIKOV {
+            Some(Kernel::Epanechnikov)
         } else {
-            let mut hist = ChartDataHistogram::default();
-            let mut line = ChartDataLine::default();
-
-            match &mut self.generator {
-                RandomGenerator::Normal(n) => {
-                    layout::create_chart_data(n, &mut hist, &mut line);
-                }
-                RandomGenerator::Uniform(u) => {
-                    layout::create_chart_data(u, &mut hist, &mut line);
-                }
-                RandomGenerator::Gamma(g) => {
-                    layout::create_chart_data(g, &mut hist, &mut line);
-                }
-            }
+            None
+        };
+        self.update_kde();
+    }
 
-            let kind = ChartKind::default();
-            let chart_data = ChartData { hist, line, kind };
-            self.chart_data = Some(chart_data);
-            if let Some(ref mut ele) = self.custom_element {
-                match ele {
-                    CustomElementKind::Chart(ptr) => {
-                        *ptr = self.chart_data.as_ref().unwrap() as *const ChartData;
-                    }
-                }
+    // What picking an entry of the reference menu does, `name` is one of `consts::REFERENCES`
+    pub fn select_reference(&mut self, name: &'static str) {
+        self.reference_menu.title = Some(name);
+        self.reference_menu.pressed = false;
+        let dist = if name == consts::DROP_DOWN_REF_UNIFORM {
+            consts::DROP_DOWN_UNIFORM
+        } else if name == consts::DROP_DOWN_REF_NORMAL {
+            consts::DROP_DOWN_NORMAL
+        } else if name == consts::DROP_DOWN_REF_GAMMA {
+            consts::DROP_DOWN_GAMMA
+        } else {
+            ""
+        };
+
+        // The reference is never sampled from, so its seed is irrelevant
+        self.reference = RandomGenerator::from_name(dist, 1);
+        self.animation.reset();
+        self.update_probability_plots();
+    }
+
+    pub fn cycle_xscale(&mut self) {
+        self.xscale = self.xscale.next();
+        self.reset_view();
+    }
+
+    pub fn cycle_yscale(&mut self) {
+        self.yscale = self.yscale.next();
+        self.reset_view();
+    }
+
+    pub fn cycle_easing(&mut self) {
+        self.animation.easing = self.animation.easing.next();
+        self.animation.reset();
+    }
+
+    pub fn cycle_reveal(&mut self) {
+        self.animation.reveal = self.animation.reveal.next();
+        self.animation.reset();
+    }
+
+    pub fn create_series_data(&mut self, index: usize) {
+        let kind = self.chart_kind();
+        if let Some(series) = self.series.get_mut(index) {
+            layout::create_chart_data(&mut series.generator, self.sample_count, self.bin_count, &mut series.data);
+            series.data.kind = kind;
+            fill_kde(&mut series.data, self.kde, self.kde_bandwidth);
+            fill_probability_plots(&mut series.data, self.reference.as_ref().unwrap_or(&series.generator));
+            fill_paths(&mut series.data, &series.generator, self.process, self.path_count);
+            fill_bivariate(&mut series.data, &series.generator, self.correlation);
+        }
+    }
+
+    pub fn add_series(&mut self) {
+        if self.series.len() + 1 >= consts::MAX_SERIES {
+            return;
+        }
+
+        // Start from the distribution after the last one shown, so the new series is worth comparing
+        let last = self.series.last().map(|s| &s.generator).unwrap_or(&self.generator);
+        let name = last.next_name();
+        let colour = (0..consts::MAX_SERIES - 1)
+            .find(|c| self.series.iter().all(|s| s.colour != *c))
+            .unwrap_or(0);
+
+        let seed = self.seeder.random::<u64>();
+        let Some(generator) = RandomGenerator::from_name(name, seed) else {
+            eprintln!("ERROR: Does not know how to handle distribution: '{name}'");
+            return;
+        };
+
+        let index = self.series.len();
+        let parent = self as *mut Self;
+        self.series.push(Series {
+            generator,
+            data: ChartData::default(),
+            colour,
+            visible: true,
+            index,
+            parent,
+        });
+        self.create_series_data(index);
+        self.animation.reset();
+    }
+
+    pub fn toggle_small_multiples(&mut self) {
+        self.small_multiples = !self.small_multiples;
+        self.animation.reset();
+    }
+
+    pub fn remove_series(&mut self, index: usize) {
+        if index < self.series.len() {
+            self.series.remove(index);
+            self.animation.reset();
+        }
+    }
+
+    pub fn cycle_series(&mut self, index: usize) {
+        let seed = self.seeder.random::<u64>();
+        let Some(series) = self.series.get_mut(index) else {
+            return;
+        };
+
+        let name = series.generator.next_name();
+        match RandomGenerator::from_name(name, seed) {
+            Some(gen_) => series.generator = gen_,
+            None => {
+                eprintln!("ERROR: Does not know how to handle distribution: '{name}'");
+                return;
             }
         }
+        self.create_series_data(index);
+        self.animation.reset();
+    }
+
+    pub fn scale_bandwidth(&mut self, factor: f32) {
+        if let Some(ref data) = self.chart_data {
+            self.kde_bandwidth = Bandwidth::Manual(data.kde_bandwidth * factor);
+            self.update_kde();
+        }
+    }
+
+    pub fn reset_bandwidth(&mut self) {
+        self.kde_bandwidth = Bandwidth::Silverman;
+        self.update_kde();
     }
 
     pub fn simulate(&mut self) {
         let seed: u64 = self.seeder.random::<u64>();
         self.reseed(seed);
+        if let Some(ref mut mc) = self.monte_carlo {
+            *mc = MonteCarlo::new(mc.experiment, self.seeder.random::<u64>());
+        }
 
         self.animation.reset();
-        let kind = match self.chart.title {
-            None => ChartKind::default(),
-            Some(name) => ChartKind::from_str(name),
-        };
+        let kind = self.chart_kind();
 
         self.create_chart_data();
-        if let Some(ref mut chart_data) = self.chart_data {
-            chart_data.kind = kind;
+        for index in 0..self.series.len() {
+            let seed = self.seeder.random::<u64>();
+            self.series[index].generator.reseed(seed);
+            self.create_series_data(index);
         }
+        self.set_chart_kind(kind);
     }
 }
 
@@ -246,17 +1094,60 @@ impl Default for State {
             height: 1024,
             width: 1024,
             sidebar_width: layout::sidebar::MAX_SIDEBAR_WIDTH,
+            sidebar_collapsed: None,
+            sidebar_preferred: None,
+            sidebar_drag: None,
             measure: None,
-            font: None,
+            fonts: FontRegistry::default(),
             chart: MenuState::default(),
             dist: MenuState::dist(),
+            kde_menu: MenuState::kde(),
+            kde: None,
+            kde_bandwidth: Bandwidth::default(),
+            reference_menu: MenuState::reference(),
+            reference: None,
             generator,
             chart_data: None,
+            stream: None,
+            clt: None,
+            sample_count: consts::SAMPLE_COUNT,
+            bin_count: consts::BIN_COUNT,
+            input: None,
+            font_file: None,
+            process: Process::default(),
+            path_count: consts::PATH_COUNTS[3],
+            correlation: consts::DEFAULT_CORRELATION,
+            finals_element: None,
+            stream_element: None,
+            monte_carlo: None,
+            monte_carlo_elements: Vec::with_capacity(2),
+            primary_visible: true,
+            series: Vec::new(),
+            small_multiples: false,
             custom_element: None,
+            chart_view: None,
+            view_window: None,
+            drag: None,
+            xscale: math::AxisScale::default(),
+            yscale: math::AxisScale::default(),
+            cell_elements: Vec::with_capacity(consts::MAX_SERIES),
             should_close: false,
-            text_array: Vec::with_capacity(256),
             chart_x: Vec::with_capacity(256),
             chart_y: Vec::with_capacity(256),
+            text: TextArena::default(),
+            show_stats: false,
+            show_markers: true,
+            show_help: false,
+            focus: keyboard::Focus::default(),
+            palette: None,
+            recent: Vec::new(),
+            session_path: None,
+            export_path: None,
+            bindings: keyboard::Bindings::default(),
+            theme: scheme::Theme::default(),
+            theme_file: None,
+            dpi_scale: 1.0,
+            ui_scale: 1.0,
             seed,
             seeder: rand::rngs::SmallRng::seed_from_u64(seed),
             animation: Animation::default(),
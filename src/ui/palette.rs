@@ -0,0 +1,335 @@
+use super::actions::Action;
+use super::{clay, keyboard, raylib, session, State};
+use crate::error::AppError;
+use crate::input;
+
+use std::path::PathBuf;
+
+pub const PALETTE_ELEMENT_ID: &str = "CommandPalette";
+const MAX_RECENT: usize = 8;
+const MAX_QUERY: usize = 64; // Characters
+const EXPORT_FILE: &str = "sample.csv"; // In the working directory until the user picks another path
+
+// Actions asking for a file path, typed in the palette instead of a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    SaveSession,
+    OpenSession,
+    Export,
+}
+
+impl Prompt {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::SaveSession => "Save session as",
+            Self::OpenSession => "Open session",
+            Self::Export => "Export the sample as CSV to",
+        }
+    }
+}
+
+// Query typed so far and the actions matching it, best first
+#[derive(Debug, Default, Clone)]
+pub struct Palette {
+    pub query: String,
+    pub matches: Vec<Action>,
+    pub selected: usize,        // Index in `matches`
+    pub prompt: Option<Prompt>, // The query is a path rather than a command
+    pub error: Option<String>,  // Of the last attempt at the prompt
+}
+
+impl Palette {
+    fn new(recent: &[Action]) -> Self {
+        let mut palette = Self::default();
+        palette.update(recent);
+        palette
+    }
+
+    fn update(&mut self, recent: &[Action]) {
+        self.matches = if self.prompt.is_some() {
+            Vec::new()
+        } else {
+            rank(&self.query, recent)
+        };
+        self.selected = 0;
+        self.error = None;
+    }
+
+    // Wrapping around at the ends, like the focus
+    fn step(&mut self, forward: bool) {
+        let len = self.matches.len();
+        if len == 0 {
+            return;
+        }
+        self.selected = if forward {
+            (self.selected + 1) % len
+        } else {
+            (self.selected + len - 1) % len
+        };
+    }
+}
+
+pub fn toggle(state: &mut State) {
+    if state.palette.take().is_some() {
+        return;
+    }
+    open(state, Palette::new(&state.recent));
+}
+
+// Opens the palette on the path of the last file saved or opened for the same kind of prompt
+pub fn ask(state: &mut State, prompt: Prompt) {
+    let path = match prompt {
+        Prompt::Export => state.export_path.clone().or_else(|| Some(PathBuf::from(EXPORT_FILE))),
+        Prompt::SaveSession | Prompt::OpenSession => state.session_path.clone().or_else(session::default_path),
+    };
+    let query = path.map(|path| path.display().to_string()).unwrap_or_default();
+    open(state, Palette { query, prompt: Some(prompt), ..Palette::default() });
+}
+
+fn open(state: &mut State, palette: Palette) {
+    // Whatever was typed before opening is not part of the query
+    while unsafe { raylib::GetCharPressed() } != 0 {}
+    state.unclick();
+    state.show_help = false;
+    state.focus.visible = false;
+    state.palette = Some(palette);
+}
+
+// Closes the palette and moves the command to the front of the recent ones
+pub fn run(state: &mut State, action: Action) {
+    state.palette = None;
+    state.recent.retain(|recent| *recent != action);
+    state.recent.insert(0, action);
+    state.recent.truncate(MAX_RECENT);
+    action.perform(state);
+}
+
+// Keeps the palette open with the error when the file cannot be written or read
+fn submit(state: &mut State, prompt: Prompt, path: PathBuf) {
+    let result = match prompt {
+        Prompt::SaveSession => session::save(state, &path),
+        Prompt::OpenSession => session::load(&path).and_then(|session| {
+            let session = session.ok_or_else(|| AppError::InvalidSession {
+                path: path.display().to_string(),
+                position: None,
+                message: "no such file".to_string(),
+            })?;
+            session.apply(state);
+            Ok(())
+        }),
+        Prompt::Export => match state.chart_data.as_ref() {
+            Some(data) => input::save(&path, &data.sample),
+            None => Err(AppError::SampleNotExported(
+                path.display().to_string(),
+                "there is no sample to export".to_string(),
+            )),
+        },
+    };
+    match result {
+        Ok(()) => {
+            state.palette = None;
+            match prompt {
+                Prompt::Export => state.export_path = Some(path),
+                Prompt::SaveSession | Prompt::OpenSession => state.session_path = Some(path),
+            }
+        }
+        Err(err) => {
+            if let Some(palette) = state.palette.as_mut() {
+                palette.error = Some(err.to_string().trim_end().to_string());
+            }
+        }
+    }
+}
+
+fn clipboard() -> Option<String> {
+    let text = unsafe { raylib::GetClipboardText() };
+    if text.is_null() {
+        return None;
+    }
+    Some(unsafe { std::ffi::CStr::from_ptr(text) }.to_string_lossy().into_owned())
+}
+
+// The characters of the query must appear in order in the text, ignoring case and spaces. Matches at the start
+// of a word and runs of consecutive characters score higher, `None` when the text does not match at all
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    const MATCH: i32 = 1;
+    const RUN: i32 = 4; // Following the previous match
+    const WORD_START: i32 = 6;
+
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let text: Vec<char> = text.chars().collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    // Best score of the query so far with its last character matched at each position of the text
+    let mut best: Vec<Option<i32>> = vec![None; text.len()];
+    for (idx, want) in query.iter().enumerate() {
+        let mut next = vec![None; text.len()];
+        let mut before: Option<i32> = None; // Best of `best` up to two positions back, leaving a gap
+        for pos in 0..text.len() {
+            if pos >= 2 {
+                before = before.max(best[pos - 2]);
+            }
+            if !text[pos].to_lowercase().eq(std::iter::once(*want)) {
+                continue;
+            }
+            let start = if pos == 0 || !text[pos - 1].is_alphanumeric() {
+                WORD_START
+            } else {
+                0
+            };
+            let previous = if idx == 0 {
+                Some(0)
+            } else {
+                let run = if pos == 0 {
+                    None
+                } else {
+                    best[pos - 1].map(|score| score + RUN)
+                };
+                run.max(before)
+            };
+            next[pos] = previous.map(|score| score + MATCH + start);
+        }
+        best = next;
+    }
+    best.into_iter().flatten().max()
+}
+
+// Recent commands first, latest on top, then the best matches, then the order of `Action::all`
+pub fn rank(query: &str, recent: &[Action]) -> Vec<Action> {
+    let mut scored: Vec<(usize, i32, usize, Action)> = Action::all()
+        .filter(|action| *action != Action::CommandPalette)
+        .enumerate()
+        .filter_map(|(order, action)| {
+            let score = fuzzy_score(query, &action.label())?;
+            let recency = recent.iter().position(|r| *r == action).unwrap_or(usize::MAX);
+            Some((recency, -score, order, action))
+        })
+        .collect();
+    scored.sort_unstable_by_key(|(recency, score, order, _)| (*recency, *score, *order));
+    scored.into_iter().map(|(_, _, _, action)| action).collect()
+}
+
+fn pressed(key: raylib::KeyboardKey) -> bool {
+    unsafe { raylib::IsKeyPressed(key as i32) || raylib::IsKeyPressedRepeat(key as i32) }
+}
+
+// Takes every key while the palette is open so that typing does not trigger the shortcuts, true if it did
+pub fn handle_keys(state: &mut State) -> bool {
+    if state.palette.is_none() {
+        return false;
+    }
+
+    let clicked_outside = unsafe {
+        raylib::IsMouseButtonPressed(0)
+            && !clay::Clay_PointerOver(clay::Clay_GetElementId(clay::Clay_String::from_str(PALETTE_ELEMENT_ID)))
+    };
+    let toggled = state
+        .bindings
+        .of(Action::CommandPalette)
+        .any(|shortcut| shortcut.is_pressed());
+    if clicked_outside || toggled || pressed(raylib::KeyboardKey_KEY_ESCAPE) {
+        state.palette = None;
+        return true;
+    }
+
+    let Some(palette) = state.palette.as_mut() else {
+        return false;
+    };
+    let mut edited = false;
+    loop {
+        let code = unsafe { raylib::GetCharPressed() };
+        if code == 0 {
+            break;
+        }
+        if let Some(c) = char::from_u32(code as u32).filter(|c| !c.is_control()) {
+            if palette.query.chars().count() < MAX_QUERY {
+                palette.query.push(c);
+                edited = true;
+            }
+        }
+    }
+    if keyboard::is_ctrl_down() && pressed(raylib::KeyboardKey_KEY_V) {
+        if let Some(text) = clipboard() {
+            palette
+                .query
+                .extend(text.chars().filter(|c| !c.is_control()).take(MAX_QUERY));
+            edited = true;
+        }
+    }
+    if pressed(raylib::KeyboardKey_KEY_BACKSPACE) {
+        if keyboard::is_ctrl_down() {
+            palette.query.clear();
+        } else {
+            palette.query.pop();
+        }
+        edited = true;
+    }
+    if edited {
+        palette.update(&state.recent);
+    }
+
+    if pressed(raylib::KeyboardKey_KEY_DOWN) || pressed(raylib::KeyboardKey_KEY_UP) {
+        palette.step(pressed(raylib::KeyboardKey_KEY_DOWN));
+    }
+    let enter = pressed(raylib::KeyboardKey_KEY_ENTER) || pressed(raylib::KeyboardKey_KEY_KP_ENTER);
+    if !enter {
+        return true;
+    }
+    match palette.prompt {
+        Some(prompt) if !palette.query.trim().is_empty() => {
+            let path = PathBuf::from(palette.query.trim());
+            submit(state, prompt, path);
+        }
+        Some(_) => {}
+        None => {
+            if let Some(action) = palette.matches.get(palette.selected).copied() {
+                run(state, action);
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chart::ChartKind;
+
+    #[test]
+    fn fuzzy_score_matches_in_order_ignoring_case_and_spaces() {
+        assert!(fuzzy_score("", "Simulate").is_some());
+        assert!(fuzzy_score("sim", "Simulate").is_some());
+        assert!(fuzzy_score("SML", "Simulate").is_some());
+        assert!(fuzzy_score("next th", "Next theme").is_some());
+        assert!(fuzzy_score("mis", "Simulate").is_none());
+        assert!(fuzzy_score("simulates", "Simulate").is_none());
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_word_starts_and_runs() {
+        assert!(fuzzy_score("nt", "Next theme") > fuzzy_score("nt", "Central"));
+        assert!(fuzzy_score("mu", "Simulate") > fuzzy_score("ml", "Simulate"));
+    }
+
+    #[test]
+    fn rank_puts_recent_commands_first() {
+        let all = rank("", &[]);
+        assert_eq!(all.first(), Some(&Action::Simulate));
+        assert!(!all.contains(&Action::CommandPalette));
+
+        let recent = [Action::Reset, Action::Chart(ChartKind::QQ)];
+        let ranked = rank("", &recent);
+        assert_eq!(&ranked[..2], &recent);
+        assert_eq!(ranked.len(), all.len());
+
+        let ranked = rank("chart", &recent);
+        assert_eq!(ranked.first(), Some(&Action::Chart(ChartKind::QQ)));
+        assert!(!ranked.contains(&Action::Reset));
+    }
+}
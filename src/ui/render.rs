@@ -2,13 +2,16 @@ use std::convert::TryInto;
 use std::pin::Pin;
 use std::time::{Duration, Instant};
 
-use super::State;
-use crate::chart::{ChartData, ChartDataHistogram, ChartDataLine, ChartKind, CustomElementKind};
+use super::{State, Stream};
+use crate::chart::{ChartData, ChartDataBox, ChartDataHistogram, ChartDataLine, ChartKind, CustomElementKind};
+use crate::easing::Easing;
 use crate::ffi::{clay, raylib};
-use crate::math::{clamp, lerp};
-use crate::ui::scheme::SchemeUi;
+use crate::font::{draw_text, FONT_MONO};
+use crate::math::{clamp, lerp, remap, AxisScale};
+use crate::montecarlo::{Experiment, MonteCarlo, BUFFON_LINES, INTEGRAL_UPPER};
+use crate::ui::scheme::{SchemeAnimation, SchemeUi, SCHEME};
 
-pub type RenderLayoutSignature = fn(Pin<&mut State>, clay::Clay_RenderCommandArray, raylib::Font);
+pub type RenderLayoutSignature = fn(Pin<&mut State>, clay::Clay_RenderCommandArray);
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct ChartCanvas {
@@ -17,6 +20,7 @@ pub struct ChartCanvas {
     pub ybgn: f32,
     pub yend: f32,
     pub thick: f32,
+    pub scale: f32, // UI scale, strokes and labels are given at a scale of 1
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -24,9 +28,60 @@ pub struct Animation {
     pub start: Instant,
     pub now: Instant,
     pub duration: Duration,
+    pub easing: Easing,
+    pub reveal: Reveal,
+    once: Option<Reveal>, // Takes over `reveal` until the next reset
+}
+
+// How the bars of a histogram come into view
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Reveal {
+    #[default]
+    Sweep, // One bar after the other, from left to right
+    All,   // Every bar grows at the same time
+    Morph, // Bars move from the previous data to the new one
+}
+
+impl Reveal {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sweep => "Sweep",
+            Self::All => "All",
+            Self::Morph => "Morph",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Sweep => Self::All,
+            Self::All => Self::Morph,
+            Self::Morph => Self::Sweep,
+        }
+    }
 }
 
 impl Animation {
+    pub fn configure(&mut self, scheme: &SchemeAnimation) {
+        self.duration = scheme.duration;
+        self.easing = scheme.easing;
+        self.reveal = scheme.reveal;
+    }
+
+    // Restarts with a given reveal, whatever the user picked, e.g. to morph between related data
+    pub fn replay(&mut self, reveal: Reveal) {
+        self.reset();
+        self.once = Some(reveal);
+    }
+
+    pub fn active_reveal(&self) -> Reveal {
+        self.once.unwrap_or(self.reveal)
+    }
+
+    // Eased version of `percentage`, may go past 1 before settling for some easings
+    pub fn progress(&self) -> f32 {
+        self.easing.apply(self.percentage())
+    }
+
     pub fn end(&self) -> Instant {
         self.start + self.duration
     }
@@ -57,23 +112,29 @@ impl Animation {
         let now = Instant::now();
         self.start = now;
         self.now = now;
+        self.once = None;
     }
 }
 
 impl Default for Animation {
     fn default() -> Self {
         let now = Instant::now();
-        Self { start: now, duration: Duration::from_millis(1000), now }
+        Self {
+            start: now,
+            duration: SCHEME.animation.duration,
+            now,
+            easing: SCHEME.animation.easing,
+            reveal: SCHEME.animation.reveal,
+            once: None,
+        }
     }
 }
 
-pub fn render_layout(
-    state: Pin<&mut State>,
-    cmd_array: clay::Clay_RenderCommandArray,
-    font: raylib::Font,
-    scheme: &SchemeUi,
-) {
+pub fn render_layout(state: Pin<&mut State>, cmd_array: clay::Clay_RenderCommandArray, scheme: &SchemeUi) {
     let state = unsafe { state.get_unchecked_mut() };
+    let fonts = state.fonts;
+    let font = fonts.get(FONT_MONO); // Chart labels are all numbers
+    state.chart_view = None;
     for item in 0..cmd_array.length {
         // SAFETY: if this causes a problem, it is an error with Clay
         let ptr = unsafe { cmd_array.internalArray.offset(item as isize) };
@@ -88,7 +149,7 @@ pub fn render_layout(
             Some(rf) => rf,
         };
 
-        let bbox = cmd.boundingBox;
+        let bbox = scale_box(cmd.boundingBox, scheme.scale);
         let cmd_type: Result<clay::ClayRenderCommandType, clay::ClayRenderCommandTypeError> =
             cmd.commandType.try_into();
 
@@ -96,30 +157,32 @@ pub fn render_layout(
             Ok(ctype) => match ctype {
                 clay::ClayRenderCommandType::Rectangle => {
                     let cfg = unsafe { cmd.renderData.rectangle };
-                    draw_rectangle(bbox, cfg.cornerRadius, cfg.backgroundColor);
+                    draw_rectangle(bbox, scale_corners(cfg.cornerRadius, scheme.scale), cfg.backgroundColor);
                 }
                 clay::ClayRenderCommandType::Text => {
                     let text_data = unsafe { cmd.renderData.text };
-                    let txt = &mut state.text_array;
-                    txt.clear();
-                    for i in 0..text_data.stringContents.length {
-                        txt.push(unsafe { *text_data.stringContents.chars.offset(i as isize) });
-                    }
-                    txt.push('\0' as i8);
+                    let txt = unsafe { text_data.stringContents.as_str_lossy() };
                     let pos = raylib::Vector2 { x: bbox.x, y: bbox.y };
-                    unsafe {
-                        raylib::DrawTextEx(
-                            font,
-                            txt.as_ptr(),
-                            pos,
-                            text_data.fontSize as f32,
-                            text_data.letterSpacing as f32,
-                            text_data.textColor.into(),
-                        );
-                    }
+                    draw_text(
+                        fonts.get(text_data.fontId),
+                        &txt,
+                        pos,
+                        text_data.fontSize as f32 * scheme.scale,
+                        text_data.letterSpacing as f32 * scheme.scale,
+                        text_data.textColor.into(),
+                    );
                 }
                 clay::ClayRenderCommandType::Border => {
-                    let cfg = unsafe { cmd.renderData.border };
+                    let mut cfg = unsafe { cmd.renderData.border };
+                    cfg.cornerRadius = scale_corners(cfg.cornerRadius, scheme.scale);
+                    for width in [
+                        &mut cfg.width.left,
+                        &mut cfg.width.right,
+                        &mut cfg.width.top,
+                        &mut cfg.width.bottom,
+                    ] {
+                        *width = scale_width(*width, scheme.scale);
+                    }
                     draw_raylib_border(bbox, cfg);
                 }
                 clay::ClayRenderCommandType::ScissorStart => unsafe {
@@ -133,7 +196,8 @@ pub fn render_layout(
                 clay::ClayRenderCommandType::ScissorEnd => unsafe { raylib::EndScissorMode() },
                 clay::ClayRenderCommandType::Custom => unsafe {
                     let custom: *mut CustomElementKind = std::mem::transmute(cmd.renderData.custom.customData);
-                    draw_rectangle(bbox, cmd.renderData.custom.cornerRadius, cmd.renderData.custom.backgroundColor);
+                    let corner = scale_corners(cmd.renderData.custom.cornerRadius, scheme.scale);
+                    draw_rectangle(bbox, corner, cmd.renderData.custom.backgroundColor);
 
                     // SAFETY: at this stage there should be no mut refs to the pointer, even
                     // though it was created from a mut ref
@@ -141,7 +205,54 @@ pub fn render_layout(
                         match rf {
                             CustomElementKind::Chart(data) => {
                                 if let Some(ref_data) = data.as_ref() {
-                                    draw_chart(state, ref_data, bbox, scheme);
+                                    let view = {
+                                        let layers = overlay_layers(state, ref_data, scheme);
+                                        draw_chart(state, &layers, bbox, font, scheme)
+                                    };
+                                    if let Some(view) = view {
+                                        draw_crosshair(&view, scheme);
+                                        draw_box_zoom(state, scheme);
+                                    }
+                                    state.chart_view = view;
+                                }
+                            }
+                            CustomElementKind::Cell(index) => {
+                                if let Some(layer) = series_layer(state, *index, scheme) {
+                                    draw_chart(state, &[layer], bbox, font, scheme);
+                                }
+                            }
+                            CustomElementKind::Finals(data) => {
+                                if let Some(ref_data) = data.as_ref() {
+                                    draw_final_values(state, ref_data, bbox, font, scheme);
+                                }
+                            }
+                            CustomElementKind::MonteCarloPi(mc) => {
+                                if let Some(mc) = mc.as_ref() {
+                                    let frame = experiment_frame(0.0, 1.0);
+                                    draw_experiment(mc, bbox, frame, font, scheme, draw_quarter_circle);
+                                }
+                            }
+                            CustomElementKind::MonteCarloIntegral(mc) => {
+                                if let Some(mc) = mc.as_ref() {
+                                    let frame = ChartFrame { xmax: INTEGRAL_UPPER, ..experiment_frame(0.0, 1.0) };
+                                    draw_experiment(mc, bbox, frame, font, scheme, draw_integrand);
+                                }
+                            }
+                            CustomElementKind::MonteCarloBuffon(mc) => {
+                                if let Some(mc) = mc.as_ref() {
+                                    // Needles near the edges stick out of the square by up to half their length
+                                    let frame = experiment_frame(-0.5, BUFFON_LINES + 0.5);
+                                    draw_experiment(mc, bbox, frame, font, scheme, draw_buffon_lines);
+                                }
+                            }
+                            CustomElementKind::MonteCarloEstimate(mc) => {
+                                if let Some(mc) = mc.as_ref() {
+                                    draw_running_estimate(mc, bbox, font, scheme);
+                                }
+                            }
+                            CustomElementKind::StreamMean(stream) => {
+                                if let Some(stream) = stream.as_ref() {
+                                    draw_running_mean(stream, bbox, font, scheme);
                                 }
                             }
                         }
@@ -156,6 +267,69 @@ pub fn render_layout(
             }
         }
     }
+    draw_focus_ring(state, scheme);
+}
+
+// Drawn over everything once the keyboard has moved the focus, hidden again by the mouse
+fn draw_focus_ring(state: &State, scheme: &SchemeUi) {
+    const GAP: f32 = 3.0;
+    const WIDTH: u16 = 2;
+    const RADIUS: f32 = 6.0;
+
+    let id = match state.focus.focused {
+        Some(id) if state.focus.visible => id,
+        _ => return,
+    };
+    let data = unsafe { clay::Clay_GetElementData(id) };
+    if !data.found {
+        return;
+    }
+    let bbox = clay::Clay_BoundingBox {
+        x: data.boundingBox.x - GAP,
+        y: data.boundingBox.y - GAP,
+        width: data.boundingBox.width + 2.0 * GAP,
+        height: data.boundingBox.height + 2.0 * GAP,
+    };
+    let width = scale_width(WIDTH, scheme.scale);
+    let cfg = clay::Clay_BorderRenderData {
+        color: scheme.canvas.focus,
+        cornerRadius: scale_corners(clay::Clay_CornerRadius::all(RADIUS), scheme.scale),
+        width: clay::Clay_BorderWidth {
+            left: width,
+            right: width,
+            top: width,
+            bottom: width,
+            betweenChildren: 0,
+        },
+    };
+    draw_raylib_border(scale_box(bbox, scheme.scale), cfg);
+}
+
+// Clay lays the UI out in logical pixels, each drawn as `scale` pixels of the window
+fn scale_box(bbox: clay::Clay_BoundingBox, scale: f32) -> clay::Clay_BoundingBox {
+    clay::Clay_BoundingBox {
+        x: bbox.x * scale,
+        y: bbox.y * scale,
+        width: bbox.width * scale,
+        height: bbox.height * scale,
+    }
+}
+
+fn scale_corners(corner: clay::Clay_CornerRadius, scale: f32) -> clay::Clay_CornerRadius {
+    clay::Clay_CornerRadius {
+        topLeft: corner.topLeft * scale,
+        topRight: corner.topRight * scale,
+        bottomLeft: corner.bottomLeft * scale,
+        bottomRight: corner.bottomRight * scale,
+    }
+}
+
+// Borders stay at least a pixel wide
+fn scale_width(width: u16, scale: f32) -> u16 {
+    if width == 0 {
+        return 0;
+    }
+    ((width as f32) * scale).round().max(1.0) as u16
 }
 
 fn draw_rectangle(bbox: clay::Clay_BoundingBox, corner: clay::Clay_CornerRadius, background_color: clay::Clay_Color) {
@@ -192,18 +366,19 @@ fn draw_chart_canvas(canvas: clay::Clay_BoundingBox, scheme: &SchemeUi) -> Chart
     let start = 0.02;
     let end = 1.0 - start;
     let mut output = ChartCanvas::default();
+    let scale = scheme.scale;
 
     let min_screen = 360.0;
     let max_screen = 1080.0;
 
-    let thick = lerp(canvas.height.min(canvas.width), min_screen, max_screen, 1.5, 3.0);
-    let padding = 5.0;
+    let thick = scale * lerp(canvas.height.min(canvas.width) / scale, min_screen, max_screen, 1.5, 3.0);
+    let padding = 5.0 * scale;
     let y = canvas.y + canvas.height * end;
     let x1 = canvas.x + (canvas.x * start).min(padding);
     let x2 = canvas.x + canvas.width * end;
     output.yend = y - thick;
 
-    let offset = clamp(canvas.height.min(canvas.width) * start, 5.0, 10.0);
+    let offset = clamp(canvas.height.min(canvas.width) * start, 5.0 * scale, 10.0 * scale);
 
     // x axis
     unsafe {
@@ -214,7 +389,7 @@ fn draw_chart_canvas(canvas: clay::Clay_BoundingBox, scheme: &SchemeUi) -> Chart
             scheme.chart.aes.xaxis.into(),
         );
 
-        let xtip = (x2 + 1.5).min(canvas.width + canvas.x);
+        let xtip = (x2 + 1.5 * scale).min(canvas.width + canvas.x);
         let xback = xtip - offset * 1.20;
         let yup = y + offset;
         let ydown = y - offset;
@@ -228,7 +403,7 @@ fn draw_chart_canvas(canvas: clay::Clay_BoundingBox, scheme: &SchemeUi) -> Chart
         );
     }
 
-    let x = x1 + (canvas.width * start).max(3.0);
+    let x = x1 + (canvas.width * start).max(3.0 * scale);
     let y1 = canvas.y + canvas.height * start;
     let y2 = canvas.y + (canvas.height * end).max(canvas.height - padding);
     output.xbgn = x + thick;
@@ -242,7 +417,7 @@ fn draw_chart_canvas(canvas: clay::Clay_BoundingBox, scheme: &SchemeUi) -> Chart
             scheme.chart.aes.yaxis.into(),
         );
 
-        let ytip = y1 - 1.5;
+        let ytip = y1 - 1.5 * scale;
         let ydwn = ytip + offset * 1.20;
         let xfwd = x + offset;
         let xbck = x - offset;
@@ -266,106 +441,1280 @@ fn draw_chart_canvas(canvas: clay::Clay_BoundingBox, scheme: &SchemeUi) -> Chart
         );
     }
     output.thick = thick;
+    output.scale = scale;
     output
 }
 
-fn draw_chart(state: &State, data: &ChartData, canvas: clay::Clay_BoundingBox, scheme: &SchemeUi) {
+// Data space window shown by the chart, shared by every layer so they can be compared against each other
+#[derive(Debug, Clone, Copy)]
+pub struct ChartFrame {
+    pub xmin: f32,
+    pub xmax: f32,
+    pub ymin: f32,
+    pub ymax: f32,
+    pub xscale: AxisScale,
+    pub yscale: AxisScale,
+}
+
+impl Default for ChartFrame {
+    fn default() -> Self {
+        Self {
+            xmin: f32::MAX,
+            xmax: f32::MIN,
+            ymin: 0.0,
+            ymax: f32::MIN,
+            xscale: AxisScale::Linear,
+            yscale: AxisScale::Linear,
+        }
+    }
+}
+
+impl ChartFrame {
+    pub fn include_hist(&mut self, hist: &ChartDataHistogram) {
+        if hist.data.is_empty() {
+            return;
+        }
+
+        let scale = hist.density_scale();
+        if !scale.is_finite() {
+            return;
+        }
+
+        let maxval = *hist.data.iter().max().unwrap_or(&0);
+        self.xmin = self.xmin.min(hist.min);
+        self.xmax = self.xmax.max(hist.max);
+        self.ymax = self.ymax.max((maxval as f32) * scale);
+    }
+
+    pub fn include_line(&mut self, line: &ChartDataLine) {
+        for (xval, yval) in line.x.iter().zip(line.y.iter()) {
+            self.xmin = self.xmin.min(*xval);
+            self.xmax = self.xmax.max(*xval);
+            self.ymin = self.ymin.min(*yval);
+            self.ymax = self.ymax.max(*yval);
+        }
+    }
+
+    // The assumption is that if min == max then we are already in the [0.0 1.0] space
+    pub fn finish(mut self) -> Self {
+        if (self.xmax - self.xmin) < crate::math::EPS {
+            self.xmin = 0.0;
+            self.xmax = 1.0;
+        }
+
+        if (self.ymax - self.ymin) < crate::math::EPS {
+            self.ymin = 0.0;
+            self.ymax = 1.0;
+        }
+        self
+    }
+
+    // Same range on both axes, so the identity line is the diagonal of the chart
+    pub fn square<'a, I: Iterator<Item = &'a ChartDataLine>>(lines: I) -> Self {
+        let mut lo = f32::MAX;
+        let mut hi = f32::MIN;
+        for line in lines {
+            for val in line.x.iter().chain(line.y.iter()) {
+                if val.is_finite() {
+                    lo = lo.min(*val);
+                    hi = hi.max(*val);
+                }
+            }
+        }
+
+        Self { xmin: lo, xmax: hi, ymin: lo, ymax: hi, ..Default::default() }.finish()
+    }
+
+    // The range is shrunk into the domain of the scales, e.g. a log axis cannot start at 0
+    pub fn with_scales(mut self, xscale: AxisScale, yscale: AxisScale) -> Self {
+        (self.xmin, self.xmax) = xscale.fit(self.xmin, self.xmax);
+        (self.ymin, self.ymax) = yscale.fit(self.ymin, self.ymax);
+        self.xscale = xscale;
+        self.yscale = yscale;
+        self
+    }
+
+    // Where bars start from, 0 when the y axis can show it and the bottom of the chart otherwise
+    pub fn baseline(&self) -> f32 {
+        if self.yscale.contains(0.0) {
+            0.0
+        } else {
+            self.ymin
+        }
+    }
+
+    // Not clamped, when zoomed in the data outside of the window is cut off by the scissor instead
+    pub fn screen_x(&self, dim: &ChartCanvas, x: f32) -> f32 {
+        let scale = self.xscale;
+        let (lo, hi) = (scale.forward(self.xmin), scale.forward(self.xmax));
+        remap(scale.forward(x), lo, hi, dim.xbgn, dim.xend - dim.thick)
+    }
+
+    pub fn screen_y(&self, dim: &ChartCanvas, y: f32) -> f32 {
+        let scale = self.yscale;
+        let (lo, hi) = (scale.forward(self.ymin), scale.forward(self.ymax));
+        remap(scale.forward(y), lo, hi, dim.yend, dim.ybgn) // Raylib's orientation is top-down
+    }
+
+    // Inverse of `screen_x`
+    pub fn data_x(&self, dim: &ChartCanvas, x: f32) -> f32 {
+        let scale = self.xscale;
+        let (lo, hi) = (scale.forward(self.xmin), scale.forward(self.xmax));
+        scale.inverse(remap(x, dim.xbgn, dim.xend - dim.thick, lo, hi))
+    }
+
+    // Inverse of `screen_y`
+    pub fn data_y(&self, dim: &ChartCanvas, y: f32) -> f32 {
+        let scale = self.yscale;
+        let (lo, hi) = (scale.forward(self.ymin), scale.forward(self.ymax));
+        scale.inverse(remap(y, dim.yend, dim.ybgn, lo, hi))
+    }
+
+    // Scales the window by `factor` keeping the point (x, y) at the same place on screen.
+    // Done in the space of the scales so that a log axis zooms by decades
+    pub fn zoomed(&self, x: f32, y: f32, factor: f32) -> Self {
+        let zoom = |scale: AxisScale, val: f32, lo: f32, hi: f32| {
+            let (val, lo, hi) = (scale.forward(val), scale.forward(lo), scale.forward(hi));
+            (scale.inverse(val - (val - lo) * factor), scale.inverse(val + (hi - val) * factor))
+        };
+
+        let (xmin, xmax) = zoom(self.xscale, x, self.xmin, self.xmax);
+        let (ymin, ymax) = zoom(self.yscale, y, self.ymin, self.ymax);
+        Self { xmin, xmax, ymin, ymax, ..*self }
+    }
+
+    // Moves the window so that the data under the screen position `from` ends up under `to`
+    pub fn panned(&self, dim: &ChartCanvas, from: raylib::Vector2, to: raylib::Vector2) -> Self {
+        let shift = |scale: AxisScale, from: f32, to: f32, lo: f32, hi: f32| {
+            let delta = scale.forward(from) - scale.forward(to);
+            (scale.inverse(scale.forward(lo) + delta), scale.inverse(scale.forward(hi) + delta))
+        };
+
+        let (xmin, xmax) = shift(self.xscale, self.data_x(dim, from.x), self.data_x(dim, to.x), self.xmin, self.xmax);
+        let (ymin, ymax) = shift(self.yscale, self.data_y(dim, from.y), self.data_y(dim, to.y), self.ymin, self.ymax);
+        Self { xmin, xmax, ymin, ymax, ..*self }
+    }
+
+    pub fn cropped(&self, x0: f32, y0: f32, x1: f32, y1: f32) -> Self {
+        Self {
+            xmin: x0.min(x1),
+            xmax: x0.max(x1),
+            ymin: y0.min(y1),
+            ymax: y0.max(y1),
+            ..*self
+        }
+    }
+}
+
+// The window picked by the user, if any, wins over the one fitted to the data
+fn view_frame(state: &State, fitted: ChartFrame, (xscale, yscale): (AxisScale, AxisScale)) -> ChartFrame {
+    state.view_window.unwrap_or(fitted).with_scales(xscale, yscale)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ChartDrag {
+    Pan {
+        last: raylib::Vector2,
+    },
+    BoxZoom {
+        start: raylib::Vector2,
+        end: raylib::Vector2,
+    },
+}
+
+fn draw_box_zoom(state: &State, scheme: &SchemeUi) {
+    let Some(ChartDrag::BoxZoom { start, end }) = state.drag else {
+        return;
+    };
+
+    let rect = raylib::Rectangle {
+        x: start.x.min(end.x),
+        y: start.y.min(end.y),
+        width: (end.x - start.x).abs(),
+        height: (end.y - start.y).abs(),
+    };
+    let mut fill: raylib::Color = scheme.chart.aes.reference.into();
+    fill.a = 50;
+    unsafe {
+        raylib::DrawRectangleV(
+            raylib::Vector2 { x: rect.x, y: rect.y },
+            raylib::Vector2 { x: rect.width, y: rect.height },
+            fill,
+        );
+        raylib::DrawRectangleLinesEx(rect, 1.5 * scheme.scale, scheme.chart.aes.reference.into());
+    }
+}
+
+const TICK_COUNT: usize = 6;
+const TICK_LENGTH: f32 = 6.0;
+const TICK_FONT_SIZE: f32 = 16.0;
+
+fn draw_tick_label(
+    label: &mut String,
+    value: f32,
+    pos: raylib::Vector2,
+    dim: &ChartCanvas,
+    font: raylib::Font,
+    colour: raylib::Color,
+) {
+    use std::fmt::Write;
+
+    label.clear();
+    let _ = write!(label, "{}", format_tick(value));
+    draw_text(font, label, pos, TICK_FONT_SIZE * dim.scale, 0.0, colour);
+}
+
+fn format_tick(value: f32) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-3..1e4).contains(&magnitude) {
+        format!("{value:.1e}")
+    } else {
+        let text = format!("{value:.3}");
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+// Labels sit inside the plot, next to their tick, as the canvas leaves no margin around the axes
+fn draw_ticks(dim: &ChartCanvas, frame: &ChartFrame, font: raylib::Font, scheme: &SchemeUi) {
+    let mut ticks = Vec::with_capacity(2 * TICK_COUNT);
+    let mut label = String::with_capacity(16);
+    let (length, size, gap) = (TICK_LENGTH * dim.scale, TICK_FONT_SIZE * dim.scale, 3.0 * dim.scale);
+
+    let colour: raylib::Color = scheme.chart.aes.xaxis.into();
+    frame.xscale.ticks(frame.xmin, frame.xmax, TICK_COUNT, &mut ticks);
+    for tick in ticks.iter() {
+        let x = frame.screen_x(dim, *tick);
+        unsafe {
+            raylib::DrawLineEx(
+                raylib::Vector2 { x, y: dim.yend },
+                raylib::Vector2 { x, y: dim.yend - length },
+                1.5 * dim.scale,
+                colour,
+            );
+        }
+        let pos = raylib::Vector2 { x: x + gap, y: dim.yend - length - size };
+        draw_tick_label(&mut label, *tick, pos, dim, font, colour);
+    }
+
+    let colour: raylib::Color = scheme.chart.aes.yaxis.into();
+    frame.yscale.ticks(frame.ymin, frame.ymax, TICK_COUNT, &mut ticks);
+    for tick in ticks.iter() {
+        let y = frame.screen_y(dim, *tick);
+        unsafe {
+            raylib::DrawLineEx(
+                raylib::Vector2 { x: dim.xbgn, y },
+                raylib::Vector2 { x: dim.xbgn + length, y },
+                1.5 * dim.scale,
+                colour,
+            );
+        }
+        let pos = raylib::Vector2 { x: dim.xbgn + length + gap, y: y - size };
+        draw_tick_label(&mut label, *tick, pos, dim, font, colour);
+    }
+}
+
+// One series drawn on a chart, with the colours used for its areas and for its lines/points
+#[derive(Debug, Clone, Copy)]
+pub struct ChartLayer<'a> {
+    pub data: &'a ChartData,
+    pub fill: raylib::Color,
+    pub colour: raylib::Color,
+}
+
+// Layer for the active series (0) or for `State::series[index - 1]`, `None` when there is nothing to show
+pub fn series_layer<'a>(state: &'a State, index: usize, scheme: &SchemeUi) -> Option<ChartLayer<'a>> {
+    if index == 0 {
+        let data = state.chart_data.as_ref()?;
+        return Some(ChartLayer {
+            data,
+            fill: scheme.chart.aes.fill.into(),
+            colour: scheme.chart.aes.colour.into(),
+        });
+    }
+
+    let series = state.series.get(index - 1)?;
+    let palette = &scheme.chart.aes.series;
+    let colour: raylib::Color = palette[series.colour % palette.len()].into();
+    Some(ChartLayer { data: &series.data, fill: colour, colour })
+}
+
+pub fn overlay_layers<'a>(state: &'a State, primary: &'a ChartData, scheme: &SchemeUi) -> Vec<ChartLayer<'a>> {
+    let mut layers = Vec::with_capacity(1 + state.series.len());
+    if state.primary_visible {
+        layers.push(ChartLayer {
+            data: primary,
+            fill: scheme.chart.aes.fill.into(),
+            colour: scheme.chart.aes.colour.into(),
+        });
+    }
+    for (index, series) in state.series.iter().enumerate() {
+        if series.visible {
+            layers.extend(series_layer(state, index + 1, scheme));
+        }
+    }
+
+    // Overlapping areas need to be see-through to be compared
+    if layers.len() > 1 {
+        for layer in layers.iter_mut() {
+            layer.fill.a = 110;
+        }
+    }
+    layers
+}
+
+// Where and how the main chart was last drawn, so mouse positions can be mapped back to data space
+#[derive(Debug, Clone, Copy)]
+pub struct ChartView {
+    pub dim: ChartCanvas,
+    pub frame: ChartFrame,
+    pub kind: ChartKind,
+}
+
+impl ChartView {
+    pub fn contains(&self, pos: raylib::Vector2) -> bool {
+        pos.x >= self.dim.xbgn && pos.x <= self.dim.xend && pos.y >= self.dim.ybgn && pos.y <= self.dim.yend
+    }
+}
+
+fn draw_crosshair(view: &ChartView, scheme: &SchemeUi) {
+    let pos = unsafe { raylib::GetMousePosition() };
+    if !view.contains(pos) {
+        return;
+    }
+
+    let colour: raylib::Color = scheme.chart.aes.reference.into();
+    unsafe {
+        raylib::DrawLineV(
+            raylib::Vector2 { x: pos.x, y: view.dim.ybgn },
+            raylib::Vector2 { x: pos.x, y: view.dim.yend },
+            colour,
+        );
+        raylib::DrawLineV(
+            raylib::Vector2 { x: view.dim.xbgn, y: pos.y },
+            raylib::Vector2 { x: view.dim.xend, y: pos.y },
+            colour,
+        );
+    }
+}
+
+fn draw_chart(
+    state: &State,
+    layers: &[ChartLayer],
+    canvas: clay::Clay_BoundingBox,
+    font: raylib::Font,
+    scheme: &SchemeUi,
+) -> Option<ChartView> {
+    let kind = layers.first().map(|layer| layer.data.kind).unwrap_or_default();
+    let (canvas, edges) = if kind.is_bivariate() {
+        let (plot, edges) = MarginalEdges::split(canvas, kind == ChartKind::Heatmap, scheme.scale);
+        (plot, Some(edges))
+    } else {
+        (canvas, None)
+    };
     let dim = draw_chart_canvas(canvas, scheme);
-    match data.kind {
-        ChartKind::Hist => draw_histogram(state, &data.hist, dim, scheme),
-        ChartKind::Line => draw_line(state, &data.line, dim, scheme),
-        ChartKind::HistLine => {
-            draw_histogram(state, &data.hist, dim, scheme);
-            draw_line(state, &data.line, dim, scheme);
+    let first = layers.first()?;
+
+    begin_plot_scissor(&dim);
+    let frame = match kind {
+        ChartKind::Hist => Some(draw_distribution(state, layers, (true, false), dim, scheme)),
+        ChartKind::Line => Some(draw_distribution(state, layers, (false, true), dim, scheme)),
+        ChartKind::HistLine => Some(draw_distribution(state, layers, (true, true), dim, scheme)),
+        ChartKind::QQ => draw_probability_plot(state, layers, |data| &data.qq, dim, scheme),
+        ChartKind::PP => draw_probability_plot(state, layers, |data| &data.pp, dim, scheme),
+        ChartKind::Box => Some(draw_box_plot(state, layers, dim, scheme)),
+        ChartKind::Violin => draw_violin_plot(state, layers, dim, scheme),
+        ChartKind::Paths => draw_paths(state, layers, dim, scheme),
+        ChartKind::Scatter => draw_scatter(state, layers, dim),
+        ChartKind::Heatmap => draw_heatmap(state, first.data, dim, scheme),
+    };
+    unsafe {
+        raylib::EndScissorMode();
+    }
+
+    let frame = frame?;
+    if let Some(edges) = edges {
+        edges.draw(first, &dim, &frame, font, scheme);
+    }
+    draw_ticks(&dim, &frame, font, scheme);
+    Some(ChartView { dim, frame, kind })
+}
+
+fn draw_distribution(
+    state: &State,
+    layers: &[ChartLayer],
+    (show_hist, show_line): (bool, bool),
+    dim: ChartCanvas,
+    scheme: &SchemeUi,
+) -> ChartFrame {
+    // The density is estimated from the full sample, which the stream does not keep
+    let show_kde = state.kde.is_some() && state.stream.is_none();
+
+    let mut frame = ChartFrame::default();
+    for layer in layers.iter() {
+        if show_hist {
+            frame.include_hist(&layer.data.hist);
+        }
+        if show_line {
+            frame.include_line(&layer.data.line);
+        }
+        if show_kde {
+            frame.include_line(&layer.data.kde);
         }
     }
+    let frame = view_frame(state, frame.finish(), state.axis_scales());
+
+    if show_hist {
+        for layer in layers.iter() {
+            draw_histogram(state, &layer.data.hist, &layer.data.previous, dim, frame, layer.fill);
+        }
+    }
+    for layer in layers.iter() {
+        if show_kde && !layer.data.kde.x.is_empty() {
+            // With a single series the density keeps its own colour, otherwise it takes the series one
+            let colour = if layers.len() > 1 {
+                layer.colour
+            } else {
+                scheme.chart.aes.kde.into()
+            };
+            draw_line(state, &layer.data.kde, dim, frame, colour);
+        }
+        if show_line {
+            draw_line(state, &layer.data.line, dim, frame, layer.colour);
+        }
+    }
+
+    // Markers of several series on top of each other would be unreadable
+    if show_hist && layers.len() == 1 {
+        draw_markers(state, layers[0].data, dim, frame, scheme);
+    }
+    frame
 }
 
-fn draw_histogram(state: &State, hist: &ChartDataHistogram, dim: ChartCanvas, scheme: &SchemeUi) {
-    let data = hist.data.as_slice();
-    if data.is_empty() {
+pub fn draw_point(center: raylib::Vector2, radius: f32, colour: raylib::Color) {
+    unsafe {
+        raylib::DrawCircleV(center, radius, colour);
+    }
+}
+
+fn draw_paths(state: &State, layers: &[ChartLayer], dim: ChartCanvas, scheme: &SchemeUi) -> Option<ChartFrame> {
+    let mut frame = ChartFrame::default();
+    for layer in layers.iter() {
+        for path in layer.data.paths.iter() {
+            frame.include_line(path);
+        }
+    }
+    if frame.xmin > frame.xmax {
+        return None;
+    }
+    let frame = view_frame(state, frame.finish(), state.axis_scales());
+
+    // A single series tells its paths apart by colour, several series colour them by series
+    let palette = scheme.chart.aes.series;
+    for layer in layers.iter() {
+        for (idx, path) in layer.data.paths.iter().enumerate() {
+            let mut colour = if layers.len() > 1 || idx == 0 {
+                layer.colour
+            } else {
+                palette[(idx - 1) % palette.len()].into()
+            };
+            colour.a = 180;
+            draw_line(state, path, dim, frame, colour);
+        }
+    }
+    Some(frame)
+}
+
+fn draw_scatter(state: &State, layers: &[ChartLayer], dim: ChartCanvas) -> Option<ChartFrame> {
+    let mut frame = ChartFrame { ymin: f32::MAX, ..Default::default() };
+    for layer in layers.iter() {
+        frame.include_line(&layer.data.scatter);
+    }
+    if frame.xmin > frame.xmax {
+        return None;
+    }
+    let frame = view_frame(state, frame.finish(), state.axis_scales());
+
+    // Faint points pile up where the pairs are dense, which is the point of drawing thousands of them
+    let pct = match state.animation.active_reveal() {
+        Reveal::Sweep => state.animation.percentage(),
+        Reveal::All | Reveal::Morph => 1.0,
+    };
+    for layer in layers.iter() {
+        let mut colour = layer.colour;
+        colour.a = 40;
+        let points = &layer.data.scatter;
+        let shown = ((points.x.len() as f32) * pct) as usize;
+        for (x, y) in points.x.iter().zip(points.y.iter()).take(shown) {
+            let center = raylib::Vector2 { x: frame.screen_x(&dim, *x), y: frame.screen_y(&dim, *y) };
+            draw_point(center, 2.5 * dim.scale, colour);
+        }
+    }
+    Some(frame)
+}
+
+fn lerp_colour(lhs: clay::Clay_Color, rhs: clay::Clay_Color, t: f32) -> raylib::Color {
+    clay::Clay_Color {
+        r: lhs.r + (rhs.r - lhs.r) * t,
+        g: lhs.g + (rhs.g - lhs.g) * t,
+        b: lhs.b + (rhs.b - lhs.b) * t,
+        a: lhs.a + (rhs.a - lhs.a) * t,
+    }
+    .into()
+}
+
+// Colour of a cell holding `fraction` of the largest count, along the ramp of the scheme
+fn heat_colour(fraction: f32, scheme: &SchemeUi) -> raylib::Color {
+    let [low, mid, high] = scheme.chart.aes.heatmap;
+    let t = clamp(fraction, 0.0, 1.0);
+    if t < 0.5 {
+        lerp_colour(low, mid, 2.0 * t)
+    } else {
+        lerp_colour(mid, high, 2.0 * t - 1.0)
+    }
+}
+
+fn draw_heatmap(state: &State, data: &ChartData, dim: ChartCanvas, scheme: &SchemeUi) -> Option<ChartFrame> {
+    let heatmap = &data.heatmap;
+    let max = heatmap.max_count();
+    if max == 0 {
+        return None;
+    }
+
+    let fitted = ChartFrame {
+        xmin: heatmap.xmin,
+        xmax: heatmap.xmax,
+        ymin: heatmap.ymin,
+        ymax: heatmap.ymax,
+        ..Default::default()
+    };
+    let frame = view_frame(state, fitted.finish(), state.axis_scales());
+
+    // The sweep reveals the grid one column at a time, like the bars of a histogram
+    let pct = match state.animation.active_reveal() {
+        Reveal::Sweep => state.animation.percentage(),
+        Reveal::All | Reveal::Morph => 1.0,
+    };
+    let (width, height) = (heatmap.cell_width(), heatmap.cell_height());
+    for col in 0..heatmap.bins {
+        if ((col + 1) as f32) / (heatmap.bins as f32) > pct {
+            break;
+        }
+
+        let x0 = frame.screen_x(&dim, heatmap.xmin + (col as f32) * width);
+        let x1 = frame.screen_x(&dim, heatmap.xmin + ((col + 1) as f32) * width);
+        for row in 0..heatmap.bins {
+            let count = heatmap.count(col, row);
+            if count == 0 {
+                continue;
+            }
+
+            let y0 = frame.screen_y(&dim, heatmap.ymin + ((row + 1) as f32) * height);
+            let y1 = frame.screen_y(&dim, heatmap.ymin + (row as f32) * height);
+            unsafe {
+                raylib::DrawRectangleV(
+                    raylib::Vector2 { x: x0, y: y0 },
+                    raylib::Vector2 { x: x1 - x0, y: y1 - y0 },
+                    heat_colour((count as f32) / (max as f32), scheme),
+                );
+            }
+        }
+    }
+    Some(frame)
+}
+
+const MARGINAL_SIZE: f32 = 0.15; // Fraction of the chart taken by each marginal histogram
+const MARGINAL_GAP: f32 = 4.0;
+const COLOUR_BAR_WIDTH: f32 = 16.0;
+const COLOUR_BAR_SPACE: f32 = 64.0; // Bar and its labels
+const COLOUR_BAR_STEPS: usize = 64;
+
+// Room around the plot of a bivariate chart: the marginals along the top and right edges, then the colour bar
+struct MarginalEdges {
+    top: clay::Clay_BoundingBox,
+    right: clay::Clay_BoundingBox,
+    colour_bar: Option<clay::Clay_BoundingBox>,
+}
+
+impl MarginalEdges {
+    // Returns what is left for the plot itself
+    fn split(canvas: clay::Clay_BoundingBox, with_colour_bar: bool, scale: f32) -> (clay::Clay_BoundingBox, Self) {
+        let bar = if with_colour_bar { COLOUR_BAR_SPACE * scale } else { 0.0 };
+        let top_height = canvas.height * MARGINAL_SIZE;
+        let right_width = (canvas.width - bar) * MARGINAL_SIZE;
+
+        let plot = clay::Clay_BoundingBox {
+            x: canvas.x,
+            y: canvas.y + top_height,
+            width: canvas.width - right_width - bar,
+            height: canvas.height - top_height,
+        };
+        let top = clay::Clay_BoundingBox { x: plot.x, y: canvas.y, width: plot.width, height: top_height };
+        let right = clay::Clay_BoundingBox {
+            x: plot.x + plot.width,
+            y: plot.y,
+            width: right_width,
+            height: plot.height,
+        };
+        let colour_bar = with_colour_bar.then_some(clay::Clay_BoundingBox {
+            x: right.x + right.width,
+            y: plot.y,
+            width: bar,
+            height: plot.height,
+        });
+        (plot, Self { top, right, colour_bar })
+    }
+
+    fn draw(&self, layer: &ChartLayer, dim: &ChartCanvas, frame: &ChartFrame, font: raylib::Font, scheme: &SchemeUi) {
+        let data = layer.data;
+        let fill = if data.kind == ChartKind::Heatmap {
+            heat_colour(0.5, scheme)
+        } else {
+            layer.colour
+        };
+
+        // Bars follow the plot when zoomed or panned, and stop at its edges
+        let hist = &data.marginal_x;
+        let max = hist.data.iter().copied().max().unwrap_or(0).max(1) as f32;
+        let width = hist.bin_width();
+        let gap = MARGINAL_GAP * dim.scale;
+        let base = self.top.y + self.top.height - gap;
+        for (bin, count) in hist.data.iter().enumerate() {
+            let lo = frame.screen_x(dim, hist.min + (bin as f32) * width).max(dim.xbgn);
+            let hi = frame.screen_x(dim, hist.min + ((bin + 1) as f32) * width).min(dim.xend);
+            if hi <= lo {
+                continue;
+            }
+            let height = (*count as f32) / max * (self.top.height - 2.0 * gap);
+            unsafe {
+                raylib::DrawRectangleV(
+                    raylib::Vector2 { x: lo, y: base - height },
+                    raylib::Vector2 { x: hi - lo, y: height },
+                    fill,
+                );
+            }
+        }
+
+        let hist = &data.marginal_y;
+        let max = hist.data.iter().copied().max().unwrap_or(0).max(1) as f32;
+        let width = hist.bin_width();
+        let base = self.right.x + gap;
+        for (bin, count) in hist.data.iter().enumerate() {
+            let lo = frame.screen_y(dim, hist.min + ((bin + 1) as f32) * width).max(dim.ybgn);
+            let hi = frame.screen_y(dim, hist.min + (bin as f32) * width).min(dim.yend);
+            if hi <= lo {
+                continue;
+            }
+            let length = (*count as f32) / max * (self.right.width - 2.0 * gap);
+            unsafe {
+                raylib::DrawRectangleV(
+                    raylib::Vector2 { x: base, y: lo },
+                    raylib::Vector2 { x: length, y: hi - lo },
+                    fill,
+                );
+            }
+        }
+
+        if let Some(bar) = self.colour_bar {
+            draw_colour_bar(bar, dim, data.heatmap.max_count(), font, scheme);
+        }
+    }
+}
+
+// Vertical ramp from no count at the bottom to the largest count at the top, level with the plot
+fn draw_colour_bar(bar: clay::Clay_BoundingBox, dim: &ChartCanvas, max: u32, font: raylib::Font, scheme: &SchemeUi) {
+    let x = bar.x + MARGINAL_GAP * dim.scale;
+    let width = COLOUR_BAR_WIDTH * dim.scale;
+    let step = (dim.yend - dim.ybgn) / (COLOUR_BAR_STEPS as f32);
+    for idx in 0..COLOUR_BAR_STEPS {
+        let fraction = (idx as f32) / ((COLOUR_BAR_STEPS - 1) as f32);
+        unsafe {
+            raylib::DrawRectangleV(
+                raylib::Vector2 { x, y: dim.yend - ((idx + 1) as f32) * step },
+                raylib::Vector2 { x: width, y: step.ceil() },
+                heat_colour(fraction, scheme),
+            );
+        }
+    }
+
+    let mut label = String::with_capacity(16);
+    let colour: raylib::Color = scheme.chart.aes.yaxis.into();
+    let lx = x + width + 4.0 * dim.scale;
+    draw_tick_label(&mut label, max as f32, raylib::Vector2 { x: lx, y: dim.ybgn }, dim, font, colour);
+    let bottom = raylib::Vector2 { x: lx, y: dim.yend - TICK_FONT_SIZE * dim.scale };
+    draw_tick_label(&mut label, 0.0, bottom, dim, font, colour);
+}
+
+fn draw_final_values(
+    state: &State,
+    data: &ChartData,
+    canvas: clay::Clay_BoundingBox,
+    font: raylib::Font,
+    scheme: &SchemeUi,
+) {
+    let dim = draw_chart_canvas(canvas, scheme);
+    let mut frame = ChartFrame::default();
+    frame.include_hist(&data.finals);
+    if frame.xmin > frame.xmax {
         return;
     }
+    let frame = frame.finish();
+
+    draw_histogram(state, &data.finals, &data.finals, dim, frame, scheme.chart.aes.fill.into());
+    draw_ticks(&dim, &frame, font, scheme);
+}
+
+fn begin_plot_scissor(dim: &ChartCanvas) {
+    unsafe {
+        raylib::BeginScissorMode(
+            dim.xbgn.round() as i32,
+            dim.ybgn.round() as i32,
+            (dim.xend - dim.xbgn).round() as i32,
+            (dim.yend - dim.ybgn).round() as i32,
+        );
+    }
+}
+
+// Straight segments between consecutive points, skipping the ones touching a missing value
+fn draw_polyline(dim: &ChartCanvas, frame: &ChartFrame, x: &[f32], y: &[f32], thick: f32, colour: raylib::Color) {
+    for (xs, ys) in x.windows(2).zip(y.windows(2)) {
+        if !(xs[0].is_finite() && xs[1].is_finite() && ys[0].is_finite() && ys[1].is_finite()) {
+            continue;
+        }
+        let lhs = raylib::Vector2 { x: frame.screen_x(dim, xs[0]), y: frame.screen_y(dim, ys[0]) };
+        let rhs = raylib::Vector2 { x: frame.screen_x(dim, xs[1]), y: frame.screen_y(dim, ys[1]) };
+        unsafe {
+            raylib::DrawLineEx(lhs, rhs, thick, colour);
+        }
+    }
+}
+
+const BOUNDARY_POINTS: usize = 100;
+
+fn experiment_frame(lo: f32, hi: f32) -> ChartFrame {
+    ChartFrame { xmin: lo, xmax: hi, ymin: lo, ymax: hi, ..Default::default() }
+}
+
+fn draw_quarter_circle(dim: &ChartCanvas, frame: &ChartFrame, colour: raylib::Color) {
+    let (x, y): (Vec<f32>, Vec<f32>) = (0..BOUNDARY_POINTS)
+        .map(|idx| {
+            let angle = remap(idx as f32, 0.0, (BOUNDARY_POINTS - 1) as f32, 0.0, std::f32::consts::FRAC_PI_2);
+            (angle.cos(), angle.sin())
+        })
+        .unzip();
+    draw_polyline(dim, frame, &x, &y, dim.thick, colour);
+}
+
+fn draw_integrand(dim: &ChartCanvas, frame: &ChartFrame, colour: raylib::Color) {
+    let (x, y): (Vec<f32>, Vec<f32>) = (0..BOUNDARY_POINTS)
+        .map(|idx| {
+            let x = remap(idx as f32, 0.0, (BOUNDARY_POINTS - 1) as f32, 0.0, INTEGRAL_UPPER);
+            (x, Experiment::integrand(x))
+        })
+        .unzip();
+    draw_polyline(dim, frame, &x, &y, dim.thick, colour);
+}
+
+fn draw_buffon_lines(dim: &ChartCanvas, frame: &ChartFrame, colour: raylib::Color) {
+    for line in 0..=(BUFFON_LINES as usize) {
+        let y = line as f32;
+        draw_polyline(dim, frame, &[frame.xmin, frame.xmax], &[y, y], dim.thick, colour);
+    }
+}
 
-    let maxval = *data.iter().max().unwrap_or(&0);
-    if maxval == 0 {
+// Trials of a Monte Carlo experiment over the region they are drawn from, the boundary decides the hits
+fn draw_experiment<F>(
+    mc: &MonteCarlo,
+    canvas: clay::Clay_BoundingBox,
+    frame: ChartFrame,
+    font: raylib::Font,
+    scheme: &SchemeUi,
+    boundary: F,
+) where
+    F: Fn(&ChartCanvas, &ChartFrame, raylib::Color),
+{
+    let dim = draw_chart_canvas(canvas, scheme);
+    let hit: raylib::Color = scheme.chart.aes.colour.into();
+    let miss: raylib::Color = scheme.chart.aes.reference.into();
+
+    begin_plot_scissor(&dim);
+    boundary(&dim, &frame, scheme.chart.aes.mean.into());
+    for trial in mc.trials.iter() {
+        let colour = if trial.hit { hit } else { miss };
+        let start = raylib::Vector2 { x: frame.screen_x(&dim, trial.x0), y: frame.screen_y(&dim, trial.y0) };
+        if mc.experiment == Experiment::Buffon {
+            let end = raylib::Vector2 { x: frame.screen_x(&dim, trial.x1), y: frame.screen_y(&dim, trial.y1) };
+            unsafe {
+                raylib::DrawLineEx(start, end, 1.5 * dim.scale, colour);
+            }
+        } else {
+            draw_point(start, 2.0 * dim.scale, colour);
+        }
+    }
+    unsafe {
+        raylib::EndScissorMode();
+    }
+    draw_ticks(&dim, &frame, font, scheme);
+}
+
+// Estimate against the number of trials on a log axis, inside its 95% band and next to the exact value
+fn draw_running_estimate(mc: &MonteCarlo, canvas: clay::Clay_BoundingBox, font: raylib::Font, scheme: &SchemeUi) {
+    let dim = draw_chart_canvas(canvas, scheme);
+    if mc.estimate.x.len() < 2 {
         return;
     }
 
-    let size = data.len() as f32;
-    let incr = 1.0 / size;
-    let maxval = maxval as f32;
-    let mut xleft = dim.xbgn;
-    let width = (dim.xend - dim.xbgn - dim.thick) / size;
-    let height = dim.yend - dim.ybgn;
-
-    let mut exit = false;
-    let mut fct = 0.0;
-    let mut next = fct;
-    let pct = state.animation.percentage();
-    let fill_color: raylib::Color = scheme.chart.aes.fill.into();
-    let edge_color = unsafe { raylib::ColorBrightness(fill_color, -0.5) };
-    for value in data.iter() {
-        next = (next + incr).min(1.0);
-        let mut factor = 1.0;
-        if pct < next {
-            factor = lerp(pct, fct, next, 0.0, 1.0).sqrt();
-            exit = true;
+    let exact = mc.experiment.exact();
+    let mut frame = ChartFrame { ymin: exact, ymax: exact, ..Default::default() };
+    frame.include_line(&mc.estimate);
+    for (lower, upper) in mc.lower.y.iter().zip(mc.upper.y.iter()) {
+        if lower.is_finite() && upper.is_finite() {
+            frame.ymin = frame.ymin.min(*lower);
+            frame.ymax = frame.ymax.max(*upper);
         }
+    }
+    let frame = frame.finish().with_scales(AxisScale::Log10, AxisScale::Linear);
 
-        let actual_height = factor * (*value as f32) * height / maxval;
-        let pos = raylib::Vector2 { x: xleft, y: 0.0 * dim.ybgn + (dim.yend - actual_height) };
-        let size = raylib::Vector2 { x: width, y: actual_height };
+    let mut band: raylib::Color = scheme.chart.aes.fill.into();
+    band.a = 90;
+    begin_plot_scissor(&dim);
+    let (x, lower, upper) = (&mc.lower.x, &mc.lower.y, &mc.upper.y);
+    for idx in 1..x.len() {
+        if !(lower[idx - 1].is_finite() && lower[idx].is_finite()) {
+            continue;
+        }
+        let (x0, x1) = (frame.screen_x(&dim, x[idx - 1]), frame.screen_x(&dim, x[idx]));
+        let top_left = raylib::Vector2 { x: x0, y: frame.screen_y(&dim, upper[idx - 1]) };
+        let bottom_left = raylib::Vector2 { x: x0, y: frame.screen_y(&dim, lower[idx - 1]) };
+        let bottom_right = raylib::Vector2 { x: x1, y: frame.screen_y(&dim, lower[idx]) };
+        let top_right = raylib::Vector2 { x: x1, y: frame.screen_y(&dim, upper[idx]) };
+        // Raylib only fills triangles given counter-clockwise
         unsafe {
-            let rect = raylib::Rectangle { x: pos.x, y: pos.y, width: size.x, height: size.y };
-            raylib::DrawRectangleV(pos, size, fill_color);
-            raylib::DrawRectangleLinesEx(rect, 2.5, edge_color);
+            raylib::DrawTriangle(top_left, bottom_left, bottom_right, band);
+            raylib::DrawTriangle(top_left, bottom_right, top_right, band);
         }
+    }
 
-        if exit {
-            break;
+    let (xmin, xmax) = (frame.xmin, frame.xmax);
+    draw_polyline(&dim, &frame, &[xmin, xmax], &[exact, exact], dim.thick, scheme.chart.aes.mean.into());
+    draw_polyline(
+        &dim,
+        &frame,
+        &mc.estimate.x,
+        &mc.estimate.y,
+        2.5 * dim.scale,
+        scheme.chart.aes.colour.into(),
+    );
+    unsafe {
+        raylib::EndScissorMode();
+    }
+    draw_ticks(&dim, &frame, font, scheme);
+}
+
+// Running mean against the number of samples on a log axis, settling on the true mean
+fn draw_running_mean(stream: &Stream, canvas: clay::Clay_BoundingBox, font: raylib::Font, scheme: &SchemeUi) {
+    let dim = draw_chart_canvas(canvas, scheme);
+    let line = &stream.running_mean;
+    if line.x.len() < 2 {
+        return;
+    }
+
+    let target = stream.target;
+    let mut frame = ChartFrame { ymin: target, ymax: target, ..Default::default() };
+    frame.include_line(line);
+    let frame = frame.finish().with_scales(AxisScale::Log10, AxisScale::Linear);
+
+    begin_plot_scissor(&dim);
+    let (xmin, xmax) = (frame.xmin, frame.xmax);
+    draw_polyline(
+        &dim,
+        &frame,
+        &[xmin, xmax],
+        &[target, target],
+        dim.thick,
+        scheme.chart.aes.reference.into(),
+    );
+    draw_polyline(&dim, &frame, &line.x, &line.y, 2.5 * dim.scale, scheme.chart.aes.mean.into());
+    unsafe {
+        raylib::EndScissorMode();
+    }
+    draw_ticks(&dim, &frame, font, scheme);
+}
+
+fn draw_probability_plot<F>(
+    state: &State,
+    layers: &[ChartLayer],
+    points_of: F,
+    dim: ChartCanvas,
+    scheme: &SchemeUi,
+) -> Option<ChartFrame>
+where
+    F: Fn(&ChartData) -> &ChartDataLine,
+{
+    if layers.iter().all(|layer| points_of(layer.data).x.is_empty()) {
+        return None;
+    }
+
+    let frame = ChartFrame::square(layers.iter().map(|layer| points_of(layer.data)));
+    let frame = view_frame(state, frame, state.axis_scales());
+    let lhs = raylib::Vector2 {
+        x: frame.screen_x(&dim, frame.xmin),
+        y: frame.screen_y(&dim, frame.ymin),
+    };
+    let rhs = raylib::Vector2 {
+        x: frame.screen_x(&dim, frame.xmax),
+        y: frame.screen_y(&dim, frame.ymax),
+    };
+    unsafe {
+        raylib::DrawLineEx(lhs, rhs, dim.thick, scheme.chart.aes.reference.into());
+    }
+
+    let pct = state.animation.progress().min(1.0);
+    for layer in layers.iter() {
+        let points = points_of(layer.data);
+        let visible = ((points.x.len() as f32) * pct).ceil() as usize;
+        for (x, y) in points.x.iter().zip(points.y.iter()).take(visible) {
+            if !x.is_finite() || !y.is_finite() {
+                continue;
+            }
+
+            let center = raylib::Vector2 { x: frame.screen_x(&dim, *x), y: frame.screen_y(&dim, *y) };
+            draw_point(center, 2.0 * dim.thick, layer.colour);
         }
-        xleft += width + dim.thick * 0.0;
-        fct = next;
     }
+    Some(frame)
 }
-fn draw_line(state: &State, line: &ChartDataLine, dim: ChartCanvas, scheme: &SchemeUi) {
-    let x = line.x.as_slice();
-    let y = line.y.as_slice();
-    if x.len() != y.len() {
-        eprintln!("ERROR: The size of vectors `x` and `y` must be the same: {} - {}", x.len(), y.len());
+
+// Box and violin grow outwards from the median, `reach` being the furthest distance to cover
+fn outward_fraction(value: f32, centre: f32, reach: f32) -> f32 {
+    if reach < crate::math::EPS {
+        return 0.0;
+    }
+    (value - centre).abs() / reach
+}
+
+// Horizontal layout sharing the x axis with the histogram, `centre` is the y of the centre line and `half`
+// the half height of the box, both in data units
+fn draw_box(
+    state: &State,
+    data: &ChartDataBox,
+    dim: ChartCanvas,
+    frame: ChartFrame,
+    (centre, half): (f32, f32),
+    fill_color: raylib::Color,
+    scheme: &SchemeUi,
+) {
+    if data.median.is_nan() {
         return;
     }
 
-    if x.is_empty() {
+    let pct = state.animation.progress();
+    let reach = (data.median - frame.xmin).max(frame.xmax - data.median);
+    let grow = |value: f32| -> f32 {
+        let fraction = outward_fraction(value, data.median, reach);
+        if fraction <= pct {
+            value
+        } else {
+            lerp(pct, 0.0, fraction, data.median, value)
+        }
+    };
+
+    let edge_color = unsafe { raylib::ColorBrightness(fill_color, -0.5) };
+    let ymid = frame.screen_y(&dim, centre);
+    let cap = 0.5 * half;
+
+    let whiskers = [(grow(data.lower), grow(data.q1)), (grow(data.q3), grow(data.upper))];
+    for (lhs, rhs) in whiskers {
+        let xl = frame.screen_x(&dim, lhs);
+        let xr = frame.screen_x(&dim, rhs);
+        unsafe {
+            raylib::DrawLineEx(
+                raylib::Vector2 { x: xl, y: ymid },
+                raylib::Vector2 { x: xr, y: ymid },
+                dim.thick,
+                edge_color,
+            );
+        }
+    }
+
+    for end in [grow(data.lower), grow(data.upper)] {
+        let x = frame.screen_x(&dim, end);
+        unsafe {
+            raylib::DrawLineEx(
+                raylib::Vector2 { x, y: frame.screen_y(&dim, centre + cap) },
+                raylib::Vector2 { x, y: frame.screen_y(&dim, centre - cap) },
+                dim.thick,
+                edge_color,
+            );
+        }
+    }
+
+    let xleft = frame.screen_x(&dim, grow(data.q1));
+    let xright = frame.screen_x(&dim, grow(data.q3));
+    let ytop = frame.screen_y(&dim, centre + half);
+    let ybottom = frame.screen_y(&dim, centre - half);
+    unsafe {
+        let rect = raylib::Rectangle { x: xleft, y: ytop, width: xright - xleft, height: ybottom - ytop };
+        raylib::DrawRectangleV(
+            raylib::Vector2 { x: rect.x, y: rect.y },
+            raylib::Vector2 { x: rect.width, y: rect.height },
+            fill_color,
+        );
+        raylib::DrawRectangleLinesEx(rect, 2.5 * dim.scale, edge_color);
+
+        let x = frame.screen_x(&dim, data.median);
+        raylib::DrawLineEx(
+            raylib::Vector2 { x, y: ytop },
+            raylib::Vector2 { x, y: ybottom },
+            dim.thick,
+            scheme.chart.aes.median.into(),
+        );
+    }
+}
+
+// Several series are stacked, the first one at the top, each in a band of height 2 centred on its own line
+fn band_centre(index: usize, count: usize, band: f32) -> f32 {
+    band * ((count - 1 - index) as f32)
+}
+
+fn draw_box_plot(state: &State, layers: &[ChartLayer], dim: ChartCanvas, scheme: &SchemeUi) -> ChartFrame {
+    let mut frame = ChartFrame {
+        xmin: f32::MAX,
+        xmax: f32::MIN,
+        ymin: -1.0,
+        ymax: 2.0 * (layers.len() as f32) - 1.0,
+        ..Default::default()
+    };
+    for layer in layers.iter() {
+        frame.xmin = frame.xmin.min(layer.data.stats.min);
+        frame.xmax = frame.xmax.max(layer.data.stats.max);
+    }
+    // The y axis only stacks the series
+    let frame = view_frame(state, frame.finish(), (state.xscale, AxisScale::Linear));
+
+    let pct = state.animation.progress();
+    for (index, layer) in layers.iter().enumerate() {
+        let data = &layer.data.boxplot;
+        let centre = band_centre(index, layers.len(), 2.0);
+        draw_box(state, data, dim, frame, (centre, 0.4), layer.fill, scheme);
+
+        let reach = (data.median - frame.xmin).max(frame.xmax - data.median);
+        let ymid = frame.screen_y(&dim, centre);
+        for value in data.outliers.iter() {
+            if outward_fraction(*value, data.median, reach) > pct {
+                continue;
+            }
+
+            let center = raylib::Vector2 { x: frame.screen_x(&dim, *value), y: ymid };
+            draw_point(center, 1.5 * dim.thick, layer.colour);
+        }
+    }
+    frame
+}
+
+fn draw_violin_plot(state: &State, layers: &[ChartLayer], dim: ChartCanvas, scheme: &SchemeUi) -> Option<ChartFrame> {
+    let mut height = 0.0f32;
+    let mut frame = ChartFrame::default();
+    for layer in layers.iter() {
+        let violin = &layer.data.violin;
+        if violin.x.len() < 2 || violin.x.len() != violin.y.len() {
+            continue;
+        }
+        height = violin.y.iter().fold(height, |acc, val| acc.max(*val));
+        frame.xmin = frame.xmin.min(violin.x[0]);
+        frame.xmax = frame.xmax.max(violin.x[violin.x.len() - 1]);
+    }
+
+    if height <= 0.0 || !height.is_finite() {
+        return None;
+    }
+
+    // Densities are mirrored around their centre line, leaving some room above and below the widest point
+    let band = 2.2 * height;
+    frame.ymin = -0.5 * band;
+    frame.ymax = band * (layers.len() as f32) - 0.5 * band;
+    let frame = view_frame(state, frame.finish(), (state.xscale, AxisScale::Linear));
+
+    for (index, layer) in layers.iter().enumerate() {
+        let centre = band_centre(index, layers.len(), band);
+        draw_violin(state, layer, dim, frame, centre);
+
+        // Slim box inside the violin, as is customary, so the quartiles can still be read off
+        draw_box(state, &layer.data.boxplot, dim, frame, (centre, 0.08 * height), layer.fill, scheme);
+    }
+    Some(frame)
+}
+
+fn draw_violin(state: &State, layer: &ChartLayer, dim: ChartCanvas, frame: ChartFrame, centre: f32) {
+    let x = layer.data.violin.x.as_slice();
+    let y = layer.data.violin.y.as_slice();
+    if x.len() < 2 || x.len() != y.len() {
         return;
     }
 
-    let color: raylib::Color = scheme.chart.aes.colour.into();
-    let mut minx = f32::MAX;
-    let mut miny = f32::MAX;
-    let mut maxx = f32::MIN;
-    let mut maxy = f32::MIN;
+    let pct = state.animation.progress();
+    let median = layer.data.boxplot.median;
+    let reach = (median - frame.xmin).max(frame.xmax - median);
 
-    for (xval, yval) in x.iter().zip(y.iter()) {
-        if minx > *xval {
-            minx = *xval;
+    let fill_color = layer.fill;
+    let edge_color = unsafe { raylib::ColorBrightness(fill_color, -0.5) };
+    for idx in 1..x.len() {
+        let mid = 0.5 * (x[idx - 1] + x[idx]);
+        if outward_fraction(mid, median, reach) > pct {
+            continue;
         }
-        if maxx < *xval {
-            maxx = *xval;
+
+        let xleft = frame.screen_x(&dim, x[idx - 1]);
+        let xright = frame.screen_x(&dim, x[idx]);
+        let density = 0.5 * (y[idx - 1] + y[idx]);
+        let ytop = frame.screen_y(&dim, centre + density);
+        let ybottom = frame.screen_y(&dim, centre - density);
+        unsafe {
+            raylib::DrawRectangleV(
+                raylib::Vector2 { x: xleft, y: ytop },
+                raylib::Vector2 { x: xright - xleft, y: ybottom - ytop },
+                fill_color,
+            );
+
+            for sign in [1.0, -1.0] {
+                raylib::DrawLineEx(
+                    raylib::Vector2 { x: xleft, y: frame.screen_y(&dim, centre + sign * y[idx - 1]) },
+                    raylib::Vector2 { x: xright, y: frame.screen_y(&dim, centre + sign * y[idx]) },
+                    dim.thick,
+                    edge_color,
+                );
+            }
         }
-        if miny > *yval {
-            miny = *yval;
+    }
+}
+
+fn draw_markers(state: &State, data: &ChartData, dim: ChartCanvas, frame: ChartFrame, scheme: &SchemeUi) {
+    if !state.show_markers || data.hist.data.is_empty() || (data.hist.max - data.hist.min) < crate::math::EPS {
+        return;
+    }
+
+    // While streaming the mean is a running one, shown against the true mean it converges to
+    let target = if state.stream.is_some() {
+        data.theory.mean
+    } else {
+        f32::NAN
+    };
+    let markers = [
+        (data.stats.mean, scheme.chart.aes.mean),
+        (data.stats.median, scheme.chart.aes.median),
+        (target, scheme.chart.aes.reference),
+    ];
+    // Only the sweep reveals positions gradually, otherwise the whole axis is there from the start
+    let pct = match state.animation.active_reveal() {
+        Reveal::Sweep => state.animation.percentage(),
+        Reveal::All | Reveal::Morph => 1.0,
+    };
+    for (value, colour) in markers {
+        if value.is_nan() {
+            continue;
         }
 
-        if maxy < *yval {
-            maxy = *yval;
+        // Only show the marker once the histogram sweep has revealed its position
+        let fraction = (value - data.hist.min) / (data.hist.max - data.hist.min);
+        if fraction > pct {
+            continue;
+        }
+
+        let x = frame.screen_x(&dim, value);
+        unsafe {
+            raylib::DrawLineEx(
+                raylib::Vector2 { x, y: dim.ybgn },
+                raylib::Vector2 { x, y: dim.yend },
+                dim.thick,
+                colour.into(),
+            );
         }
     }
+}
+
+fn draw_histogram(
+    state: &State,
+    hist: &ChartDataHistogram,
+    previous: &ChartDataHistogram,
+    dim: ChartCanvas,
+    frame: ChartFrame,
+    fill_color: raylib::Color,
+) {
+    let data = hist.data.as_slice();
+    if data.is_empty() {
+        return;
+    }
+
+    let scale = hist.density_scale();
+    if !scale.is_finite() {
+        return;
+    }
+
+    let animation = &state.animation;
+    let pct = animation.percentage();
+    let mut reveal = animation.active_reveal();
+    let prev_scale = previous.density_scale();
+    if reveal == Reveal::Morph && (previous.data.len() != data.len() || !prev_scale.is_finite()) {
+        // Nothing to morph from the first time around
+        reveal = Reveal::All;
+    }
+
+    let size = data.len() as f32;
+    let step = hist.bin_width();
+    let prev_step = previous.bin_width();
+    let edge_color = unsafe { raylib::ColorBrightness(fill_color, -0.5) };
+    for (idx, value) in data.iter().enumerate() {
+        let height = (*value as f32) * scale;
+        let (mut xval, mut width) = (hist.min + (idx as f32) * step, step);
+        let height = match reveal {
+            Reveal::Sweep => {
+                // Each bar has its own slot of the animation, eased within it
+                let (bgn, end) = ((idx as f32) / size, ((idx + 1) as f32) / size);
+                if pct < bgn {
+                    break;
+                }
+                animation.easing.apply(lerp(pct, bgn, end, 0.0, 1.0)) * height
+            }
+            Reveal::All => animation.progress() * height,
+            Reveal::Morph => {
+                let t = animation.progress();
+                let prev_x = previous.min + (idx as f32) * prev_step;
+                let prev_height = (previous.data[idx] as f32) * prev_scale;
+                xval = remap(t, 0.0, 1.0, prev_x, xval);
+                width = remap(t, 0.0, 1.0, prev_step, step);
+                remap(t, 0.0, 1.0, prev_height, height)
+            }
+        };
+
+        let xleft = frame.screen_x(&dim, xval);
+        let xright = frame.screen_x(&dim, xval + width);
+        let ytop = frame.screen_y(&dim, height.max(0.0));
+        let ybottom = frame.screen_y(&dim, frame.baseline());
+
+        let pos = raylib::Vector2 { x: xleft, y: ytop };
+        let size = raylib::Vector2 { x: xright - xleft, y: ybottom - ytop };
+        unsafe {
+            let rect = raylib::Rectangle { x: pos.x, y: pos.y, width: size.x, height: size.y };
+            raylib::DrawRectangleV(pos, size, fill_color);
+            raylib::DrawRectangleLinesEx(rect, 2.5 * dim.scale, edge_color);
+        }
+    }
+}
+
+fn draw_line(state: &State, line: &ChartDataLine, dim: ChartCanvas, frame: ChartFrame, color: raylib::Color) {
+    let x = line.x.as_slice();
+    let y = line.y.as_slice();
+    if x.len() != y.len() {
+        eprintln!("ERROR: The size of vectors `x` and `y` must be the same: {} - {}", x.len(), y.len());
+        return;
+    }
+
+    if x.is_empty() {
+        return;
+    }
 
     if x.len() < 2 {
-        let x0 = lerp(x[0], minx, maxx, dim.xbgn, dim.xend);
-        let y0 = lerp(y[0], miny, maxy, dim.yend, dim.ybgn); // Raylib's orientation is top-down
+        let x0 = frame.screen_x(&dim, x[0]);
+        let y0 = frame.screen_y(&dim, y[0]);
         unsafe {
             raylib::DrawPixelV(raylib::Vector2 { x: x0, y: y0 }, color);
         }
@@ -375,40 +1724,28 @@ fn draw_line(state: &State, line: &ChartDataLine, dim: ChartCanvas, scheme: &Sch
     let mut prev = 0;
     let mut next = 1;
 
+    let (pct, height) = match state.animation.active_reveal() {
+        Reveal::Sweep => (state.animation.percentage(), 1.0),
+        Reveal::All => (1.0, state.animation.progress()),
+        Reveal::Morph => (1.0, 1.0),
+    };
     while prev < next && next < x.len() {
         let factor = (next as f32) / (x.len() as f32);
-        let pct = state.animation.percentage();
         if factor > pct {
             break;
         }
 
-        let xprev: f32;
-        let xnext: f32;
-        let yprev: f32;
-        let ynext: f32;
-
-        // The assumption is that if min == max then we are already in the [0.0 0.1] space
-        if (maxx - minx) < crate::math::EPS {
-            xprev = lerp(x[prev], 0.0, 1.0, dim.xbgn, dim.xend);
-            xnext = lerp(x[next], 0.0, 1.0, dim.xbgn, dim.xend);
-        } else {
-            xprev = lerp(x[prev], minx, maxx, dim.xbgn, dim.xend);
-            xnext = lerp(x[next], minx, maxx, dim.xbgn, dim.xend);
-        }
-
-        if (maxy - miny) < crate::math::EPS {
-            yprev = lerp(y[prev], 0.0, 1.0, dim.yend, dim.ybgn); // Raylib's orientation is top-down
-            ynext = lerp(y[next], 0.0, 1.0, dim.yend, dim.ybgn); // Raylib's orientation is top-down
-        } else {
-            yprev = lerp(y[prev], miny, maxy, dim.yend, dim.ybgn); // Raylib's orientation is top-down
-            ynext = lerp(y[next], miny, maxy, dim.yend, dim.ybgn); // Raylib's orientation is top-down
-        }
-
-        let lhs = raylib::Vector2 { x: xprev, y: yprev };
-        let rhs = raylib::Vector2 { x: xnext, y: ynext };
+        let lhs = raylib::Vector2 {
+            x: frame.screen_x(&dim, x[prev]),
+            y: frame.screen_y(&dim, height * y[prev]),
+        };
+        let rhs = raylib::Vector2 {
+            x: frame.screen_x(&dim, x[next]),
+            y: frame.screen_y(&dim, height * y[next]),
+        };
 
         unsafe {
-            raylib::DrawLineEx(lhs, rhs, 4.0, color);
+            raylib::DrawLineEx(lhs, rhs, 4.0 * dim.scale, color);
         }
 
         prev += 1;
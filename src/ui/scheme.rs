@@ -8,6 +8,7 @@ pub mod colour {
     pub const SLATE_GRAY: Clay_Color = Clay_Color { r: 112.0, g: 128.0, b: 144.0, a: 255.0 };
     pub const MID_GRAY: Clay_Color = Clay_Color { r: 188.0, g: 189.0, b: 220.0, a: 255.0 };
     pub const BLACK: Clay_Color = Clay_Color { r: 0.0, g: 0.0, b: 0.0, a: 255.0 };
+    pub const WHITE: Clay_Color = Clay_Color { r: 255.0, g: 255.0, b: 255.0, a: 255.0 };
     pub const RED: Clay_Color = Clay_Color { r: 240.0, g: 50.0, b: 50.0, a: 255.0 };
     pub const YELLOW: Clay_Color = Clay_Color { r: 190.0, g: 190.0, b: 90.0, a: 255.0 };
     pub const SKY_BLUE: Clay_Color = Clay_Color { r: 135.0, g: 206.0, b: 250.0, a: 255.0 };
@@ -46,8 +47,70 @@ pub mod colour {
 
     // pub const CHART_BACKGROUND: Clay_Color = Clay_Color { r: 90.0, g: 90.0, b: 190.0, a: 255.0 };
     pub const CHART_BACKGROUND: Clay_Color = LAVENDER;
+    pub const STATS_BACKGROUND: Clay_Color = Clay_Color { r: 46.0, g: 85.0, b: 117.0, a: 230.0 };
+    pub const STATS_BORDER: Clay_Color = DARK_STEEL_BLUE;
+    pub const STATS_TITLE: Clay_Color = SELECTED;
+    pub const STATS_TEXT: Clay_Color = WHITE;
+
+    pub const KDE: Clay_Color = Clay_Color { r: 44.0, g: 162.0, b: 95.0, a: 255.0 };
+    pub const GOLDENROD: Clay_Color = Clay_Color { r: 218.0, g: 165.0, b: 32.0, a: 255.0 };
+    pub const MEDIUM_PURPLE: Clay_Color = Clay_Color { r: 147.0, g: 112.0, b: 219.0, a: 255.0 };
+    pub const TEAL: Clay_Color = Clay_Color { r: 0.0, g: 128.0, b: 128.0, a: 255.0 };
+
     pub const LINE: raylib::Color = raylib::Color { r: 44, g: 162, b: 95, a: 255 };
     pub const HIST: raylib::Color = raylib::Color { r: 188, g: 189, b: 220, a: 255 };
+
+    pub mod dark {
+        use super::Clay_Color;
+
+        pub const CLEAR: Clay_Color = Clay_Color { r: 18.0, g: 19.0, b: 23.0, a: 255.0 };
+        pub const CANVAS: Clay_Color = Clay_Color { r: 30.0, g: 32.0, b: 38.0, a: 255.0 };
+        pub const PANEL: Clay_Color = Clay_Color { r: 42.0, g: 45.0, b: 53.0, a: 255.0 };
+        pub const CHART: Clay_Color = Clay_Color { r: 24.0, g: 26.0, b: 31.0, a: 255.0 };
+        pub const BORDER: Clay_Color = Clay_Color { r: 78.0, g: 84.0, b: 98.0, a: 255.0 };
+        pub const BUTTON: Clay_Color = Clay_Color { r: 60.0, g: 65.0, b: 78.0, a: 255.0 };
+        pub const MENU: Clay_Color = Clay_Color { r: 52.0, g: 56.0, b: 66.0, a: 255.0 };
+        pub const HOVER: Clay_Color = Clay_Color { r: 84.0, g: 90.0, b: 108.0, a: 255.0 };
+        pub const PRESSED: Clay_Color = Clay_Color { r: 120.0, g: 80.0, b: 150.0, a: 255.0 };
+        pub const STATS: Clay_Color = Clay_Color { r: 20.0, g: 22.0, b: 28.0, a: 230.0 };
+        pub const TEXT: Clay_Color = Clay_Color { r: 230.0, g: 230.0, b: 235.0, a: 255.0 };
+
+        pub const FILL: Clay_Color = Clay_Color { r: 88.0, g: 124.0, b: 178.0, a: 255.0 };
+        pub const LINE: Clay_Color = Clay_Color { r: 255.0, g: 122.0, b: 92.0, a: 255.0 };
+        pub const KDE: Clay_Color = Clay_Color { r: 72.0, g: 200.0, b: 130.0, a: 255.0 };
+        pub const REFERENCE: Clay_Color = Clay_Color { r: 130.0, g: 136.0, b: 152.0, a: 255.0 };
+        pub const MEAN: Clay_Color = Clay_Color { r: 110.0, g: 160.0, b: 255.0, a: 255.0 };
+        pub const MEDIAN: Clay_Color = Clay_Color { r: 255.0, g: 150.0, b: 60.0, a: 255.0 };
+        pub const PURPLE: Clay_Color = Clay_Color { r: 176.0, g: 146.0, b: 240.0, a: 255.0 };
+        pub const TEAL: Clay_Color = Clay_Color { r: 40.0, g: 184.0, b: 184.0, a: 255.0 };
+        pub const HOT: Clay_Color = Clay_Color { r: 250.0, g: 222.0, b: 120.0, a: 255.0 };
+    }
+
+    pub mod contrast {
+        use super::Clay_Color;
+
+        pub const BUTTON: Clay_Color = Clay_Color { r: 40.0, g: 40.0, b: 40.0, a: 255.0 };
+        pub const HOVER: Clay_Color = Clay_Color { r: 0.0, g: 70.0, b: 200.0, a: 255.0 };
+        pub const PRESSED: Clay_Color = Clay_Color { r: 150.0, g: 0.0, b: 150.0, a: 255.0 };
+        pub const GRAY: Clay_Color = Clay_Color { r: 170.0, g: 170.0, b: 170.0, a: 255.0 };
+        pub const YELLOW: Clay_Color = Clay_Color { r: 255.0, g: 255.0, b: 0.0, a: 255.0 };
+        pub const CYAN: Clay_Color = Clay_Color { r: 0.0, g: 255.0, b: 255.0, a: 255.0 };
+        pub const MAGENTA: Clay_Color = Clay_Color { r: 255.0, g: 0.0, b: 255.0, a: 255.0 };
+        pub const GREEN: Clay_Color = Clay_Color { r: 0.0, g: 255.0, b: 0.0, a: 255.0 };
+    }
+
+    // Okabe and Ito, "Color Universal Design"
+    pub mod okabe_ito {
+        use super::Clay_Color;
+
+        pub const ORANGE: Clay_Color = Clay_Color { r: 230.0, g: 159.0, b: 0.0, a: 255.0 };
+        pub const SKY_BLUE: Clay_Color = Clay_Color { r: 86.0, g: 180.0, b: 233.0, a: 255.0 };
+        pub const BLUISH_GREEN: Clay_Color = Clay_Color { r: 0.0, g: 158.0, b: 115.0, a: 255.0 };
+        pub const YELLOW: Clay_Color = Clay_Color { r: 240.0, g: 228.0, b: 66.0, a: 255.0 };
+        pub const BLUE: Clay_Color = Clay_Color { r: 0.0, g: 114.0, b: 178.0, a: 255.0 };
+        pub const VERMILLION: Clay_Color = Clay_Color { r: 213.0, g: 94.0, b: 0.0, a: 255.0 };
+        pub const REDDISH_PURPLE: Clay_Color = Clay_Color { r: 204.0, g: 121.0, b: 167.0, a: 255.0 };
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -73,6 +136,7 @@ pub struct SchemeSidebarMenu {
 pub struct SchemeSidebar {
     pub button: SchemeSidebarButton,
     pub background: Clay_Color,
+    pub text: Clay_Color, // Buttons, menus and their drop down items
     pub line: Clay_Color,
     pub chart_menu: SchemeSidebarMenu,
     pub distr_menu: SchemeSidebarMenu,
@@ -88,6 +152,7 @@ pub struct SchemeHeaderBorder {
 pub struct SchemeHeader {
     pub button: SchemeHeaderButton,
     pub background: Clay_Color,
+    pub text: Clay_Color,
     pub border: SchemeHeaderBorder,
     pub height: f32,
     pub child_gap: u16,
@@ -96,6 +161,8 @@ pub struct SchemeHeader {
 #[derive(Debug, Clone, Copy)]
 pub struct SchemeCanvas {
     pub background: Clay_Color,
+    pub clear: Clay_Color, // Window background, only seen while the layout is not drawn
+    pub focus: Clay_Color, // Ring around the control focused with the keyboard
     pub padding: u16,
     pub child_gap: u16,
 }
@@ -104,6 +171,16 @@ pub struct SchemeCanvas {
 pub struct SchemeChart {
     pub aes: SchemeChartAesthetics,
     pub layout: SchemeChartLayout,
+    pub legend: SchemeChartLegend,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SchemeChartLegend {
+    pub text: Clay_Color,
+    pub hover: Clay_Color,
+    pub title_size: u16,
+    pub font_size: u16,
+    pub swatch: f32,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -124,15 +201,37 @@ pub struct SchemeChartAesthetics {
     pub colour: Clay_Color,
     pub yaxis: Clay_Color,
     pub xaxis: Clay_Color,
+    pub kde: Clay_Color,
+    pub reference: Clay_Color,
+    pub mean: Clay_Color,
+    pub median: Clay_Color,
+    pub series: [Clay_Color; crate::ui::consts::MAX_SERIES - 1],
+    pub heatmap: [Clay_Color; 3], // Low, middle and high counts, interpolated in between
 }
 
 #[derive(Debug, Clone, Copy)]
+pub struct SchemeStats {
+    pub background: Clay_Color,
+    pub border: Clay_Color,
+    pub title: Clay_Color,
+    pub text: Clay_Color,
+    pub font_size: u16,
+    pub padding: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SchemeFont {
     pub base_size: i32,
-    pub glyph_count: i32,
     pub glyph_padding: i32,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct SchemeAnimation {
+    pub duration: std::time::Duration,
+    pub easing: crate::easing::Easing,
+    pub reveal: super::render::Reveal,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct SchemeUi {
     pub canvas: SchemeCanvas,
@@ -140,57 +239,313 @@ pub struct SchemeUi {
     pub content: SchemeContent,
     pub sidebar: SchemeSidebar,
     pub chart: SchemeChart,
-    pub font_config: SchemeFont,
-    pub font_data: &'static [u8],
+    pub stats: SchemeStats,
+    pub animation: SchemeAnimation,
+    pub font_config: SchemeFont, // Shared by every font of the registry
+    pub scale: f32,              // Physical pixels per layout pixel, set every frame from the state
 }
 
-pub const SCHEME: SchemeUi = SchemeUi {
-    sidebar: SchemeSidebar {
-        button: SchemeSidebarButton {
-            default: colour::SIDEBAR_BUTTON,
-            pressed: colour::SIDEBAR_BUTTON_PRESSED,
-            hover: colour::SIDEBAR_BUTTON_HOVER,
+// Colours of a theme, everything else (sizes, font and animation) is shared by all of them
+struct Palette {
+    canvas: Clay_Color,
+    clear: Clay_Color,
+    text: Clay_Color,
+    header: Clay_Color,
+    header_border: Clay_Color,
+    header_button: SchemeHeaderButton,
+    sidebar: Clay_Color,
+    sidebar_line: Clay_Color,
+    sidebar_button: SchemeSidebarButton,
+    menu_button: SchemeSidebarButton,
+    aes: SchemeChartAesthetics,
+    legend_text: Clay_Color,
+    legend_hover: Clay_Color,
+    stats_background: Clay_Color,
+    stats_border: Clay_Color,
+    stats_title: Clay_Color,
+    stats_text: Clay_Color,
+}
+
+const fn themed(palette: Palette) -> SchemeUi {
+    SchemeUi {
+        sidebar: SchemeSidebar {
+            button: palette.sidebar_button,
+            background: palette.sidebar,
+            text: palette.text,
+            line: palette.sidebar_line,
+            chart_menu: SchemeSidebarMenu { button: palette.menu_button },
+            distr_menu: SchemeSidebarMenu { button: palette.menu_button },
         },
-        background: colour::SIDEBAR_BACKGROUND,
-        line: colour::VERY_DARK_STEEL_BLUE,
-        chart_menu: SchemeSidebarMenu {
-            button: SchemeSidebarButton {
-                default: colour::SIDEBAR_MENU_BUTTON,
-                pressed: colour::SIDEBAR_MENU_BUTTON_PRESSED,
-                hover: colour::SIDEBAR_MENU_BUTTON_HOVER,
-            },
+        header: SchemeHeader {
+            button: palette.header_button,
+            background: palette.header,
+            text: palette.text,
+            border: SchemeHeaderBorder { colour: palette.header_border, width: 4 },
+            height: 50.0,
+            child_gap: 16,
         },
-        distr_menu: SchemeSidebarMenu {
-            button: SchemeSidebarButton {
-                default: colour::SIDEBAR_MENU_BUTTON,
-                pressed: colour::SIDEBAR_MENU_BUTTON_PRESSED,
-                hover: colour::SIDEBAR_MENU_BUTTON_HOVER,
+        canvas: SchemeCanvas {
+            background: palette.canvas,
+            clear: palette.clear,
+            focus: palette.aes.reference,
+            padding: 16,
+            child_gap: 16,
+        },
+        chart: SchemeChart {
+            aes: palette.aes,
+            layout: SchemeChartLayout { child_gap: 8, padding: 20 },
+            legend: SchemeChartLegend {
+                text: palette.legend_text,
+                hover: palette.legend_hover,
+                title_size: 28,
+                font_size: 20,
+                swatch: 16.0,
             },
         },
-    },
-    header: SchemeHeader {
-        button: SchemeHeaderButton {
-            default: colour::HEADER_BUTTON,
-            pressed: colour::HEADER_BUTTON_PRESSED,
-            hover: colour::HEADER_BUTTON_HOVER,
+        stats: SchemeStats {
+            background: palette.stats_background,
+            border: palette.stats_border,
+            title: palette.stats_title,
+            text: palette.stats_text,
+            font_size: 20,
+            padding: 12,
         },
-        background: colour::HEADER,
-        border: SchemeHeaderBorder { colour: colour::HEADER_BORDER, width: 4 },
-        height: 50.0,
-        child_gap: 16,
-    },
-    canvas: SchemeCanvas { background: colour::CANVAS, padding: 16, child_gap: 16 },
-    chart: SchemeChart {
-        aes: SchemeChartAesthetics {
-            background: colour::CHART_BACKGROUND,
-            fill: colour::LIGHT_STEEL_BLUE,
-            colour: colour::TOMATO,
-            yaxis: colour::BLACK,
-            xaxis: colour::BLACK,
+        content: SchemeContent { child_gap: 12 },
+        animation: SchemeAnimation {
+            duration: std::time::Duration::from_millis(750),
+            easing: crate::easing::Easing::CubicOut,
+            reveal: super::render::Reveal::Sweep,
         },
-        layout: SchemeChartLayout { child_gap: 8, padding: 20 },
+        font_config: SchemeFont { base_size: 48, glyph_padding: 2 },
+        scale: 1.0,
+    }
+}
+
+const LIGHT_PALETTE: Palette = Palette {
+    canvas: colour::CANVAS,
+    clear: colour::BACKGROUND,
+    text: colour::WHITE,
+    header: colour::HEADER,
+    header_border: colour::HEADER_BORDER,
+    header_button: SchemeHeaderButton {
+        default: colour::HEADER_BUTTON,
+        pressed: colour::HEADER_BUTTON_PRESSED,
+        hover: colour::HEADER_BUTTON_HOVER,
+    },
+    sidebar: colour::SIDEBAR_BACKGROUND,
+    sidebar_line: colour::VERY_DARK_STEEL_BLUE,
+    sidebar_button: SchemeSidebarButton {
+        default: colour::SIDEBAR_BUTTON,
+        pressed: colour::SIDEBAR_BUTTON_PRESSED,
+        hover: colour::SIDEBAR_BUTTON_HOVER,
+    },
+    menu_button: SchemeSidebarButton {
+        default: colour::SIDEBAR_MENU_BUTTON,
+        pressed: colour::SIDEBAR_MENU_BUTTON_PRESSED,
+        hover: colour::SIDEBAR_MENU_BUTTON_HOVER,
     },
-    content: SchemeContent { child_gap: 12 },
-    font_config: SchemeFont { base_size: 48, glyph_count: 95, glyph_padding: 2 },
-    font_data: crate::font::ROBOTO,
+    aes: SchemeChartAesthetics {
+        background: colour::CHART_BACKGROUND,
+        fill: colour::LIGHT_STEEL_BLUE,
+        colour: colour::TOMATO,
+        yaxis: colour::BLACK,
+        xaxis: colour::BLACK,
+        kde: colour::KDE,
+        reference: colour::SLATE_GRAY,
+        mean: colour::ROYAL_BLUE,
+        median: colour::ORANGE_RED,
+        series: [colour::GOLDENROD, colour::MEDIUM_PURPLE, colour::TEAL],
+        heatmap: [
+            colour::LIGHT_STEEL_BLUE,
+            colour::STEEL_BLUE,
+            colour::VERY_DARK_STEEL_BLUE,
+        ],
+    },
+    legend_text: colour::BLACK,
+    legend_hover: colour::LIGHT_STEEL_BLUE,
+    stats_background: colour::STATS_BACKGROUND,
+    stats_border: colour::STATS_BORDER,
+    stats_title: colour::STATS_TITLE,
+    stats_text: colour::STATS_TEXT,
 };
+
+pub const LIGHT: SchemeUi = themed(LIGHT_PALETTE);
+
+pub const DARK: SchemeUi = themed(Palette {
+    canvas: colour::dark::CANVAS,
+    clear: colour::dark::CLEAR,
+    text: colour::dark::TEXT,
+    header: colour::dark::PANEL,
+    header_border: colour::dark::BORDER,
+    header_button: SchemeHeaderButton {
+        default: colour::dark::BUTTON,
+        pressed: colour::dark::PRESSED,
+        hover: colour::dark::HOVER,
+    },
+    sidebar: colour::dark::PANEL,
+    sidebar_line: colour::dark::BORDER,
+    sidebar_button: SchemeSidebarButton {
+        default: colour::dark::BUTTON,
+        pressed: colour::dark::PRESSED,
+        hover: colour::dark::HOVER,
+    },
+    menu_button: SchemeSidebarButton {
+        default: colour::dark::MENU,
+        pressed: colour::dark::PRESSED,
+        hover: colour::dark::HOVER,
+    },
+    aes: SchemeChartAesthetics {
+        background: colour::dark::CHART,
+        fill: colour::dark::FILL,
+        colour: colour::dark::LINE,
+        yaxis: colour::dark::TEXT,
+        xaxis: colour::dark::TEXT,
+        kde: colour::dark::KDE,
+        reference: colour::dark::REFERENCE,
+        mean: colour::dark::MEAN,
+        median: colour::dark::MEDIAN,
+        series: [colour::GOLDENROD, colour::dark::PURPLE, colour::dark::TEAL],
+        heatmap: [colour::dark::BUTTON, colour::dark::FILL, colour::dark::HOT],
+    },
+    legend_text: colour::dark::TEXT,
+    legend_hover: colour::dark::BUTTON,
+    stats_background: colour::dark::STATS,
+    stats_border: colour::dark::BORDER,
+    stats_title: colour::GOLDENROD,
+    stats_text: colour::dark::TEXT,
+});
+
+// White on black, and the data in saturated colours only
+pub const HIGH_CONTRAST: SchemeUi = themed(Palette {
+    canvas: colour::BLACK,
+    clear: colour::BLACK,
+    text: colour::WHITE,
+    header: colour::BLACK,
+    header_border: colour::WHITE,
+    header_button: SchemeHeaderButton {
+        default: colour::contrast::BUTTON,
+        pressed: colour::contrast::PRESSED,
+        hover: colour::contrast::HOVER,
+    },
+    sidebar: colour::BLACK,
+    sidebar_line: colour::WHITE,
+    sidebar_button: SchemeSidebarButton {
+        default: colour::contrast::BUTTON,
+        pressed: colour::contrast::PRESSED,
+        hover: colour::contrast::HOVER,
+    },
+    menu_button: SchemeSidebarButton {
+        default: colour::contrast::BUTTON,
+        pressed: colour::contrast::PRESSED,
+        hover: colour::contrast::HOVER,
+    },
+    aes: SchemeChartAesthetics {
+        background: colour::BLACK,
+        fill: colour::contrast::GRAY,
+        colour: colour::contrast::YELLOW,
+        yaxis: colour::WHITE,
+        xaxis: colour::WHITE,
+        kde: colour::contrast::CYAN,
+        reference: colour::contrast::GRAY,
+        mean: colour::contrast::MAGENTA,
+        median: colour::contrast::GREEN,
+        series: [
+            colour::contrast::CYAN,
+            colour::contrast::MAGENTA,
+            colour::contrast::GREEN,
+        ],
+        heatmap: [
+            colour::contrast::BUTTON,
+            colour::contrast::GRAY,
+            colour::contrast::YELLOW,
+        ],
+    },
+    legend_text: colour::WHITE,
+    legend_hover: colour::contrast::BUTTON,
+    stats_background: colour::BLACK,
+    stats_border: colour::WHITE,
+    stats_title: colour::contrast::YELLOW,
+    stats_text: colour::WHITE,
+});
+
+// The light theme with the data drawn from the Okabe-Ito palette, told apart with any colour vision
+pub const COLOUR_BLIND: SchemeUi = themed(Palette {
+    aes: SchemeChartAesthetics {
+        background: colour::CHART_BACKGROUND,
+        fill: colour::okabe_ito::SKY_BLUE,
+        colour: colour::okabe_ito::VERMILLION,
+        yaxis: colour::BLACK,
+        xaxis: colour::BLACK,
+        kde: colour::okabe_ito::BLUISH_GREEN,
+        reference: colour::SLATE_GRAY,
+        mean: colour::okabe_ito::BLUE,
+        median: colour::okabe_ito::ORANGE,
+        series: [
+            colour::okabe_ito::REDDISH_PURPLE,
+            colour::okabe_ito::YELLOW,
+            colour::BLACK,
+        ],
+        heatmap: [
+            colour::okabe_ito::SKY_BLUE,
+            colour::okabe_ito::BLUE,
+            colour::okabe_ito::VERMILLION,
+        ],
+    },
+    ..LIGHT_PALETTE
+});
+
+pub const SCHEME: SchemeUi = LIGHT; // Default theme, its font and animation settings are those of every theme
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+    ColourBlind,
+    File, // Loaded from the theme file, see `super::theme`
+}
+
+impl Theme {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+            Self::HighContrast => "High contrast",
+            Self::ColourBlind => "Colour-blind",
+            Self::File => "File",
+        }
+    }
+
+    // As written in theme files, `base = "dark"`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "light" => Some(Self::Light),
+            "dark" => Some(Self::Dark),
+            "high-contrast" => Some(Self::HighContrast),
+            "colour-blind" | "color-blind" => Some(Self::ColourBlind),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark => Self::HighContrast,
+            Self::HighContrast => Self::ColourBlind,
+            Self::ColourBlind => Self::File,
+            Self::File => Self::Light,
+        }
+    }
+
+    // `file` is the scheme read from the theme file, the default one stands in until there is one
+    pub fn scheme<'a>(&self, file: Option<&'a SchemeUi>) -> &'a SchemeUi {
+        match self {
+            Self::Light => &LIGHT,
+            Self::Dark => &DARK,
+            Self::HighContrast => &HIGH_CONTRAST,
+            Self::ColourBlind => &COLOUR_BLIND,
+            Self::File => file.unwrap_or(&SCHEME),
+        }
+    }
+}
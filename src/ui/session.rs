@@ -0,0 +1,274 @@
+use super::theme::{expected, float, int, string};
+use super::{consts, raylib, RandomGenerator, State};
+use crate::chart::ChartKind;
+use crate::error::AppError;
+use crate::toml::{Document, Error};
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use rand::SeedableRng;
+
+const SESSION_ENV: &str = "CLAYDASH_SESSION"; // Overrides the path in the configuration directory
+const SESSION_FILE: &str = "session.toml";
+pub const MIN_WINDOW: i32 = 200;
+pub const MAX_WINDOW: i32 = 16384;
+
+// `$CLAYDASH_SESSION`, otherwise `session.toml` in the claydash directory of the user configuration
+pub fn default_path() -> Option<PathBuf> {
+    crate::config::path(SESSION_ENV, SESSION_FILE)
+}
+
+// What is kept from one run to the next. Samples are not stored, they are drawn again from the seed, and
+// anything left out of the file keeps its current value
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Session {
+    pub distribution: Option<&'static str>, // One of `consts::DISTRIBUTIONS`
+    pub params: Option<[f32; 2]>,           // Of the distribution, in the order of `RandomGenerator::describe`
+    pub chart: Option<ChartKind>,
+    pub seed: Option<u64>, // Of the sample on screen
+    pub window_width: Option<i32>,
+    pub window_height: Option<i32>,
+    pub sidebar_width: Option<f32>,
+    pub sidebar_collapsed: Option<bool>,
+}
+
+impl Session {
+    pub fn capture(state: &State) -> Self {
+        Self {
+            distribution: Some(state.generator.name()),
+            params: Some(state.generator.params()),
+            chart: Some(state.chart_kind()),
+            seed: Some(state.generator.seed()),
+            window_width: Some(state.width),
+            window_height: Some(state.height),
+            sidebar_width: state.sidebar_preferred,
+            sidebar_collapsed: state.sidebar_collapsed,
+        }
+    }
+
+    // Draws the sample again, so it is shown as it was when the session was saved
+    pub fn apply(&self, state: &mut State) {
+        if let (Some(width), Some(height)) = (self.window_width, self.window_height) {
+            state.width = width;
+            state.height = height;
+            if unsafe { raylib::IsWindowReady() } {
+                unsafe { raylib::SetWindowSize(width, height) };
+            }
+        }
+        if self.sidebar_width.is_some() {
+            state.sidebar_preferred = self.sidebar_width;
+        }
+        if self.sidebar_collapsed.is_some() {
+            state.sidebar_collapsed = self.sidebar_collapsed;
+        }
+
+        let name = self.distribution.unwrap_or_else(|| state.generator.name());
+        let seed = self.seed.unwrap_or_else(|| state.generator.seed());
+        let generator = match self.params {
+            Some(params) => RandomGenerator::with_params(name, seed, params),
+            None => RandomGenerator::from_name(name, seed),
+        };
+        match generator {
+            Some(generator) => {
+                state.generator = generator;
+                state.dist.title = Some(name);
+            }
+            None => eprintln!("ERROR: Does not know how to handle distribution: '{name}'"),
+        }
+        state.seed = seed;
+        state.seeder = rand::rngs::SmallRng::seed_from_u64(seed);
+
+        state.unclick();
+        state.reset_view();
+        state.create_chart_data();
+        state.select_chart_kind(self.chart.unwrap_or_else(|| state.chart_kind()));
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from("# Written by claydash, the sample is drawn again from the seed\n");
+        if let Some(name) = self.distribution {
+            let _ = writeln!(out, "distribution = \"{}\"", name.to_lowercase());
+        }
+        if let Some([first, second]) = self.params {
+            let _ = writeln!(out, "params = [{first:?}, {second:?}]");
+        }
+        if let Some(kind) = self.chart {
+            let _ = writeln!(out, "chart = \"{}\"", kind.key());
+        }
+        if let Some(seed) = self.seed {
+            // A string, TOML integers stop at 2^63 - 1
+            let _ = writeln!(out, "seed = \"{seed}\"");
+        }
+        if self.window_width.is_some() || self.window_height.is_some() {
+            out.push_str("\n[window]\n");
+        }
+        if let Some(width) = self.window_width {
+            let _ = writeln!(out, "width = {width}");
+        }
+        if let Some(height) = self.window_height {
+            let _ = writeln!(out, "height = {height}");
+        }
+        if self.sidebar_width.is_some() || self.sidebar_collapsed.is_some() {
+            out.push_str("\n[sidebar]\n");
+        }
+        if let Some(width) = self.sidebar_width {
+            let _ = writeln!(out, "width = {}", width.round());
+        }
+        if let Some(collapsed) = self.sidebar_collapsed {
+            let _ = writeln!(out, "collapsed = {collapsed}");
+        }
+        out
+    }
+
+    pub fn parse(src: &str) -> Result<Self, Error> {
+        let document = Document::parse(src)?;
+        let mut session = Self::default();
+        let mut params_at = None; // Checked once the distribution is known, it may come later in the file
+        for entry in &document.entries {
+            match entry.key.as_str() {
+                "distribution" => {
+                    let name = string(entry)?;
+                    let found = consts::DISTRIBUTIONS
+                        .iter()
+                        .copied()
+                        .find(|known| known.eq_ignore_ascii_case(name))
+                        .ok_or_else(|| Error::new(entry.position, format!("unknown distribution `{name}`")))?;
+                    session.distribution = Some(found);
+                }
+                "params" => {
+                    let values = entry.value.as_array().unwrap_or_default();
+                    let params: Vec<f64> = values.iter().filter_map(|value| value.as_float()).collect();
+                    match params[..] {
+                        [first, second] if values.len() == 2 => session.params = Some([first as f32, second as f32]),
+                        _ => return Err(expected(entry, "an array of two numbers")),
+                    }
+                    params_at = Some(entry.position);
+                }
+                "chart" => {
+                    let key = string(entry)?;
+                    let kind = ChartKind::from_key(key)
+                        .ok_or_else(|| Error::new(entry.position, format!("unknown chart `{key}`")))?;
+                    session.chart = Some(kind);
+                }
+                "seed" => {
+                    let text = string(entry)?;
+                    let seed = text.parse::<u64>().map_err(|_| {
+                        Error::new(entry.position, format!("`seed` must be a whole number, found `{text}`"))
+                    })?;
+                    session.seed = Some(seed);
+                }
+                "window.width" => session.window_width = Some(int(entry, MIN_WINDOW, MAX_WINDOW)?),
+                "window.height" => session.window_height = Some(int(entry, MIN_WINDOW, MAX_WINDOW)?),
+                "sidebar.width" => session.sidebar_width = Some(float(entry)?),
+                "sidebar.collapsed" => {
+                    let collapsed = entry.value.as_bool().ok_or_else(|| expected(entry, "a boolean"))?;
+                    session.sidebar_collapsed = Some(collapsed);
+                }
+                key => return Err(Error::new(entry.position, format!("unknown key `{key}`"))),
+            }
+        }
+        if let (Some(params), Some(position)) = (session.params, params_at) {
+            let name = session
+                .distribution
+                .ok_or_else(|| Error::new(position, "`params` needs `distribution`, the parameters depend on it"))?;
+            RandomGenerator::check_params(name, params)
+                .map_err(|problem| Error::new(position, format!("`params`: {problem}")))?;
+        }
+        Ok(session)
+    }
+}
+
+// `None` when there is no such file, e.g. on the first run
+pub fn load(path: &Path) -> Result<Option<Session>, AppError> {
+    let invalid = |position, message| AppError::InvalidSession { path: path.display().to_string(), position, message };
+    let src = match std::fs::read_to_string(path) {
+        Ok(src) => src,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(invalid(None, err.to_string())),
+    };
+    Session::parse(&src)
+        .map(Some)
+        .map_err(|err| invalid(Some(err.position), err.message))
+}
+
+pub fn save(state: &State, path: &Path) -> Result<(), AppError> {
+    let failed = |err: std::io::Error| AppError::SessionNotSaved(path.display().to_string(), err.to_string());
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(failed)?;
+    }
+    std::fs::write(path, Session::capture(state).to_toml()).map_err(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_toml() {
+        let session = Session {
+            distribution: Some(consts::DROP_DOWN_GAMMA),
+            params: Some([3.0, 0.5]),
+            chart: Some(ChartKind::QQ),
+            seed: Some(u64::MAX),
+            window_width: Some(1280),
+            window_height: Some(800),
+            sidebar_width: Some(312.0),
+            sidebar_collapsed: Some(false),
+        };
+        assert_eq!(Session::parse(&session.to_toml()).unwrap(), session);
+        assert_eq!(Session::parse(&Session::default().to_toml()).unwrap(), Session::default());
+    }
+
+    #[test]
+    fn rejects_parameters_the_distribution_cannot_take() {
+        let error_at = |src: &str| {
+            let err = Session::parse(src).unwrap_err();
+            (err.position.line, err.position.column)
+        };
+        assert_eq!(error_at("distribution = \"gamma\"\nparams = [2.5, 1.0]\n"), (2, 10));
+        assert_eq!(error_at("params = [1000, 1]\ndistribution = \"gamma\"\n"), (1, 10));
+        assert_eq!(error_at("distribution = \"uniform\"\nparams = [2.0, 1.0]\n"), (2, 10));
+        assert_eq!(error_at("distribution = \"normal\"\nparams = [0.0, 0.0]\n"), (2, 10));
+        assert_eq!(error_at("distribution = \"normal\"\nparams = [nan, 1.0]\n"), (2, 10));
+        assert_eq!(error_at("params = [0.0, 1.0]\n"), (1, 10));
+
+        let session = Session::parse("distribution = \"gamma\"\nparams = [255, 0.5]\n").unwrap();
+        assert_eq!(session.params, Some([255.0, 0.5]));
+    }
+
+    #[test]
+    fn restores_the_sample_on_screen_after_a_clt_round_trip() {
+        let sample = |state: &State| {
+            state
+                .chart_data
+                .as_ref()
+                .map(|data| data.sample.clone())
+                .unwrap_or_default()
+        };
+
+        let mut state = State { sample_count: 1000, ..State::default() };
+        state.create_chart_data();
+        state.toggle_clt();
+        state.toggle_clt();
+        assert!(state.clt.is_none());
+        let shown = sample(&state);
+        assert_eq!(shown.len(), 1000);
+
+        let session = Session::parse(&Session::capture(&state).to_toml()).unwrap();
+        let mut restored = State { sample_count: 1000, ..State::default() };
+        session.apply(&mut restored);
+        assert_eq!(sample(&restored), shown);
+    }
+
+    #[test]
+    fn rejects_unknown_values() {
+        assert!(Session::parse("distribution = \"cauchy\"").is_err());
+        assert!(Session::parse("chart = \"pie\"").is_err());
+        assert!(Session::parse("params = [1.0]").is_err());
+        assert!(Session::parse("params = [\"a\", 1.0]").is_err());
+        assert!(Session::parse("seed = \"-1\"").is_err());
+        assert!(Session::parse("[window]\nwidth = 10").is_err());
+        assert!(Session::parse("colour = \"red\"").is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use super::scheme::{self, Clay_Color, SchemeUi, Theme};
+use crate::error::AppError;
+use crate::font::{FONT_COUNT, FONT_LABEL, FONT_MONO};
+use crate::toml::{Document, Entry, Error};
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const THEME_ENV: &str = "CLAYDASH_THEME"; // Overrides the path in the configuration directory
+const THEME_FILE: &str = "theme.toml";
+
+// `$CLAYDASH_THEME`, otherwise `theme.toml` in the claydash directory of the user configuration
+pub fn default_path() -> Option<PathBuf> {
+    crate::config::path(THEME_ENV, THEME_FILE)
+}
+
+// What a theme file describes, the scheme and the font files replacing the embedded ones
+#[derive(Debug, Clone)]
+pub struct FileTheme {
+    pub scheme: SchemeUi,
+    pub fonts: [Option<PathBuf>; FONT_COUNT], // Indexed by font id
+}
+
+// A theme file, read again whenever its modification time changes
+#[derive(Debug)]
+pub struct ThemeFile {
+    pub path: PathBuf,
+    modified: Option<SystemTime>,
+    checked: Option<Instant>,
+}
+
+impl ThemeFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, modified: None, checked: None }
+    }
+
+    // `None` while the file is unchanged or missing, checking the disk at most every `POLL_INTERVAL`
+    pub fn poll(&mut self) -> Option<Result<FileTheme, AppError>> {
+        if self.checked.is_some_and(|checked| checked.elapsed() < POLL_INTERVAL) {
+            return None;
+        }
+        self.checked = Some(Instant::now());
+
+        let modified = std::fs::metadata(&self.path).and_then(|meta| meta.modified()).ok();
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(self.load())
+    }
+
+    pub fn load(&self) -> Result<FileTheme, AppError> {
+        let invalid =
+            |position, message| AppError::InvalidTheme { path: self.path.display().to_string(), position, message };
+        let src = std::fs::read_to_string(&self.path).map_err(|err| invalid(None, err.to_string()))?;
+        let mut theme = parse(&src).map_err(|err| invalid(Some(err.position), err.message))?;
+
+        // Font paths are relative to the theme file
+        if let Some(dir) = self.path.parent() {
+            for path in theme.fonts.iter_mut().flatten() {
+                *path = dir.join(&*path);
+            }
+        }
+        Ok(theme)
+    }
+}
+
+// Keys left out keep the value of the `base` theme, light unless stated otherwise
+pub fn parse(src: &str) -> Result<FileTheme, Error> {
+    let document = Document::parse(src)?;
+    let scheme = match document.get("base") {
+        None => *Theme::Light.scheme(None),
+        Some(entry) => {
+            let name = string(entry)?;
+            *Theme::from_name(name)
+                .ok_or_else(|| Error::new(entry.position, format!("unknown base theme `{name}`")))?
+                .scheme(None)
+        }
+    };
+    let mut theme = FileTheme { scheme, fonts: Default::default() };
+    for entry in &document.entries {
+        apply(&mut theme, entry)?;
+    }
+    Ok(theme)
+}
+
+fn apply(theme: &mut FileTheme, entry: &Entry) -> Result<(), Error> {
+    let ui = &mut theme.scheme;
+    match entry.key.as_str() {
+        "base" => {}
+
+        "canvas.background" => ui.canvas.background = colour(entry)?,
+        "canvas.clear" => ui.canvas.clear = colour(entry)?,
+        "canvas.focus" => ui.canvas.focus = colour(entry)?,
+        "canvas.padding" => ui.canvas.padding = size(entry)?,
+        "canvas.child_gap" => ui.canvas.child_gap = size(entry)?,
+        "content.child_gap" => ui.content.child_gap = size(entry)?,
+
+        "header.background" => ui.header.background = colour(entry)?,
+        "header.text" => ui.header.text = colour(entry)?,
+        "header.height" => ui.header.height = float(entry)?,
+        "header.child_gap" => ui.header.child_gap = size(entry)?,
+        "header.border.colour" => ui.header.border.colour = colour(entry)?,
+        "header.border.width" => ui.header.border.width = size(entry)?,
+        "header.button.default" => ui.header.button.default = colour(entry)?,
+        "header.button.pressed" => ui.header.button.pressed = colour(entry)?,
+        "header.button.hover" => ui.header.button.hover = colour(entry)?,
+
+        "sidebar.background" => ui.sidebar.background = colour(entry)?,
+        "sidebar.text" => ui.sidebar.text = colour(entry)?,
+        "sidebar.line" => ui.sidebar.line = colour(entry)?,
+        "sidebar.button.default" => ui.sidebar.button.default = colour(entry)?,
+        "sidebar.button.pressed" => ui.sidebar.button.pressed = colour(entry)?,
+        "sidebar.button.hover" => ui.sidebar.button.hover = colour(entry)?,
+        "sidebar.menu.default" => {
+            ui.sidebar.chart_menu.button.default = colour(entry)?;
+            ui.sidebar.distr_menu.button.default = ui.sidebar.chart_menu.button.default;
+        }
+        "sidebar.menu.pressed" => {
+            ui.sidebar.chart_menu.button.pressed = colour(entry)?;
+            ui.sidebar.distr_menu.button.pressed = ui.sidebar.chart_menu.button.pressed;
+        }
+        "sidebar.menu.hover" => {
+            ui.sidebar.chart_menu.button.hover = colour(entry)?;
+            ui.sidebar.distr_menu.button.hover = ui.sidebar.chart_menu.button.hover;
+        }
+
+        "chart.padding" => ui.chart.layout.padding = size(entry)?,
+        "chart.child_gap" => ui.chart.layout.child_gap = size(entry)?,
+        "chart.background" => ui.chart.aes.background = colour(entry)?,
+        "chart.fill" => ui.chart.aes.fill = colour(entry)?,
+        "chart.colour" => ui.chart.aes.colour = colour(entry)?,
+        "chart.xaxis" => ui.chart.aes.xaxis = colour(entry)?,
+        "chart.yaxis" => ui.chart.aes.yaxis = colour(entry)?,
+        "chart.kde" => ui.chart.aes.kde = colour(entry)?,
+        "chart.reference" => ui.chart.aes.reference = colour(entry)?,
+        "chart.mean" => ui.chart.aes.mean = colour(entry)?,
+        "chart.median" => ui.chart.aes.median = colour(entry)?,
+        "chart.series" => ui.chart.aes.series = colours(entry)?,
+        "chart.heatmap" => ui.chart.aes.heatmap = colours(entry)?,
+
+        "legend.text" => ui.chart.legend.text = colour(entry)?,
+        "legend.hover" => ui.chart.legend.hover = colour(entry)?,
+        "legend.title_size" => ui.chart.legend.title_size = size(entry)?,
+        "legend.font_size" => ui.chart.legend.font_size = size(entry)?,
+        "legend.swatch" => ui.chart.legend.swatch = float(entry)?,
+
+        "stats.background" => ui.stats.background = colour(entry)?,
+        "stats.border" => ui.stats.border = colour(entry)?,
+        "stats.title" => ui.stats.title = colour(entry)?,
+        "stats.text" => ui.stats.text = colour(entry)?,
+        "stats.font_size" => ui.stats.font_size = size(entry)?,
+        "stats.padding" => ui.stats.padding = size(entry)?,
+
+        "font.base_size" => ui.font_config.base_size = int(entry, 8, 256)?,
+        "font.glyph_padding" => ui.font_config.glyph_padding = int(entry, 0, 16)?,
+        "font.label" => theme.fonts[FONT_LABEL as usize] = Some(PathBuf::from(string(entry)?)),
+        "font.mono" => theme.fonts[FONT_MONO as usize] = Some(PathBuf::from(string(entry)?)),
+
+        key => return Err(Error::new(entry.position, format!("unknown key `{key}`"))),
+    }
+    Ok(())
+}
+
+// The readers below are shared with the session file
+pub(super) fn expected(entry: &Entry, what: &str) -> Error {
+    Error::new(
+        entry.position,
+        format!("`{}` must be {what}, found {}", entry.key, entry.value.kind()),
+    )
+}
+
+pub(super) fn string(entry: &Entry) -> Result<&str, Error> {
+    entry.value.as_str().ok_or_else(|| expected(entry, "a string"))
+}
+
+pub(super) fn float(entry: &Entry) -> Result<f32, Error> {
+    match entry.value.as_float() {
+        Some(value) if value.is_finite() && value >= 0.0 => Ok(value as f32),
+        Some(_) => Err(Error::new(entry.position, format!("`{}` must be positive", entry.key))),
+        None => Err(expected(entry, "a number")),
+    }
+}
+
+pub(super) fn int(entry: &Entry, min: i32, max: i32) -> Result<i32, Error> {
+    let value = entry.value.as_integer().ok_or_else(|| expected(entry, "an integer"))?;
+    if value < i64::from(min) || value > i64::from(max) {
+        let message = format!("`{}` must be between {min} and {max}, found {value}", entry.key);
+        return Err(Error::new(entry.position, message));
+    }
+    Ok(value as i32)
+}
+
+fn size(entry: &Entry) -> Result<u16, Error> {
+    int(entry, 0, i32::from(u16::MAX)).map(|value| value as u16)
+}
+
+fn colour(entry: &Entry) -> Result<Clay_Color, Error> {
+    let text = string(entry).map_err(|_| expected(entry, "a colour like \"#4682b4\""))?;
+    parse_colour(text).ok_or_else(|| {
+        let message = format!("invalid colour `{text}`, expected `#rrggbb` or `#rrggbbaa`");
+        Error::new(entry.position, message)
+    })
+}
+
+fn colours<const N: usize>(entry: &Entry) -> Result<[Clay_Color; N], Error> {
+    let values = entry
+        .value
+        .as_array()
+        .ok_or_else(|| expected(entry, "an array of colours"))?;
+    if values.len() != N {
+        let message = format!("`{}` must hold {N} colours, found {}", entry.key, values.len());
+        return Err(Error::new(entry.position, message));
+    }
+    let mut out = [scheme::colour::BLACK; N];
+    for (slot, value) in out.iter_mut().zip(values) {
+        let element = Entry { key: entry.key.clone(), value: value.clone(), position: entry.position };
+        *slot = colour(&element)?;
+    }
+    Ok(out)
+}
+
+pub fn parse_colour(text: &str) -> Option<Clay_Color> {
+    let hex = text.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).map(f32::from).ok();
+    let a = if hex.len() == 8 { channel(6)? } else { 255.0 };
+    Some(Clay_Color { r: channel(0)?, g: channel(2)?, b: channel(4)?, a })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba(colour: Clay_Color) -> [f32; 4] {
+        [colour.r, colour.g, colour.b, colour.a]
+    }
+
+    fn error_at(src: &str) -> (usize, usize) {
+        let err = parse(src).unwrap_err();
+        (err.position.line, err.position.column)
+    }
+
+    #[test]
+    fn reads_colours_with_and_without_alpha() {
+        assert_eq!(parse_colour("#4682b4").map(rgba), Some([70.0, 130.0, 180.0, 255.0]));
+        assert_eq!(parse_colour("#FF000080").map(rgba), Some([255.0, 0.0, 0.0, 128.0]));
+        for text in ["4682b4", "#4682b", "#4682b4f", "#gg82b4", "#4682b4ff00", ""] {
+            assert!(parse_colour(text).is_none(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn applies_keys_over_the_base_theme() {
+        let theme = parse("base = \"dark\"\n[chart]\nmean = \"#102030\"\n\n[font]\nmono = \"mono.ttf\"\n").unwrap();
+        let dark = Theme::from_name("dark").unwrap().scheme(None);
+        assert_eq!(rgba(theme.scheme.chart.aes.mean), [16.0, 32.0, 48.0, 255.0]);
+        assert_eq!(rgba(theme.scheme.chart.aes.median), rgba(dark.chart.aes.median));
+        assert_eq!(theme.fonts[FONT_MONO as usize], Some(PathBuf::from("mono.ttf")));
+        assert_eq!(theme.fonts[FONT_LABEL as usize], None);
+    }
+
+    #[test]
+    fn reports_bad_entries_where_they_are() {
+        assert_eq!(error_at("[chart]\nmean = \"#12345\"\n"), (2, 8));
+        assert_eq!(error_at("[chart]\n\nmean = 3\n"), (3, 8));
+        assert_eq!(error_at("canvas.glow = \"#000000\"\n"), (1, 15));
+        assert_eq!(error_at("base = \"neon\"\n"), (1, 8));
+    }
+
+    #[test]
+    fn colour_arrays_must_have_the_right_length() {
+        let series = r##"chart.series = ["#000000", "#111111", "#222222"]"##;
+        assert_eq!(rgba(parse(series).unwrap().scheme.chart.aes.series[1]), [17.0, 17.0, 17.0, 255.0]);
+
+        let short = r##"chart.heatmap = ["#000000", "#ffffff"]"##;
+        assert!(parse(short)
+            .unwrap_err()
+            .message
+            .contains("must hold 3 colours, found 2"));
+        let long = r##"chart.heatmap = ["#000000", "#777777", "#ffffff", "#ffffff"]"##;
+        assert!(parse(long).is_err());
+        let mixed = r##"chart.heatmap = ["#000000", 7, "#ffffff"]"##;
+        assert!(parse(mixed).is_err());
+    }
+}